@@ -0,0 +1,456 @@
+use bevy::math::DVec2;
+use std::path::PathBuf;
+
+use bsarconf::{report::Constraints, simulation::PointTarget};
+
+pub const USAGE: &str = "\
+Usage:
+  bsarconf [--scenario <path>]
+  bsarconf --help
+  bsarconf compute --scenario <path> [--format json|csv] [constraints]
+  bsarconf simulate --scenario <path> --output <raw data path> [targets] [--sampling-rate <Hz>]
+  bsarconf focus --raw <raw data sidecar> --output <image path> [grid] [--threads <n>] [--dynamic-range <dB>]
+  bsarconf irf --image <image sidecar> --scenario <path> [--target <east>,<north>] [--format json|csv]
+
+Constraints (checked at the centroid of the common Tx/Rx illuminated area):
+  --max-range-resolution <m>      maximum ground range resolution
+  --max-doppler-resolution <m>    maximum ground Doppler resolution
+  --min-bistatic-angle <deg>      minimum bistatic angle
+  --max-bistatic-angle <deg>      maximum bistatic angle
+  --min-common-area <m2>          minimum common illuminated area
+
+Targets (repeatable, at least one):
+  --target <east>,<north>[,<rcs>] point target ground position (m) and radar cross-section (m2)
+  --targets <path>                CSV file of point targets
+
+The raw data is written as complex float32 samples, with a JSON sidecar describing it
+(the raw data path followed by .json). The sampling rate defaults to the scenario one,
+or 1.2 times the chirp bandwidth if unset.
+
+Grid (ground pixels in World frame):
+  --center <east>,<north>         grid center (m), defaults to the centroid of the simulated targets
+  --extent <east>,<north>         grid extent (m), defaults to 64,64
+  --spacing <m>                   pixel spacing, defaults to a quarter of the range sum resolution c/B
+
+The complex image is written as float32 samples with a JSON sidecar, and its magnitude
+as a PNG file next to it (the image path followed by .json and .png). Threads default
+to the available parallelism, the PNG dynamic range to 50 dB.
+
+The impulse response analysis measures the response closest to the target (the image
+peak by default) along the ground range and Doppler gradients of the scenario, and
+reports its -3 dB widths, PSLR and ISLR next to the predicted resolutions.";
+
+/// Report output format of the `compute` subcommand
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Arguments of the interactive application
+#[derive(Debug, Default)]
+pub struct Args {
+    /// Scenario file loaded at startup
+    pub scenario: Option<PathBuf>,
+}
+
+/// Arguments of the `compute` subcommand
+#[derive(Debug)]
+pub struct ComputeArgs {
+    pub scenario: PathBuf,
+    pub format: ReportFormat,
+    pub constraints: Constraints,
+}
+
+/// Arguments of the `simulate` subcommand
+#[derive(Debug)]
+pub struct SimulateArgs {
+    pub scenario: PathBuf,
+    /// Raw data file, its sidecar being written next to it
+    pub output: PathBuf,
+    /// Targets given on the command line
+    pub targets: Vec<PointTarget>,
+    /// Target files
+    pub target_files: Vec<PathBuf>,
+    pub sampling_rate_hz: Option<f64>,
+}
+
+/// Arguments of the `focus` subcommand
+#[derive(Debug)]
+pub struct FocusArgs {
+    /// Raw data sidecar
+    pub raw: PathBuf,
+    /// Image file, its sidecar and PNG rendering being written next to it
+    pub output: PathBuf,
+    pub center_m: Option<DVec2>,
+    pub extent_m: Option<DVec2>,
+    pub spacing_m: Option<f64>,
+    /// Worker threads, 0 for the available parallelism
+    pub threads: usize,
+    pub dynamic_range_db: Option<f64>,
+}
+
+/// Arguments of the `irf` subcommand
+#[derive(Debug)]
+pub struct IrfArgs {
+    /// Image sidecar
+    pub image: PathBuf,
+    /// Scenario of the focused raw data
+    pub scenario: PathBuf,
+    pub target_m: Option<DVec2>,
+    pub format: ReportFormat,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    /// Opens the 3D configurator window
+    Gui(Args),
+    /// Computes the performance report of a scenario without window
+    Compute(ComputeArgs),
+    /// Simulates the raw data of point targets without window
+    Simulate(SimulateArgs),
+    /// Focuses raw data by backprojection without window
+    Focus(FocusArgs),
+    /// Analyzes the impulse response of a focused image without window
+    Irf(IrfArgs),
+    /// Prints the usage
+    Help,
+}
+
+fn value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value after `{}`\n{}", flag, USAGE))
+}
+
+fn number<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<f64, String> {
+    let text = value(args, flag)?;
+    text.parse()
+        .map_err(|_| format!("invalid number `{}` after `{}`", text, flag))
+}
+
+fn report_format<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<ReportFormat, String> {
+    match value(args, flag)?.as_str() {
+        "json" => Ok(ReportFormat::Json),
+        "csv" => Ok(ReportFormat::Csv),
+        other => Err(format!("unknown report format `{}`\n{}", other, USAGE))
+    }
+}
+
+fn parse_compute_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut scenario = None;
+    let mut format = ReportFormat::default();
+    let mut constraints = Constraints::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scenario" => scenario = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--format" => format = report_format(&mut args, &arg)?,
+            "--max-range-resolution" => constraints.max_ground_range_resolution_m = Some(number(&mut args, &arg)?),
+            "--max-doppler-resolution" => constraints.max_ground_doppler_resolution_m = Some(number(&mut args, &arg)?),
+            "--min-bistatic-angle" => constraints.min_bistatic_angle_deg = Some(number(&mut args, &arg)?),
+            "--max-bistatic-angle" => constraints.max_bistatic_angle_deg = Some(number(&mut args, &arg)?),
+            "--min-common-area" => constraints.min_common_area_m2 = Some(number(&mut args, &arg)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Compute(ComputeArgs {
+        scenario: scenario.ok_or_else(|| format!("`compute` requires `--scenario <path>`\n{}", USAGE))?,
+        format,
+        constraints,
+    }))
+}
+
+/// Parses a `<east>,<north>` pair
+fn pair<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<DVec2, String> {
+    let text = value(args, flag)?;
+    match text.split(',').map(|value| value.trim().parse::<f64>()).collect::<Result<Vec<f64>, _>>() {
+        Ok(values) if values.len() == 2 => Ok(DVec2::new(values[0], values[1])),
+        _ => Err(format!("invalid value `{}` after `{}`, expected `<east>,<north>`", text, flag)),
+    }
+}
+
+/// Parses a `<east>,<north>[,<rcs>]` point target
+fn target(text: &str) -> Result<PointTarget, String> {
+    let values = text
+        .split(',')
+        .map(|value| value.trim().parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| format!("invalid target `{}`", text))?;
+    match values[..] {
+        [east, north] => Ok(PointTarget::new(DVec2::new(east, north))),
+        [east, north, rcs_m2] if rcs_m2 >= 0.0 => Ok(PointTarget {
+            position_m: DVec2::new(east, north),
+            rcs_m2,
+        }),
+        _ => Err(format!("invalid target `{}`, expected `<east>,<north>[,<rcs>]`", text)),
+    }
+}
+
+fn parse_simulate_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut scenario = None;
+    let mut output = None;
+    let mut targets = Vec::new();
+    let mut target_files = Vec::new();
+    let mut sampling_rate_hz = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scenario" => scenario = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--output" => output = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--target" => targets.push(target(&value(&mut args, &arg)?)?),
+            "--targets" => target_files.push(PathBuf::from(value(&mut args, &arg)?)),
+            "--sampling-rate" => sampling_rate_hz = Some(number(&mut args, &arg)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    if targets.is_empty() && target_files.is_empty() {
+        return Err(format!("`simulate` requires `--target` or `--targets`\n{}", USAGE));
+    }
+    Ok(Command::Simulate(SimulateArgs {
+        scenario: scenario.ok_or_else(|| format!("`simulate` requires `--scenario <path>`\n{}", USAGE))?,
+        output: output.ok_or_else(|| format!("`simulate` requires `--output <path>`\n{}", USAGE))?,
+        targets,
+        target_files,
+        sampling_rate_hz,
+    }))
+}
+
+fn parse_focus_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut raw = None;
+    let mut output = None;
+    let mut center_m = None;
+    let mut extent_m = None;
+    let mut spacing_m = None;
+    let mut threads = 0;
+    let mut dynamic_range_db = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--raw" => raw = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--output" => output = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--center" => center_m = Some(pair(&mut args, &arg)?),
+            "--extent" => extent_m = Some(pair(&mut args, &arg)?),
+            "--spacing" => spacing_m = Some(number(&mut args, &arg)?),
+            "--threads" => {
+                let text = value(&mut args, &arg)?;
+                threads = text
+                    .parse()
+                    .map_err(|_| format!("invalid thread count `{}` after `{}`", text, arg))?;
+            }
+            "--dynamic-range" => dynamic_range_db = Some(number(&mut args, &arg)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Focus(FocusArgs {
+        raw: raw.ok_or_else(|| format!("`focus` requires `--raw <path>`\n{}", USAGE))?,
+        output: output.ok_or_else(|| format!("`focus` requires `--output <path>`\n{}", USAGE))?,
+        center_m,
+        extent_m,
+        spacing_m,
+        threads,
+        dynamic_range_db,
+    }))
+}
+
+fn parse_irf_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut image = None;
+    let mut scenario = None;
+    let mut target_m = None;
+    let mut format = ReportFormat::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--image" => image = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--scenario" => scenario = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--target" => target_m = Some(pair(&mut args, &arg)?),
+            "--format" => format = report_format(&mut args, &arg)?,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Irf(IrfArgs {
+        image: image.ok_or_else(|| format!("`irf` requires `--image <path>`\n{}", USAGE))?,
+        scenario: scenario.ok_or_else(|| format!("`irf` requires `--scenario <path>`\n{}", USAGE))?,
+        target_m,
+        format,
+    }))
+}
+
+/// Parses the command line arguments (without the program name)
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_some_and(|arg| arg == "compute") {
+        args.next();
+        return parse_compute_args(args);
+    }
+    if args.peek().is_some_and(|arg| arg == "simulate") {
+        args.next();
+        return parse_simulate_args(args);
+    }
+    if args.peek().is_some_and(|arg| arg == "focus") {
+        args.next();
+        return parse_focus_args(args);
+    }
+    if args.peek().is_some_and(|arg| arg == "irf") {
+        args.next();
+        return parse_irf_args(args);
+    }
+
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scenario" => parsed.scenario = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Command::Gui(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn gui_args() {
+        let Ok(Command::Gui(args)) = parse(&[]) else { panic!() };
+        assert_eq!(args.scenario, None);
+        let Ok(Command::Gui(args)) = parse(&["--scenario", "a.toml"]) else { panic!() };
+        assert_eq!(args.scenario, Some(PathBuf::from("a.toml")));
+        assert!(parse(&["--scenario"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn help() {
+        for args in [
+            &["-h"][..],
+            &["--help"],
+            &["compute", "--help"],
+            &["simulate", "-h"],
+            &["focus", "--help"],
+            &["irf", "--scenario", "a.toml", "-h"]
+        ] {
+            assert!(matches!(parse(args), Ok(Command::Help)), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn compute_args() {
+        let Ok(Command::Compute(args)) = parse(&[
+            "compute",
+            "--scenario", "a.toml",
+            "--format", "csv",
+            "--max-range-resolution", "1.5",
+            "--max-doppler-resolution", "2",
+            "--min-bistatic-angle", "10",
+            "--max-bistatic-angle", "60",
+            "--min-common-area", "1e6"
+        ]) else { panic!() };
+        assert_eq!(args.scenario, PathBuf::from("a.toml"));
+        assert_eq!(args.format, ReportFormat::Csv);
+        assert_eq!(args.constraints.max_ground_range_resolution_m, Some(1.5));
+        assert_eq!(args.constraints.max_ground_doppler_resolution_m, Some(2.0));
+        assert_eq!(args.constraints.min_bistatic_angle_deg, Some(10.0));
+        assert_eq!(args.constraints.max_bistatic_angle_deg, Some(60.0));
+        assert_eq!(args.constraints.min_common_area_m2, Some(1e6));
+
+        let Ok(Command::Compute(args)) = parse(&["compute", "--scenario", "a.toml"]) else { panic!() };
+        assert_eq!(args.format, ReportFormat::Json);
+        assert_eq!(args.constraints.min_bistatic_angle_deg, None);
+    }
+
+    #[test]
+    fn compute_errors() {
+        assert!(parse(&["compute"]).is_err());
+        assert!(parse(&["compute", "--scenario", "a.toml", "--format", "xml"]).is_err());
+        assert!(parse(&["compute", "--scenario", "a.toml", "--min-common-area", "large"]).is_err());
+        assert!(parse(&["compute", "--scenario", "a.toml", "--max-range-resolution"]).is_err());
+    }
+
+    #[test]
+    fn simulate_args() {
+        let Ok(Command::Simulate(args)) = parse(&[
+            "simulate",
+            "--scenario", "a.toml",
+            "--output", "raw.bin",
+            "--target", "10,-20",
+            "--target", "0, 5, 3.5",
+            "--targets", "targets.csv",
+            "--sampling-rate", "180e6"
+        ]) else { panic!() };
+        assert_eq!(args.scenario, PathBuf::from("a.toml"));
+        assert_eq!(args.output, PathBuf::from("raw.bin"));
+        assert_eq!(args.targets, vec![
+            PointTarget::new(DVec2::new(10.0, -20.0)),
+            PointTarget { position_m: DVec2::new(0.0, 5.0), rcs_m2: 3.5 }
+        ]);
+        assert_eq!(args.target_files, vec![PathBuf::from("targets.csv")]);
+        assert_eq!(args.sampling_rate_hz, Some(180e6));
+    }
+
+    #[test]
+    fn simulate_errors() {
+        assert!(parse(&["simulate", "--scenario", "a.toml", "--output", "raw.bin"]).is_err());
+        assert!(parse(&["simulate", "--scenario", "a.toml", "--target", "1,2"]).is_err());
+        assert!(parse(&["simulate", "--output", "raw.bin", "--target", "1,2"]).is_err());
+        for target in ["1", "1,2,3,4", "1,2,-3", "a,b"] {
+            assert!(parse(&["simulate", "--scenario", "a.toml", "--output", "raw.bin", "--target", target]).is_err());
+        }
+    }
+
+    #[test]
+    fn focus_args() {
+        let Ok(Command::Focus(args)) = parse(&[
+            "focus",
+            "--raw", "raw.json",
+            "--output", "image.bin",
+            "--center", "30,-20",
+            "--extent", "100, 50",
+            "--spacing", "0.25",
+            "--threads", "3",
+            "--dynamic-range", "40"
+        ]) else { panic!() };
+        assert_eq!(args.raw, PathBuf::from("raw.json"));
+        assert_eq!(args.output, PathBuf::from("image.bin"));
+        assert_eq!(args.center_m, Some(DVec2::new(30.0, -20.0)));
+        assert_eq!(args.extent_m, Some(DVec2::new(100.0, 50.0)));
+        assert_eq!(args.spacing_m, Some(0.25));
+        assert_eq!(args.threads, 3);
+        assert_eq!(args.dynamic_range_db, Some(40.0));
+
+        let Ok(Command::Focus(args)) = parse(&["focus", "--raw", "raw.json", "--output", "image.bin"]) else { panic!() };
+        assert_eq!((args.center_m, args.extent_m, args.spacing_m), (None, None, None));
+        assert_eq!(args.threads, 0);
+    }
+
+    #[test]
+    fn focus_errors() {
+        assert!(parse(&["focus", "--output", "image.bin"]).is_err());
+        assert!(parse(&["focus", "--raw", "raw.json"]).is_err());
+        assert!(parse(&["focus", "--raw", "raw.json", "--output", "image.bin", "--center", "30"]).is_err());
+        assert!(parse(&["focus", "--raw", "raw.json", "--output", "image.bin", "--threads", "-1"]).is_err());
+    }
+
+    #[test]
+    fn irf_args() {
+        let Ok(Command::Irf(args)) = parse(&[
+            "irf",
+            "--image", "image.json",
+            "--scenario", "a.toml",
+            "--target", "30,-20",
+            "--format", "csv"
+        ]) else { panic!() };
+        assert_eq!(args.image, PathBuf::from("image.json"));
+        assert_eq!(args.scenario, PathBuf::from("a.toml"));
+        assert_eq!(args.target_m, Some(DVec2::new(30.0, -20.0)));
+        assert_eq!(args.format, ReportFormat::Csv);
+
+        assert!(parse(&["irf", "--image", "image.json"]).is_err());
+        assert!(parse(&["irf", "--scenario", "a.toml"]).is_err());
+    }
+}
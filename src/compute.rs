@@ -0,0 +1,74 @@
+use bsarconf::{report::PerformanceReport, scenario::Scenario};
+
+use crate::cli::{ComputeArgs, ReportFormat};
+
+/// Exit status when a requested constraint is violated
+pub const EXIT_CONSTRAINT_VIOLATED: i32 = 1;
+/// Exit status when the scenario can't be loaded or the report can't be written
+pub const EXIT_ERROR: i32 = 2;
+
+/// Headless batch mode: loads the scenario, prints its performance report on stdout
+/// and returns the process exit status
+pub fn run(args: &ComputeArgs) -> i32 {
+    let scenario = match Scenario::load(&args.scenario) {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            eprintln!("Can't load scenario {}: {}", args.scenario.display(), err);
+            return EXIT_ERROR;
+        }
+    };
+
+    let mut report = PerformanceReport::new(&scenario);
+    report.violations = args.constraints.check(&report);
+
+    let output = match args.format {
+        ReportFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => json,
+            Err(err) => {
+                eprintln!("Can't serialize report: {}", err);
+                return EXIT_ERROR;
+            }
+        },
+        ReportFormat::Csv => report.to_csv()
+    };
+    println!("{}", output.trim_end());
+
+    for violation in &report.violations {
+        eprintln!(
+            "Constraint violated: {} (value {}, limit {})",
+            violation.constraint, violation.value, violation.limit
+        );
+    }
+    if report.violations.is_empty() { 0 } else { EXIT_CONSTRAINT_VIOLATED }
+}
+
+#[cfg(test)]
+mod tests {
+    use bsarconf::report::Constraints;
+    use std::{env, fs, path::PathBuf};
+
+    use super::*;
+
+    fn args(scenario: PathBuf, constraints: Constraints) -> ComputeArgs {
+        ComputeArgs {
+            scenario,
+            format: ReportFormat::Csv,
+            constraints,
+        }
+    }
+
+    #[test]
+    fn exit_status() {
+        let path = env::temp_dir().join(format!("bsarconf-compute-{}.toml", std::process::id()));
+        Scenario::default().save(&path).unwrap();
+
+        assert_eq!(run(&args(path.clone(), Constraints::default())), 0);
+        let impossible = Constraints {
+            min_bistatic_angle_deg: Some(179.0),
+            ..Default::default()
+        };
+        assert_eq!(run(&args(path.clone(), impossible)), EXIT_CONSTRAINT_VIOLATED);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(run(&args(path, Constraints::default())), EXIT_ERROR);
+    }
+}
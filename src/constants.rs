@@ -1,11 +1,43 @@
 use bevy::{
     color::LinearRgba,
-    math::{Quat, Vec3, Mat3},
+    math::{DMat3, DQuat, DVec3, Quat, Vec3, Mat3},
     pbr::StandardMaterial
 };
 
 use lazy_static::lazy_static;
 
+/// Physical constants
+pub const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+/// Standard gravity, used for the bank angle of coordinated turns
+pub const STANDARD_GRAVITY_M_S2: f64 = 9.806_65;
+/// Boltzmann constant and reference noise temperature of the radar equation
+pub const BOLTZMANN_CONSTANT_J_K: f64 = 1.380_649e-23;
+pub const REFERENCE_NOISE_TEMPERATURE_K: f64 = 290.0;
+
+/// WGS84 ellipsoid constants
+pub const WGS84_SEMI_MAJOR_AXIS_M: f64 = 6_378_137.0;
+pub const WGS84_FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// Mean Earth radius used by the spherical Earth model
+pub const EARTH_MEAN_RADIUS_M: f64 = 6_371_008.8;
+/// Earth gravitational parameter, J2 zonal harmonic and rotation rate (WGS84)
+pub const EARTH_GM_M3_S2: f64 = 3.986_004_418e14;
+pub const EARTH_J2: f64 = 1.082_626_68e-3;
+pub const EARTH_ROTATION_RATE_RAD_S: f64 = 7.292_115e-5;
+
+/// Footprint constants
+/// Footprints are clipped at this ground range from the antenna nadir...
+pub const FOOTPRINT_MAX_GROUND_RANGE_M: f64 = 100_000.0;
+/// ...or at this many times the antenna height above ground if larger (spaceborne antennas)
+pub const FOOTPRINT_RANGE_PER_HEIGHT: f64 = 10.0;
+/// Number of beam edge directions sampled to compute a footprint
+pub const FOOTPRINT_SEGMENTS: usize = 360;
+
+/// Number of polar angles at which the beam contour of an antenna pattern is sampled
+pub const BEAM_CONTOUR_SEGMENTS: usize = 180;
+
+/// Default Rx sampling rate relative to the chirp bandwidth
+pub const DEFAULT_OVERSAMPLING: f64 = 1.2;
+
 lazy_static! {
     /// Material constants
 
@@ -44,4 +76,10 @@ lazy_static!(
         z_axis: -Vec3::Z
     });
 
+    pub static ref ENU_TO_NED_DROT: DQuat = DQuat::from_mat3(&DMat3 { // ENU -> NED rotation (f64)
+        x_axis: DVec3::Y,
+        y_axis: DVec3::X,
+        z_axis: -DVec3::Z
+    });
+
 );
@@ -0,0 +1,64 @@
+use bevy::math::DVec2;
+
+use bsarconf::{
+    constants::SPEED_OF_LIGHT_M_S,
+    focusing::{backproject, FocusedImage, FocusingGrid, DEFAULT_DYNAMIC_RANGE_DB},
+    simulation::RawData
+};
+
+use crate::{cli::FocusArgs, compute::EXIT_ERROR};
+
+/// Default grid extent (East, North)
+const DEFAULT_EXTENT_M: DVec2 = DVec2::splat(64.0);
+/// Default pixels per slant range resolution cell
+const DEFAULT_PIXELS_PER_RESOLUTION: f64 = 4.0;
+
+/// Headless focusing mode: loads the raw data, focuses it onto the requested grid, writes
+/// the complex image with its sidecar and PNG rendering, and returns the process exit status
+pub fn run(args: &FocusArgs) -> i32 {
+    let raw = match RawData::load(&args.raw) {
+        Ok(raw) => raw,
+        Err(err) => {
+            eprintln!("Can't load raw data {}: {}", args.raw.display(), err);
+            return EXIT_ERROR;
+        }
+    };
+
+    let targets = &raw.header.targets;
+    let center_m = args.center_m.unwrap_or_else(|| {
+        targets.iter().map(|target| target.position_m).sum::<DVec2>() / targets.len().max(1) as f64
+    });
+    let spacing_m = args
+        .spacing_m
+        .unwrap_or(SPEED_OF_LIGHT_M_S / (DEFAULT_PIXELS_PER_RESOLUTION * raw.header.bandwidth_hz));
+    let grid = FocusingGrid::centered(center_m, args.extent_m.unwrap_or(DEFAULT_EXTENT_M), spacing_m);
+    let image = match backproject(&raw, &grid, args.threads) {
+        Ok(image) => image,
+        Err(err) => {
+            eprintln!("Can't focus raw data: {}", err);
+            return EXIT_ERROR;
+        }
+    };
+
+    let png_path = FocusedImage::png_path(&args.output);
+    let written = image.save(&args.output).and_then(|()| {
+        image.save_png(&png_path, args.dynamic_range_db.unwrap_or(DEFAULT_DYNAMIC_RANGE_DB))
+    });
+    if let Err(err) = written {
+        eprintln!("Can't write image {}: {}", args.output.display(), err);
+        return EXIT_ERROR;
+    }
+    let (i, j) = image.peak();
+    let peak_m = grid.position_m(i, j);
+    println!(
+        "{} by {} pixels written to {} (sidecar {}, magnitude {}), peak at ({:.2}, {:.2}) m",
+        grid.width,
+        grid.height,
+        args.output.display(),
+        FocusedImage::sidecar_path(&args.output).display(),
+        png_path.display(),
+        peak_m.x,
+        peak_m.y
+    );
+    0
+}
@@ -0,0 +1,273 @@
+//! Time-domain bistatic backprojection: focuses raw data onto a grid of ground points,
+//! from the Tx and Rx antenna phase center positions recorded with the data.
+//!
+//! Each pulse is range-compressed by the chirp matched filter in the frequency domain
+//! and upsampled by zero padding of its spectrum. For each grid point, the compressed
+//! pulses are linearly interpolated at the bistatic delay of the point, compensated
+//! for the carrier phase of this delay and summed. Pulses and image rows are spread
+//! over worker threads.
+
+use bevy::math::{DVec2, DVec3};
+use num_complex::{Complex32, Complex64};
+use rustfft::FftPlanner;
+use std::{
+    f64::consts::{PI, TAU},
+    fmt,
+    io,
+    thread
+};
+
+use crate::{
+    constants::SPEED_OF_LIGHT_M_S,
+    geometry::Earth,
+    simulation::{RawData, SimulationError}
+};
+
+mod image;
+mod irf;
+pub use image::{FocusedImage, ImageHeader, DEFAULT_DYNAMIC_RANGE_DB};
+pub use irf::{IrfCut, IrfReport};
+
+/// Upsampling factor of the range-compressed pulses
+const RANGE_UPSAMPLING: usize = 8;
+/// Compressed samples kept beyond the delays spanned by the grid, on each side
+const RANGE_MARGIN_SAMPLES: usize = 4;
+
+#[derive(Debug)]
+pub enum FocusingError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Png(png::EncodingError),
+    /// Invalid raw data
+    Raw(SimulationError),
+    /// Inconsistent grid, image or processing parameters
+    Settings(String),
+}
+
+/// Regular grid of ground points, rows going North
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FocusingGrid {
+    /// Position (East, North) of the first (South-West) grid point
+    pub origin_m: DVec2,
+    /// Distance between neighbouring grid points along East and North
+    pub spacing_m: f64,
+    /// Number of grid points along East and North
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Upsampled range-compressed samples of a pulse, over the delays spanned by the grid
+struct RangeLine {
+    /// Delay of the first sample after the pulse transmission
+    first_delay_s: f64,
+    samples: Vec<Complex32>,
+}
+
+impl fmt::Display for FocusingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Json(err) => write!(f, "invalid JSON: {}", err),
+            Self::Png(err) => write!(f, "PNG encoding error: {}", err),
+            Self::Raw(err) => write!(f, "raw data: {}", err),
+            Self::Settings(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for FocusingError {}
+
+impl From<io::Error> for FocusingError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for FocusingError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<png::EncodingError> for FocusingError {
+    fn from(err: png::EncodingError) -> Self {
+        Self::Png(err)
+    }
+}
+
+impl From<SimulationError> for FocusingError {
+    fn from(err: SimulationError) -> Self {
+        Self::Raw(err)
+    }
+}
+
+impl FocusingGrid {
+    /// Grid of extent `extent_m` (East, North) centered on `center_m`
+    pub fn centered(center_m: DVec2, extent_m: DVec2, spacing_m: f64) -> Self {
+        let cells = (extent_m / spacing_m).round().max(DVec2::ONE);
+        Self {
+            origin_m: center_m - 0.5 * cells * spacing_m,
+            spacing_m,
+            width: cells.x as usize + 1,
+            height: cells.y as usize + 1,
+        }
+    }
+
+    /// Position (East, North) of the grid point of column `i` and row `j`
+    #[inline]
+    pub fn position_m(&self, i: usize, j: usize) -> DVec2 {
+        self.origin_m + DVec2::new(i as f64, j as f64) * self.spacing_m
+    }
+
+    pub fn center_m(&self) -> DVec2 {
+        0.5 * (self.origin_m + self.position_m(self.width - 1, self.height - 1))
+    }
+
+    /// Length of the grid diagonal
+    pub fn diagonal_m(&self) -> f64 {
+        self.position_m(self.width - 1, self.height - 1).distance(self.origin_m)
+    }
+}
+
+impl RangeLine {
+    /// Sample at `delay_s`, linearly interpolated, zero out of the line
+    #[inline]
+    fn sample_at(&self, delay_s: f64, sampling_rate_hz: f64) -> Complex64 {
+        let x = (delay_s - self.first_delay_s) * sampling_rate_hz;
+        let k = x.floor();
+        if k < 0.0 || k as usize + 1 >= self.samples.len() {
+            return Complex64::ZERO;
+        }
+        let (a, b) = (self.samples[k as usize], self.samples[k as usize + 1]);
+        let f = (x - k) as f32;
+        let value = a + (b - a) * f;
+        Complex64::new(value.re as f64, value.im as f64)
+    }
+}
+
+/// Worker threads count: `threads`, or the available parallelism if 0
+fn thread_count(threads: usize) -> usize {
+    match threads {
+        0 => thread::available_parallelism().map_or(1, |count| count.get()),
+        n => n,
+    }
+}
+
+/// Range-compresses and upsamples each pulse of `raw`, keeping the samples whose delays
+/// are within `max_offset_s` of the delay of `center_m` at this pulse
+fn range_compress(raw: &RawData, center_m: DVec3, max_offset_s: f64, threads: usize) -> Vec<RangeLine> {
+    let header = &raw.header;
+    let chirp_samples = (header.pulse_duration_s * header.sampling_rate_hz).round() as usize;
+    // Linear (not circular) correlation of the pulse samples with the chirp
+    let n = (header.samples_per_pulse + chirp_samples).next_power_of_two();
+    let upsampled_n = n * RANGE_UPSAMPLING;
+    let upsampled_rate_hz = header.sampling_rate_hz * RANGE_UPSAMPLING as f64;
+
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(n);
+    let ifft = planner.plan_fft_inverse(upsampled_n);
+    let mut reference = vec![Complex64::ZERO; n];
+    for (i, sample) in reference.iter_mut().enumerate().take(chirp_samples) {
+        let u = i as f64 / header.sampling_rate_hz - 0.5 * header.pulse_duration_s;
+        *sample = Complex64::cis(PI * header.chirp_rate_hz_s * u * u);
+    }
+    fft.process(&mut reference);
+    // Matched filter, including the inverse transform scaling
+    let filter: Vec<Complex64> = reference.iter().map(|value| value.conj() / n as f64).collect();
+
+    let compress = |pulse: usize| -> RangeLine {
+        let center_delay_s = (center_m.distance(header.tx_positions_m[pulse])
+            + center_m.distance(header.rx_positions_m[pulse])) / SPEED_OF_LIGHT_M_S;
+        let to_index = |delay_s: f64| (delay_s - header.window_start_s) * upsampled_rate_hz;
+        let first = (to_index(center_delay_s - max_offset_s).floor() as i64 - RANGE_MARGIN_SAMPLES as i64).max(0) as usize;
+        let end = (to_index(center_delay_s + max_offset_s).ceil() as usize + RANGE_MARGIN_SAMPLES)
+            .min(header.samples_per_pulse * RANGE_UPSAMPLING);
+
+        let mut spectrum = vec![Complex64::ZERO; n];
+        for (value, sample) in spectrum.iter_mut().zip(raw.pulse(pulse)) {
+            *value = Complex64::new(sample.re as f64, sample.im as f64);
+        }
+        fft.process(&mut spectrum);
+        // Zero padding between the positive and negative frequencies
+        let mut upsampled = vec![Complex64::ZERO; upsampled_n];
+        for (k, (value, filter)) in spectrum.iter().zip(&filter).enumerate() {
+            let index = if k < n / 2 { k } else { k + upsampled_n - n };
+            upsampled[index] = value * filter;
+        }
+        ifft.process(&mut upsampled);
+        RangeLine {
+            first_delay_s: header.window_start_s + first as f64 / upsampled_rate_hz,
+            samples: upsampled
+                .get(first..end)
+                .unwrap_or_default()
+                .iter()
+                .map(|value| Complex32::new(value.re as f32, value.im as f32))
+                .collect(),
+        }
+    };
+
+    let chunk = header.pulses.div_ceil(thread_count(threads)).max(1);
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..header.pulses)
+            .step_by(chunk)
+            .map(|start| {
+                let compress = &compress;
+                scope.spawn(move || (start..(start + chunk).min(header.pulses)).map(compress).collect::<Vec<_>>())
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Range compression thread panicked"))
+            .collect()
+    })
+}
+
+/// Focuses `raw` onto the ground points of `grid` by backprojection, with `threads`
+/// worker threads (the available parallelism if 0). Pixels are the coherent sums of
+/// the range-compressed pulses over the acquisition.
+pub fn backproject(raw: &RawData, grid: &FocusingGrid, threads: usize) -> Result<FocusedImage, FocusingError> {
+    let header = &raw.header;
+    if !(grid.width > 0 && grid.height > 0 && grid.spacing_m > 0.0) {
+        return Err(FocusingError::Settings("the focusing grid is empty".to_string()));
+    }
+    if !(header.pulse_duration_s > 0.0 && raw.samples.len() == header.pulses * header.samples_per_pulse) {
+        return Err(FocusingError::Settings("inconsistent raw data".to_string()));
+    }
+    let earth = Earth::new(header.earth, &header.origin);
+    let center_m = earth.ground_point(grid.center_m());
+    // Bistatic delays of the grid points differ from the center one by at most the
+    // grid diagonal (twice the largest distance to the center) over the speed of light
+    let lines = range_compress(raw, center_m, grid.diagonal_m() / SPEED_OF_LIGHT_M_S, threads);
+
+    let upsampled_rate_hz = header.sampling_rate_hz * RANGE_UPSAMPLING as f64;
+    let focus_row = |j: usize, row: &mut [Complex32]| {
+        for (i, pixel) in row.iter_mut().enumerate() {
+            let point_m = earth.ground_point(grid.position_m(i, j));
+            let mut sum = Complex64::ZERO;
+            for (line, (tx_m, rx_m)) in lines.iter().zip(header.tx_positions_m.iter().zip(&header.rx_positions_m)) {
+                let delay_s = (point_m.distance(*tx_m) + point_m.distance(*rx_m)) / SPEED_OF_LIGHT_M_S;
+                sum += line.sample_at(delay_s, upsampled_rate_hz)
+                    * Complex64::cis((TAU * header.carrier_frequency_hz * delay_s).rem_euclid(TAU));
+            }
+            *pixel = Complex32::new(sum.re as f32, sum.im as f32);
+        }
+    };
+
+    let mut pixels = vec![Complex32::ZERO; grid.width * grid.height];
+    let rows_per_thread = grid.height.div_ceil(thread_count(threads)).max(1);
+    thread::scope(|scope| {
+        for (chunk_index, chunk) in pixels.chunks_mut(rows_per_thread * grid.width).enumerate() {
+            let focus_row = &focus_row;
+            scope.spawn(move || {
+                for (k, row) in chunk.chunks_mut(grid.width).enumerate() {
+                    focus_row(chunk_index * rows_per_thread + k, row);
+                }
+            });
+        }
+    });
+
+    Ok(FocusedImage {
+        header: ImageHeader::new(grid, header),
+        pixels,
+    })
+}
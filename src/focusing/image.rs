@@ -0,0 +1,220 @@
+use bevy::math::DVec2;
+use num_complex::Complex32;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf}
+};
+
+use crate::{
+    simulation::{companion_path, read_samples, write_samples, PointTarget, RawDataHeader, RAW_DATA_FORMAT, SAMPLE_BYTES},
+    state::{EarthModel, SceneOrigin}
+};
+
+use super::{FocusingError, FocusingGrid};
+
+/// Default magnitude range mapped to the gray levels of the PNG rendering
+pub const DEFAULT_DYNAMIC_RANGE_DB: f64 = 50.0;
+
+/// Focused image description, stored as a JSON sidecar of the binary image file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ImageHeader {
+    /// Pixel format, `RAW_DATA_FORMAT`
+    pub format: String,
+    /// Binary image file, relative to the sidecar directory
+    pub data_file: PathBuf,
+    /// Pixels along East and North, stored row after row from the South
+    pub width: usize,
+    pub height: usize,
+    /// Ground position (East, North) of the first (South-West) pixel, in World frame
+    pub origin_m: DVec2,
+    /// Distance between neighbouring pixels along East and North
+    pub spacing_m: f64,
+    /// World frame origin and ground surface model of the focused raw data
+    pub origin: SceneOrigin,
+    pub earth: EarthModel,
+    /// Signal parameters and pulses count of the focused raw data
+    pub carrier_frequency_hz: f64,
+    pub bandwidth_hz: f64,
+    pub prf_hz: f64,
+    pub pulses: usize,
+    /// Point targets of the focused raw data
+    pub targets: Vec<PointTarget>,
+}
+
+/// Complex focused image: `height` rows of `width` pixels
+#[derive(Clone, Debug)]
+pub struct FocusedImage {
+    pub header: ImageHeader,
+    pub pixels: Vec<Complex32>,
+}
+
+impl ImageHeader {
+    /// Header of the image of `raw` focused onto `grid`
+    pub fn new(grid: &FocusingGrid, raw: &RawDataHeader) -> Self {
+        Self {
+            format: RAW_DATA_FORMAT.to_string(),
+            data_file: Default::default(),
+            width: grid.width,
+            height: grid.height,
+            origin_m: grid.origin_m,
+            spacing_m: grid.spacing_m,
+            origin: raw.origin,
+            earth: raw.earth,
+            carrier_frequency_hz: raw.carrier_frequency_hz,
+            bandwidth_hz: raw.bandwidth_hz,
+            prf_hz: raw.prf_hz,
+            pulses: raw.pulses,
+            targets: raw.targets.clone(),
+        }
+    }
+
+    /// Grid of the pixels
+    pub fn grid(&self) -> FocusingGrid {
+        FocusingGrid {
+            origin_m: self.origin_m,
+            spacing_m: self.spacing_m,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl FocusedImage {
+    /// Pixel of column `i` and row `j`
+    #[inline]
+    pub fn pixel(&self, i: usize, j: usize) -> Complex32 {
+        self.pixels[j * self.header.width + i]
+    }
+
+    /// Column and row of the pixel of largest magnitude
+    pub fn peak(&self) -> (usize, usize) {
+        let index = self
+            .pixels
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.norm_sqr().total_cmp(&b.norm_sqr()))
+            .map_or(0, |(index, _)| index);
+        (index % self.header.width, index / self.header.width)
+    }
+
+    /// Sidecar file of the image file `path`: its full file name followed by `.json`
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        companion_path(path, "json")
+    }
+
+    /// PNG rendering of the image file `path`: its full file name followed by `.png`
+    pub fn png_path(path: &Path) -> PathBuf {
+        companion_path(path, "png")
+    }
+
+    /// Writes the pixels to the image file `path` and the header to its JSON sidecar
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), FocusingError> {
+        let path = path.as_ref();
+        write_samples(path, &self.pixels)?;
+
+        let header = ImageHeader {
+            data_file: path.file_name().map(PathBuf::from).unwrap_or_default(),
+            ..self.header.clone()
+        };
+        fs::write(Self::sidecar_path(path), serde_json::to_string_pretty(&header)?)?;
+        Ok(())
+    }
+
+    /// Reads an image from its JSON sidecar `path` and the image file it refers to
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FocusingError> {
+        let path = path.as_ref();
+        let header: ImageHeader = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if header.format != RAW_DATA_FORMAT {
+            return Err(FocusingError::Settings(format!("unsupported image format `{}`", header.format)));
+        }
+        let data_path = path.parent().unwrap_or(Path::new("")).join(&header.data_file);
+        if fs::metadata(&data_path)?.len() != (header.width * header.height * SAMPLE_BYTES) as u64 {
+            return Err(FocusingError::Settings(format!(
+                "the image file does not hold {} by {} pixels",
+                header.width,
+                header.height
+            )));
+        }
+        let pixels = read_samples(&data_path)?;
+        Ok(Self { header, pixels })
+    }
+
+    /// Writes the pixel magnitudes to the PNG file `path`, North up, as 8-bit gray levels
+    /// spanning `dynamic_range_db` below the image peak
+    pub fn save_png<P: AsRef<Path>>(&self, path: P, dynamic_range_db: f64) -> Result<(), FocusingError> {
+        if !(dynamic_range_db > 0.0 && dynamic_range_db.is_finite()) {
+            return Err(FocusingError::Settings("the dynamic range must be positive".to_string()));
+        }
+        let (width, height) = (self.header.width, self.header.height);
+        let peak_db = self.pixels.iter().map(|pixel| pixel.norm_sqr()).fold(0.0, f32::max).log10() as f64 * 10.0;
+        let mut levels = Vec::with_capacity(width * height);
+        for row in self.pixels.chunks(width).rev() {
+            levels.extend(row.iter().map(|pixel| {
+                let db = 10.0 * (pixel.norm_sqr() as f64).log10() - peak_db;
+                (255.0 * (1.0 + db / dynamic_range_db)).clamp(0.0, 255.0).round() as u8
+            }));
+        }
+
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&levels)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::focusing::FocusingGrid;
+
+    use super::*;
+
+    #[test]
+    fn sidecar_and_png_do_not_overwrite_pixels() {
+        let grid = FocusingGrid::centered(DVec2::new(100.0, -50.0), DVec2::splat(4.0), 1.0);
+        let raw = RawDataHeader {
+            format: RAW_DATA_FORMAT.to_string(),
+            data_file: Default::default(),
+            pulses: 1,
+            samples_per_pulse: 1,
+            sampling_rate_hz: 60e6,
+            prf_hz: 500.0,
+            carrier_frequency_hz: 9.65e9,
+            bandwidth_hz: 50e6,
+            pulse_duration_s: 2e-6,
+            chirp_rate_hz_s: 2.5e13,
+            window_start_s: 0.0,
+            origin: SceneOrigin::default(),
+            earth: EarthModel::Flat,
+            pulse_times_s: vec![0.0],
+            tx_positions_m: vec![Default::default()],
+            rx_positions_m: vec![Default::default()],
+            targets: Vec::new(),
+        };
+        let image = FocusedImage {
+            header: ImageHeader::new(&grid, &raw),
+            pixels: (0..grid.width * grid.height).map(|k| Complex32::new(k as f32, -(k as f32))).collect(),
+        };
+
+        for name in ["json", "png"] {
+            let path = env::temp_dir().join(format!("bsarconf-image-{}.{}", std::process::id(), name));
+            let (sidecar, png) = (FocusedImage::sidecar_path(&path), FocusedImage::png_path(&path));
+            assert_eq!(sidecar, PathBuf::from(format!("{}.json", path.display())));
+            assert_eq!(png, PathBuf::from(format!("{}.png", path.display())));
+
+            image.save(&path).unwrap();
+            image.save_png(&png, DEFAULT_DYNAMIC_RANGE_DB).unwrap();
+            let loaded = FocusedImage::load(&sidecar);
+            for file in [&path, &sidecar, &png] {
+                fs::remove_file(file).unwrap();
+            }
+            let loaded = loaded.unwrap();
+            assert_eq!(loaded.header.data_file, PathBuf::from(path.file_name().unwrap()));
+            assert_eq!(loaded.pixels, image.pixels);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use bevy::math::DVec2;
+use num_complex::Complex64;
+use rustfft::FftPlanner;
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::{
+    geometry::{BistaticGeometry, Earth, PlatformGeometry},
+    scenario::Scenario
+};
+
+use super::{FocusedImage, FocusingError};
+
+/// Chip extent in predicted resolutions (of the coarsest direction)
+const CHIP_RESOLUTIONS: f64 = 8.0;
+/// Chip size bounds, in pixels (powers of two)
+const MIN_CHIP_PIXELS: usize = 16;
+const MAX_CHIP_PIXELS: usize = 128;
+/// Size of the upsampled chip, in pixels
+const UPSAMPLED_PIXELS: usize = 512;
+/// Power ratio of the resolution width (-3 dB)
+const HALF_POWER: f64 = 0.5;
+/// Relative tolerance on the image and scenario signal parameters
+const SIGNAL_TOLERANCE: f64 = 1e-9;
+
+/// Impulse response measured along a ground direction through its peak.
+///
+/// Cuts follow the range and Doppler ground gradients, so that the measured width
+/// matches the predicted resolution only when the gradients are orthogonal: otherwise
+/// a cut also crosses the other response, which narrows it.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct IrfCut {
+    /// Cut direction, from East (counterclockwise)
+    pub direction_deg: f64,
+    /// -3 dB width of the main lobe, `None` if the power does not drop below -3 dB
+    /// within the chip
+    pub resolution_m: Option<f64>,
+    /// Predicted ground resolution along this direction, from the geometry engine. The
+    /// -3 dB width of an unweighted (sinc) response is 0.886 times this resolution.
+    pub predicted_resolution_m: f64,
+    /// Peak to sidelobe ratio: highest sidelobe relative to the peak, `None` if the cut
+    /// holds no sidelobe
+    pub pslr_db: Option<f64>,
+    /// Integrated sidelobe ratio: sidelobes energy relative to the main lobe one, along
+    /// the cut
+    pub islr_db: Option<f64>,
+}
+
+/// Impulse response analysis of a point target of a focused image
+#[derive(Serialize, Clone, Debug)]
+pub struct IrfReport {
+    /// Position (East, North) of the upsampled response peak
+    pub peak_m: DVec2,
+    /// Peak power relative to the image peak
+    pub peak_db: f64,
+    /// Chip size in pixels and upsampling factor of the analysis
+    pub chip_pixels: usize,
+    pub upsampling: usize,
+    /// Cuts along the ground range and Doppler gradients
+    pub range: IrfCut,
+    pub doppler: IrfCut,
+    /// Angle between the range and Doppler ground gradients
+    pub gradients_angle_deg: f64,
+}
+
+/// Square chip of an image, upsampled by zero padding of its spectrum
+struct UpsampledChip {
+    /// Position (East, North) of the first upsampled pixel
+    origin_m: DVec2,
+    spacing_m: f64,
+    size: usize,
+    power: Vec<f64>,
+}
+
+impl UpsampledChip {
+    /// `chip_pixels` wide chip of `image` centered on the pixel (`i`, `j`), pixels out of
+    /// the image being null, upsampled by `upsampling`
+    fn new(image: &FocusedImage, (i, j): (usize, usize), chip_pixels: usize, upsampling: usize) -> Self {
+        let header = &image.header;
+        let n = chip_pixels;
+        let (i0, j0) = (i as i64 - (n / 2) as i64, j as i64 - (n / 2) as i64);
+        let mut chip = vec![Complex64::ZERO; n * n];
+        for (k, value) in chip.iter_mut().enumerate() {
+            let (ci, cj) = (i0 + (k % n) as i64, j0 + (k / n) as i64);
+            if (0..header.width as i64).contains(&ci) && (0..header.height as i64).contains(&cj) {
+                let pixel = image.pixel(ci as usize, cj as usize);
+                *value = Complex64::new(pixel.re as f64, pixel.im as f64);
+            }
+        }
+
+        // The response spectrum is centered on the spatial frequency of the carrier
+        // phase: remove the mean linear phase ramp so that it is centered on zero and
+        // not cut by the zero padding
+        let (mut ramp_x, mut ramp_y) = (Complex64::ZERO, Complex64::ZERO);
+        for y in 0..n {
+            for x in 0..n {
+                let value = chip[y * n + x];
+                if x + 1 < n {
+                    ramp_x += chip[y * n + x + 1] * value.conj();
+                }
+                if y + 1 < n {
+                    ramp_y += chip[(y + 1) * n + x] * value.conj();
+                }
+            }
+        }
+        let (phase_x, phase_y) = (ramp_x.arg(), ramp_y.arg());
+        for (k, value) in chip.iter_mut().enumerate() {
+            *value *= Complex64::cis(-phase_x * (k % n) as f64 - phase_y * (k / n) as f64);
+        }
+
+        let m = n * upsampling;
+        let mut planner = FftPlanner::<f64>::new();
+        fft_2d(&mut chip, n, planner.plan_fft_forward(n).as_ref());
+        let spread = |k: usize| if k < n / 2 { k } else { k + m - n };
+        let mut upsampled = vec![Complex64::ZERO; m * m];
+        for (k, value) in chip.iter().enumerate() {
+            upsampled[spread(k / n) * m + spread(k % n)] = value / (n * n) as f64;
+        }
+        fft_2d(&mut upsampled, m, planner.plan_fft_inverse(m).as_ref());
+
+        Self {
+            origin_m: header.origin_m + DVec2::new(i0 as f64, j0 as f64) * header.spacing_m,
+            spacing_m: header.spacing_m / upsampling as f64,
+            size: m,
+            power: upsampled.iter().map(Complex64::norm_sqr).collect(),
+        }
+    }
+
+    /// Index of the upsampled pixel of largest power in the central half of the chip,
+    /// which is centered on the brightest image pixel: responses of neighbouring targets
+    /// reaching the chip borders are ignored
+    fn peak(&self) -> usize {
+        let central = self.size / 4..3 * self.size / 4;
+        self.power
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| central.contains(&(index % self.size)) && central.contains(&(index / self.size)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn position_m(&self, index: usize) -> DVec2 {
+        self.origin_m + DVec2::new((index % self.size) as f64, (index / self.size) as f64) * self.spacing_m
+    }
+
+    /// Power at `position_m`, bilinearly interpolated, `None` out of the chip
+    fn power_at(&self, position_m: DVec2) -> Option<f64> {
+        let p = (position_m - self.origin_m) / self.spacing_m;
+        let (x, y) = (p.x.floor(), p.y.floor());
+        if x < 0.0 || y < 0.0 || x as usize + 1 >= self.size || y as usize + 1 >= self.size {
+            return None;
+        }
+        let (fx, fy) = (p.x - x, p.y - y);
+        let k = y as usize * self.size + x as usize;
+        let row = |k: usize| self.power[k] * (1.0 - fx) + self.power[k + 1] * fx;
+        Some(row(k) * (1.0 - fy) + row(k + self.size) * fy)
+    }
+
+    /// Cut through `peak_m` along the unit vector `direction`, sampled at the upsampled
+    /// spacing, and index of the peak in the cut
+    fn cut(&self, peak_m: DVec2, direction: DVec2) -> (Vec<f64>, usize) {
+        let step = |k: i64| self.power_at(peak_m + k as f64 * self.spacing_m * direction);
+        let backward: Vec<f64> = (1..).map_while(|k| step(-k)).collect();
+        let forward = (0..).map_while(step);
+        let peak = backward.len();
+        (backward.into_iter().rev().chain(forward).collect(), peak)
+    }
+}
+
+/// In-place 2D transform of the `n` by `n` row-major `data`
+fn fft_2d(data: &mut [Complex64], n: usize, fft: &dyn rustfft::Fft<f64>) {
+    for row in data.chunks_mut(n) {
+        fft.process(row);
+    }
+    let mut column = vec![Complex64::ZERO; n];
+    for x in 0..n {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = data[y * n + x];
+        }
+        fft.process(&mut column);
+        for (y, value) in column.iter().enumerate() {
+            data[y * n + x] = *value;
+        }
+    }
+}
+
+impl IrfCut {
+    /// Measures the cut `power` of peak `peak`, sampled every `spacing_m` along `direction`
+    fn new(power: &[f64], peak: usize, spacing_m: f64, direction: DVec2, predicted_resolution_m: f64) -> Self {
+        let peak_power = power[peak];
+        // Distance from the peak to the half power crossing on one side
+        let half_width = |side: &mut dyn Iterator<Item = f64>| {
+            let mut previous = peak_power;
+            for (k, value) in side.enumerate() {
+                if value < HALF_POWER * peak_power {
+                    let f = (previous - HALF_POWER * peak_power) / (previous - value);
+                    return Some((k as f64 + f) * spacing_m);
+                }
+                previous = value;
+            }
+            None
+        };
+        let resolution_m = half_width(&mut power[peak + 1..].iter().copied())
+            .zip(half_width(&mut power[..peak].iter().rev().copied()))
+            .map(|(after, before)| after + before);
+
+        // Main lobe between the first minimum on each side of the peak
+        let first_null = |side: &mut dyn Iterator<Item = usize>| {
+            let mut previous = peak;
+            for k in side {
+                if power[k] > power[previous] {
+                    return Some(previous);
+                }
+                previous = k;
+            }
+            None
+        };
+        let lobe = first_null(&mut (0..peak).rev()).zip(first_null(&mut (peak + 1..power.len())));
+        let (pslr_db, islr_db) = match lobe {
+            Some((start, end)) => {
+                let sidelobes = power[..start].iter().chain(&power[end + 1..]);
+                let main_lobe_energy: f64 = power[start..=end].iter().sum();
+                (
+                    Some(10.0 * (sidelobes.clone().fold(0.0, |max: f64, &value| max.max(value)) / peak_power).log10()),
+                    Some(10.0 * (sidelobes.sum::<f64>() / main_lobe_energy).log10()),
+                )
+            }
+            None => (None, None),
+        };
+
+        Self {
+            direction_deg: direction.y.atan2(direction.x).to_degrees(),
+            resolution_m,
+            predicted_resolution_m,
+            pslr_db,
+            islr_db,
+        }
+    }
+}
+
+/// Bistatic geometry of `scenario` `time_s` after the start of the acquisition
+fn geometry_at(scenario: &Scenario, time_s: f64) -> Result<BistaticGeometry, FocusingError> {
+    let frame = scenario.origin.frame();
+    let mut geometry = BistaticGeometry::new(
+        PlatformGeometry::new(&scenario.tx.carrier, &scenario.tx.antenna, &scenario.tx.beam, &scenario.tx.pattern),
+        PlatformGeometry::new(&scenario.rx.carrier, &scenario.rx.antenna, &scenario.rx.beam, &scenario.rx.pattern),
+        Earth::new(scenario.earth, &scenario.origin)
+    );
+    for (platform, platform_scenario) in [(&mut geometry.tx, &scenario.tx), (&mut geometry.rx, &scenario.rx)] {
+        let carrier = platform_scenario
+            .carrier_state_at(&frame, time_s)
+            .ok_or_else(|| FocusingError::Settings(format!("no carrier state at {} s", time_s)))?;
+        platform.set_carrier(&carrier, &platform_scenario.antenna);
+    }
+    Ok(geometry)
+}
+
+impl IrfReport {
+    /// Analyzes the response of `image` whose peak is the closest to `target_m` (the
+    /// image peak if `None`), focused from the raw data of `scenario`. The response is
+    /// cut along the ground range and Doppler gradients at the middle of the acquisition,
+    /// and its resolutions are compared to the ones predicted by the geometry engine.
+    pub fn new(image: &FocusedImage, scenario: &Scenario, target_m: Option<DVec2>) -> Result<Self, FocusingError> {
+        let header = &image.header;
+        if image.pixels.is_empty() {
+            return Err(FocusingError::Settings("the image is empty".to_string()));
+        }
+        // Predictions are made with the scenario signal, which must be the focused one
+        let signal = &scenario.signal;
+        for (name, image_hz, scenario_hz) in [
+            ("bandwidth", header.bandwidth_hz, signal.bandwidth_hz),
+            ("PRF", header.prf_hz, signal.prf_hz),
+            ("carrier frequency", header.carrier_frequency_hz, signal.carrier_frequency_hz),
+        ] {
+            let difference_hz = (image_hz - scenario_hz).abs();
+            if difference_hz.is_nan() || difference_hz > SIGNAL_TOLERANCE * image_hz.abs() {
+                return Err(FocusingError::Settings(format!(
+                    "the image {} ({} Hz) differs from the scenario one ({} Hz)",
+                    name,
+                    image_hz,
+                    scenario_hz
+                )));
+            }
+        }
+        let geometry = geometry_at(scenario, 0.5 * header.pulses as f64 / header.prf_hz)?;
+        let approximate_m = target_m.unwrap_or_else(|| {
+            let (i, j) = image.peak();
+            header.grid().position_m(i, j)
+        });
+        let resolution = geometry.resolution_at(geometry.earth.ground_point(approximate_m), &scenario.signal);
+        let coarsest_m = resolution.ground_range_resolution_m.max(resolution.ground_doppler_resolution_m);
+        if !coarsest_m.is_finite() {
+            return Err(FocusingError::Settings("undefined predicted resolution at the target".to_string()));
+        }
+        let chip_pixels = ((CHIP_RESOLUTIONS * coarsest_m / header.spacing_m).ceil() as usize)
+            .next_power_of_two()
+            .clamp(MIN_CHIP_PIXELS, MAX_CHIP_PIXELS);
+        let upsampling = UPSAMPLED_PIXELS / chip_pixels;
+
+        // Brightest pixel within half a chip of the target
+        let grid = header.grid();
+        let center = ((approximate_m - grid.origin_m) / grid.spacing_m).round();
+        let radius = (chip_pixels / 2) as f64;
+        let brightest = (0..header.height)
+            .flat_map(|j| (0..header.width).map(move |i| (i, j)))
+            .filter(|&(i, j)| (DVec2::new(i as f64, j as f64) - center).abs().max_element() <= radius)
+            .max_by(|&(ai, aj), &(bi, bj)| image.pixel(ai, aj).norm_sqr().total_cmp(&image.pixel(bi, bj).norm_sqr()))
+            .ok_or_else(|| FocusingError::Settings("the target is out of the image".to_string()))?;
+
+        let chip = UpsampledChip::new(image, brightest, chip_pixels, upsampling);
+        let peak = chip.peak();
+        let peak_m = chip.position_m(peak);
+        let (peak_i, peak_j) = image.peak();
+        let image_peak = image.pixel(peak_i, peak_j).norm_sqr() as f64;
+        // Upsampling keeps the pixel values: compare the peak powers directly
+        let peak_db = 10.0 * (chip.power[peak] / image_peak).log10();
+
+        let measure = |gradient: DVec2, predicted_resolution_m: f64| {
+            let direction = gradient.normalize();
+            let (power, index) = chip.cut(peak_m, direction);
+            IrfCut::new(&power, index, chip.spacing_m, direction, predicted_resolution_m)
+        };
+        Ok(Self {
+            peak_m,
+            peak_db,
+            chip_pixels,
+            upsampling,
+            range: measure(resolution.range_gradient, resolution.ground_range_resolution_m),
+            doppler: measure(resolution.doppler_gradient_hz_m, resolution.ground_doppler_resolution_m),
+            gradients_angle_deg: resolution.gradients_angle_rad.to_degrees(),
+        })
+    }
+
+    /// Flat list of the analysis figures, `None` standing for an undefined value
+    pub fn metrics(&self) -> Vec<(String, Option<f64>)> {
+        let mut metrics = vec![
+            ("peak_east_m".to_string(), Some(self.peak_m.x)),
+            ("peak_north_m".to_string(), Some(self.peak_m.y)),
+            ("peak_db".to_string(), Some(self.peak_db)),
+            ("chip_pixels".to_string(), Some(self.chip_pixels as f64)),
+            ("upsampling".to_string(), Some(self.upsampling as f64)),
+            ("gradients_angle_deg".to_string(), Some(self.gradients_angle_deg)),
+        ];
+        for (name, cut) in [("range", &self.range), ("doppler", &self.doppler)] {
+            for (field, value) in [
+                ("direction_deg", Some(cut.direction_deg)),
+                ("resolution_m", cut.resolution_m),
+                ("predicted_resolution_m", Some(cut.predicted_resolution_m)),
+                ("pslr_db", cut.pslr_db),
+                ("islr_db", cut.islr_db),
+            ] {
+                metrics.push((format!("{}.{}", name, field), value));
+            }
+        }
+        metrics
+    }
+
+    /// Analysis as `metric,value` CSV rows, undefined values being left empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = "metric,value\n".to_string();
+        for (name, value) in self.metrics() {
+            let _ = writeln!(csv, "{},{}", name, value.map(|v| v.to_string()).unwrap_or_default());
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::{
+        focusing::{backproject, FocusingGrid},
+        simulation::{simulate, tests::short_acquisition},
+        simulation::PointTarget,
+        state::{AntennaState, CarrierState, SignalState}
+    };
+
+    use super::*;
+
+    #[test]
+    fn scenario_signal_must_match_the_image() {
+        let (scenario, target) = short_acquisition();
+        let raw = simulate(&scenario, &[target], scenario.signal.rx_sampling_rate_hz()).unwrap();
+        let spacing_m = 0.25 * scenario.signal.range_sum_resolution_m();
+        let grid = FocusingGrid::centered(target.position_m, DVec2::splat(32.0 * spacing_m), spacing_m);
+        let image = backproject(&raw, &grid, 2).unwrap();
+
+        let report = IrfReport::new(&image, &scenario, Some(target.position_m)).unwrap();
+        assert!(report.peak_m.distance(target.position_m) < spacing_m, "{:?}", report.peak_m);
+        assert!(report.range.resolution_m.is_some());
+
+        let changes: [fn(&mut SignalState); 3] = [
+            |signal| signal.bandwidth_hz *= 2.0,
+            |signal| signal.prf_hz += 1.0,
+            |signal| signal.carrier_frequency_hz = 5.4e9,
+        ];
+        for change in changes {
+            let mut other = scenario.clone();
+            change(&mut other.signal);
+            assert!(matches!(IrfReport::new(&image, &other, None), Err(FocusingError::Settings(_))));
+        }
+    }
+
+    #[test]
+    fn sinc2_cut() {
+        // Unweighted response of resolution 1 m (first nulls at ±1 m), sampled every 1/64 m
+        // over ±32 m
+        let power: Vec<f64> = (-2048..=2048)
+            .map(|k| {
+                let x = PI * k as f64 / 64.0;
+                if k == 0 { 1.0 } else { (x.sin() / x).powi(2) }
+            })
+            .collect();
+        let cut = IrfCut::new(&power, 2048, 1.0 / 64.0, DVec2::Y, 1.0);
+        assert_eq!(cut.direction_deg, 90.0);
+        assert!((cut.resolution_m.unwrap() - 0.8859).abs() < 1e-3, "{:?}", cut);
+        assert!((cut.pslr_db.unwrap() + 13.26).abs() < 0.01, "{:?}", cut);
+        // -9.68 dB over an infinite cut, less the sidelobes beyond ±32 m
+        assert!((cut.islr_db.unwrap() + 9.82).abs() < 0.01, "{:?}", cut);
+
+        // No crossing nor sidelobe within a main lobe
+        let cut = IrfCut::new(&power[1984..=2112], 64, 1.0 / 64.0, DVec2::X, 1.0);
+        assert!(cut.resolution_m.is_some() && cut.pslr_db.is_none() && cut.islr_db.is_none());
+        let cut = IrfCut::new(&power[2032..=2064], 16, 1.0 / 64.0, DVec2::X, 1.0);
+        assert!(cut.resolution_m.is_none());
+    }
+
+    #[test]
+    fn monostatic_broadside_response() {
+        // Monostatic carrier flying North, looking East without squint: the range and
+        // Doppler gradients are orthogonal and the responses are unweighted sincs
+        let (mut scenario, _) = short_acquisition();
+        scenario.rx.carrier = CarrierState { height_m: 3000.0, ..Default::default() };
+        scenario.rx.antenna = AntennaState::default();
+        scenario.tx = scenario.rx.clone();
+        let earth = Earth::new(scenario.earth, &scenario.origin);
+        let rx = PlatformGeometry::new(&scenario.rx.carrier, &scenario.rx.antenna, &scenario.rx.beam, &scenario.rx.pattern);
+        let position_m = earth.intersect(rx.antenna_position_m, rx.boresight()).unwrap().truncate();
+        let target = PointTarget { position_m, rcs_m2: 1.0 };
+        // The recording window starts with the nearest echo, whose range sidelobes before
+        // the window are lost: a faint nearer target keeps them in the window
+        let decoy = PointTarget { position_m: position_m - DVec2::new(300.0, 0.0), rcs_m2: 1e-6 };
+        let raw = simulate(&scenario, &[target, decoy], scenario.signal.rx_sampling_rate_hz()).unwrap();
+        let spacing_m = 0.25 * scenario.signal.range_sum_resolution_m();
+        let grid = FocusingGrid::centered(position_m, DVec2::splat(64.0 * spacing_m), spacing_m);
+        let image = backproject(&raw, &grid, 2).unwrap();
+
+        let report = IrfReport::new(&image, &scenario, Some(position_m)).unwrap();
+        assert!((report.gradients_angle_deg - 90.0).abs() < 1.0, "{:?}", report);
+        assert!(report.peak_db.abs() < 0.5, "{:?}", report);
+        for cut in [&report.range, &report.doppler] {
+            // -3 dB width within 2% of 0.886 resolution
+            let ratio = cut.resolution_m.unwrap() / cut.predicted_resolution_m;
+            assert!((ratio / 0.886 - 1.0).abs() < 0.02, "{:?}", cut);
+            assert!((cut.pslr_db.unwrap() + 13.3).abs() < 1.0, "{:?}", cut);
+            // The chip holds 3 (range) to 8 (Doppler) sidelobes on each side
+            assert!((cut.islr_db.unwrap() + 10.0).abs() < 1.5, "{:?}", cut);
+        }
+    }
+}
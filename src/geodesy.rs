@@ -0,0 +1,227 @@
+//! Geodetic coordinates on the WGS84 ellipsoid and conversions between the
+//! geodetic, ECEF (Earth-Centered Earth-Fixed), local ENU (World frame) and
+//! local NED frames.
+//!
+//! The local frames are tangent to the ellipsoid at a scene origin, the World
+//! frame being the ENU frame at this origin.
+
+use bevy::math::{DMat3, DVec3};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ENU_TO_NED_DROT, WGS84_FLATTENING, WGS84_SEMI_MAJOR_AXIS_M};
+
+/// Maximum number of iterations of the ECEF to geodetic conversion
+const ECEF_TO_GEODETIC_ITERATIONS: usize = 10;
+
+/// Position on the WGS84 ellipsoid
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GeodeticPosition {
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    /// Height above the ellipsoid
+    pub height_m: f64,
+}
+
+/// Square of the WGS84 first eccentricity
+#[inline]
+fn wgs84_e2() -> f64 {
+    WGS84_FLATTENING * (2.0 - WGS84_FLATTENING)
+}
+
+/// WGS84 prime vertical radius of curvature at `latitude_rad`
+#[inline]
+fn prime_vertical_radius_m(latitude_rad: f64) -> f64 {
+    let sin_lat = latitude_rad.sin();
+    WGS84_SEMI_MAJOR_AXIS_M / (1.0 - wgs84_e2() * sin_lat * sin_lat).sqrt()
+}
+
+impl GeodeticPosition {
+    pub fn new(latitude_deg: f64, longitude_deg: f64, height_m: f64) -> Self {
+        Self { latitude_deg, longitude_deg, height_m }
+    }
+
+    /// ECEF coordinates of the position
+    pub fn to_ecef(&self) -> DVec3 {
+        let (sin_lat, cos_lat) = self.latitude_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude_deg.to_radians().sin_cos();
+        let n = prime_vertical_radius_m(self.latitude_deg.to_radians());
+        DVec3::new(
+            (n + self.height_m) * cos_lat * cos_lon,
+            (n + self.height_m) * cos_lat * sin_lon,
+            (n * (1.0 - wgs84_e2()) + self.height_m) * sin_lat
+        )
+    }
+
+    /// Geodetic position of the ECEF point `ecef_m` (fixed-point iteration on the latitude)
+    pub fn from_ecef(ecef_m: DVec3) -> Self {
+        let e2 = wgs84_e2();
+        let p = ecef_m.truncate().length();
+        let longitude = ecef_m.y.atan2(ecef_m.x);
+        let mut latitude = ecef_m.z.atan2(p * (1.0 - e2));
+        let mut height = 0.0;
+        for _ in 0..ECEF_TO_GEODETIC_ITERATIONS {
+            let n = prime_vertical_radius_m(latitude);
+            let (sin_lat, cos_lat) = latitude.sin_cos();
+            // Height formula valid at any latitude, including the poles
+            height = p * cos_lat + ecef_m.z * sin_lat - WGS84_SEMI_MAJOR_AXIS_M * WGS84_SEMI_MAJOR_AXIS_M / n;
+            let next = ecef_m.z.atan2(p * (1.0 - e2 * n / (n + height)));
+            let converged = (next - latitude).abs() < 1e-14;
+            latitude = next;
+            if converged {
+                break;
+            }
+        }
+        Self {
+            latitude_deg: latitude.to_degrees(),
+            longitude_deg: longitude.to_degrees(),
+            height_m: height,
+        }
+    }
+}
+
+/// Local tangent frames (ENU and NED) at a geodetic origin
+#[derive(Clone, Copy, Debug)]
+pub struct LocalFrame {
+    pub origin: GeodeticPosition,
+    pub origin_ecef_m: DVec3,
+    /// Rotation from the ENU frame to the ECEF frame (columns are the East, North and Up axes)
+    pub enu_to_ecef: DMat3,
+}
+
+impl LocalFrame {
+    pub fn new(origin: GeodeticPosition) -> Self {
+        let (sin_lat, cos_lat) = origin.latitude_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = origin.longitude_deg.to_radians().sin_cos();
+        Self {
+            origin,
+            origin_ecef_m: origin.to_ecef(),
+            enu_to_ecef: DMat3::from_cols(
+                DVec3::new(-sin_lon, cos_lon, 0.0),
+                DVec3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+                DVec3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat)
+            ),
+        }
+    }
+
+    /// ENU coordinates of the ECEF point `ecef_m`
+    #[inline]
+    pub fn ecef_to_enu(&self, ecef_m: DVec3) -> DVec3 {
+        self.enu_to_ecef.transpose() * (ecef_m - self.origin_ecef_m)
+    }
+
+    /// ECEF coordinates of the ENU point `enu_m`
+    #[inline]
+    pub fn enu_to_ecef(&self, enu_m: DVec3) -> DVec3 {
+        self.origin_ecef_m + self.enu_to_ecef * enu_m
+    }
+
+    #[inline]
+    pub fn geodetic_to_enu(&self, position: &GeodeticPosition) -> DVec3 {
+        self.ecef_to_enu(position.to_ecef())
+    }
+
+    #[inline]
+    pub fn enu_to_geodetic(&self, enu_m: DVec3) -> GeodeticPosition {
+        GeodeticPosition::from_ecef(self.enu_to_ecef(enu_m))
+    }
+
+    /// NED coordinates of the ECEF point `ecef_m`
+    #[inline]
+    pub fn ecef_to_ned(&self, ecef_m: DVec3) -> DVec3 {
+        enu_to_ned(self.ecef_to_enu(ecef_m))
+    }
+
+    /// ECEF coordinates of the NED point `ned_m`
+    #[inline]
+    pub fn ned_to_ecef(&self, ned_m: DVec3) -> DVec3 {
+        self.enu_to_ecef(ned_to_enu(ned_m))
+    }
+}
+
+/// Converts a vector from the ENU to the NED frame
+#[inline]
+pub fn enu_to_ned(enu: DVec3) -> DVec3 {
+    *ENU_TO_NED_DROT * enu
+}
+
+/// Converts a vector from the NED to the ENU frame
+#[inline]
+pub fn ned_to_enu(ned: DVec3) -> DVec3 {
+    ENU_TO_NED_DROT.inverse() * ned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(position: GeodeticPosition) {
+        let ecef_m = position.to_ecef();
+        let back = GeodeticPosition::from_ecef(ecef_m);
+        assert!((back.latitude_deg - position.latitude_deg).abs() < 1e-9, "{:?} -> {:?}", position, back);
+        assert!((back.height_m - position.height_m).abs() < 1e-6, "{:?} -> {:?}", position, back);
+        // The longitude is undefined at the poles
+        assert!(back.to_ecef().abs_diff_eq(ecef_m, 1e-6), "{:?} -> {:?}", position, back);
+    }
+
+    #[test]
+    fn ecef_round_trips() {
+        for position in [
+            GeodeticPosition::new(0.0, 0.0, 0.0),
+            GeodeticPosition::new(0.0, -120.0, 500.0),
+            GeodeticPosition::new(90.0, 0.0, 100.0),
+            GeodeticPosition::new(-90.0, 30.0, 0.0),
+            GeodeticPosition::new(45.0, 170.0, -100.0),
+            GeodeticPosition::new(-30.0, 60.0, 800_000.0),
+            GeodeticPosition::new(10.0, -75.0, 35_786_000.0)
+        ] {
+            assert_round_trip(position);
+        }
+    }
+
+    #[test]
+    fn ecef_axes() {
+        let b = WGS84_SEMI_MAJOR_AXIS_M * (1.0 - WGS84_FLATTENING);
+        assert!(GeodeticPosition::new(0.0, 0.0, 0.0).to_ecef().abs_diff_eq(DVec3::new(WGS84_SEMI_MAJOR_AXIS_M, 0.0, 0.0), 1e-6));
+        assert!(GeodeticPosition::new(0.0, 90.0, 0.0).to_ecef().abs_diff_eq(DVec3::new(0.0, WGS84_SEMI_MAJOR_AXIS_M, 0.0), 1e-6));
+        assert!(GeodeticPosition::new(90.0, 0.0, 0.0).to_ecef().abs_diff_eq(DVec3::new(0.0, 0.0, b), 1e-6));
+    }
+
+    #[test]
+    fn published_ecef_position() {
+        // GeographicLib CartConvert documentation example, published to the centimeter
+        let position = GeodeticPosition::new(33.3, 44.4, 6000.0);
+        let ecef_m = DVec3::new(3_816_209.60, 3_737_108.55, 3_485_109.57);
+        assert!(position.to_ecef().abs_diff_eq(ecef_m, 0.01));
+        let back = GeodeticPosition::from_ecef(ecef_m);
+        assert!((back.latitude_deg - 33.3).abs() < 1e-7);
+        assert!((back.longitude_deg - 44.4).abs() < 1e-7);
+        assert!((back.height_m - 6000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn enu_ned_swap() {
+        let enu = DVec3::new(1.0, 2.0, 3.0);
+        assert!(enu_to_ned(enu).abs_diff_eq(DVec3::new(2.0, 1.0, -3.0), 1e-12));
+        assert!(ned_to_enu(enu_to_ned(enu)).abs_diff_eq(enu, 1e-12));
+    }
+
+    #[test]
+    fn local_frame_round_trips() {
+        let frame = LocalFrame::new(GeodeticPosition::new(43.6, 1.44, 150.0));
+        assert!(frame.origin_ecef_m.abs_diff_eq(frame.origin.to_ecef(), 1e-9));
+        assert!(frame.ecef_to_enu(frame.origin_ecef_m).abs_diff_eq(DVec3::ZERO, 1e-6));
+
+        let enu_m = DVec3::new(1200.0, -3400.0, 560.0);
+        let ecef_m = frame.enu_to_ecef(enu_m);
+        assert!(frame.ecef_to_enu(ecef_m).abs_diff_eq(enu_m, 1e-6));
+        assert!(frame.ecef_to_ned(ecef_m).abs_diff_eq(enu_to_ned(enu_m), 1e-6));
+        assert!(frame.ned_to_ecef(enu_to_ned(enu_m)).abs_diff_eq(ecef_m, 1e-6));
+
+        // Up is the ellipsoid normal at the origin
+        let above = frame.enu_to_geodetic(DVec3::new(0.0, 0.0, 1000.0));
+        assert!((above.latitude_deg - 43.6).abs() < 1e-9);
+        assert!((above.longitude_deg - 1.44).abs() < 1e-9);
+        assert!((above.height_m - 1150.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,41 @@
+//! Headless bistatic geometry engine.
+//!
+//! Every quantity is computed in f64 and expressed in the World frame (ENU
+//! referential, ground plane at Z = 0 for a flat Earth), independently of the
+//! Bevy scene.
+
+mod platform;
+pub use platform::PlatformGeometry;
+
+mod bistatic;
+pub use bistatic::{BistaticAngles, BistaticGeometry};
+
+mod ground;
+pub use ground::intersect_ground;
+
+mod earth;
+pub use earth::Earth;
+
+mod resolution;
+pub use resolution::{BistaticResolution, ResolutionEllipse};
+
+mod radiometry;
+pub use radiometry::{db_to_linear, linear_to_db};
+
+mod contour;
+pub use contour::{contour_levels, iso_contours, GroundGrid};
+
+mod footprint;
+pub use footprint::{CommonFootprint, Footprint};
+
+mod ambiguity;
+pub use ambiguity::AmbiguityCheck;
+
+mod polygon;
+pub use polygon::{
+    convex_hull,
+    convex_polygon_intersection,
+    polygon_centroid,
+    polygon_contains,
+    polygon_signed_area
+};
@@ -0,0 +1,211 @@
+use bevy::math::DVec2;
+
+use crate::{constants::SPEED_OF_LIGHT_M_S, state::SignalState};
+
+use super::{polygon_contains, BistaticGeometry, CommonFootprint};
+
+/// Number of points per side of the grid sampling the common footprint interior, in
+/// addition to its vertices
+const AMBIGUITY_GRID_SAMPLES: usize = 32;
+
+/// Echo timing and Doppler spread over the common footprint, checked against the PRF.
+///
+/// Delays are counted from the transmission of a pulse. The echo window spans from the
+/// start of the echo of the nearest point (smallest range sum) to the end of the echo
+/// of the farthest one, and the direct signal is the pulse received by Rx along the
+/// baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AmbiguityCheck {
+    /// Smallest and largest range sums R_tx + R_rx over the common footprint
+    pub min_range_sum_m: f64,
+    pub max_range_sum_m: f64,
+    /// Echo window start and end delays
+    pub echo_start_s: f64,
+    pub echo_end_s: f64,
+    /// Delay of the direct Tx to Rx signal
+    pub direct_delay_s: f64,
+    /// Smallest and largest bistatic Doppler frequencies over the common footprint
+    pub min_doppler_hz: f64,
+    pub max_doppler_hz: f64,
+    /// Pulse repetition interval and pulse duration
+    pub pri_s: f64,
+    pub pulse_duration_s: f64,
+    /// Pulses transmitted before the echo window of a pulse starts
+    pub pulses_in_flight: u64,
+    /// The Doppler bandwidth exceeds the PRF: the azimuth spectrum is aliased
+    pub doppler_aliased: bool,
+    /// The echo window exceeds the pulse repetition interval: echoes of successive
+    /// pulses overlap
+    pub range_ambiguous: bool,
+    /// The direct signal of a pulse falls within the echo window of a pulse
+    pub direct_signal_overlap: bool,
+}
+
+/// True if the intervals [`a`, `a + a_length`] and [`b`, `b + b_length`], repeated
+/// every `period`, overlap
+fn periodic_overlap(a: f64, a_length: f64, b: f64, b_length: f64, period: f64) -> bool {
+    if a_length >= period || b_length >= period {
+        return true;
+    }
+    // Start of the first repetition of b after the start of a
+    let offset = (b - a).rem_euclid(period);
+    offset < a_length || offset + b_length > period
+}
+
+impl AmbiguityCheck {
+    #[inline]
+    pub fn echo_window_s(&self) -> f64 {
+        self.echo_end_s - self.echo_start_s
+    }
+
+    #[inline]
+    pub fn doppler_bandwidth_hz(&self) -> f64 {
+        self.max_doppler_hz - self.min_doppler_hz
+    }
+
+    /// True if any ambiguity is flagged
+    #[inline]
+    pub fn is_ambiguous(&self) -> bool {
+        self.doppler_aliased || self.range_ambiguous || self.direct_signal_overlap
+    }
+}
+
+impl BistaticGeometry {
+    /// Checks the echo window and the Doppler bandwidth over `common` against the PRF of
+    /// `signal`. The range sum and the Doppler frequency are sampled at the footprint
+    /// vertices and on a grid over its interior.
+    pub fn ambiguity_check(&self, common: &CommonFootprint, signal: &SignalState) -> AmbiguityCheck {
+        let step = (common.max_m - common.min_m) / (AMBIGUITY_GRID_SAMPLES - 1) as f64;
+        let interior = (0..AMBIGUITY_GRID_SAMPLES)
+            .flat_map(|j| (0..AMBIGUITY_GRID_SAMPLES).map(move |i| DVec2::new(i as f64, j as f64)))
+            .map(|index| common.min_m + index * step)
+            .filter(|&point| polygon_contains(&common.points, point));
+
+        let wavelength_m = signal.wavelength_m();
+        let (mut range_sums_m, mut dopplers_hz) = ((f64::INFINITY, f64::NEG_INFINITY), (f64::INFINITY, f64::NEG_INFINITY));
+        for point in common.points.iter().copied().chain(interior) {
+            let point_m = self.earth.ground_point(point);
+            let range_sum_m = self.range_sum_m(point_m);
+            let doppler_hz = self.doppler_hz(point_m, wavelength_m);
+            range_sums_m = (range_sums_m.0.min(range_sum_m), range_sums_m.1.max(range_sum_m));
+            dopplers_hz = (dopplers_hz.0.min(doppler_hz), dopplers_hz.1.max(doppler_hz));
+        }
+
+        let pri_s = 1.0 / signal.prf_hz;
+        let echo_start_s = range_sums_m.0 / SPEED_OF_LIGHT_M_S;
+        let echo_end_s = range_sums_m.1 / SPEED_OF_LIGHT_M_S + signal.pulse_duration_s;
+        let direct_delay_s = self.baseline_m().length() / SPEED_OF_LIGHT_M_S;
+        AmbiguityCheck {
+            min_range_sum_m: range_sums_m.0,
+            max_range_sum_m: range_sums_m.1,
+            echo_start_s,
+            echo_end_s,
+            direct_delay_s,
+            min_doppler_hz: dopplers_hz.0,
+            max_doppler_hz: dopplers_hz.1,
+            pri_s,
+            pulse_duration_s: signal.pulse_duration_s,
+            pulses_in_flight: (echo_start_s / pri_s).floor() as u64,
+            doppler_aliased: dopplers_hz.1 - dopplers_hz.0 > signal.prf_hz,
+            range_ambiguous: echo_end_s - echo_start_s > pri_s,
+            direct_signal_overlap: periodic_overlap(
+                echo_start_s,
+                echo_end_s - echo_start_s,
+                direct_delay_s,
+                signal.pulse_duration_s,
+                pri_s
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::DVec3;
+
+    use crate::{
+        constants::FOOTPRINT_SEGMENTS,
+        geometry::{Earth, PlatformGeometry},
+        state::{AntennaBeamState, AntennaState, CarrierState}
+    };
+
+    use super::*;
+
+    #[test]
+    fn periodic_overlaps() {
+        // Touching windows do not overlap, on either side and across the period boundary
+        assert!(!periodic_overlap(0.5, 0.25, 0.75, 0.25, 1.0));
+        assert!(!periodic_overlap(0.5, 0.25, 0.25, 0.25, 1.0));
+        assert!(!periodic_overlap(0.75, 0.25, 0.0, 0.25, 1.0));
+        assert!(periodic_overlap(0.5, 0.25, 0.625, 0.25, 1.0));
+        assert!(periodic_overlap(0.0, 0.25, 0.875, 0.25, 1.0));
+        // A window longer than the period overlaps anything
+        assert!(periodic_overlap(0.0, 1.5, 0.5, 0.0625, 1.0));
+        assert!(periodic_overlap(0.5, 0.0625, 0.0, 1.0, 1.0));
+        // Echo window of a pulse transmitted 10 periods earlier
+        assert!(!periodic_overlap(10.25, 0.25, 0.125, 0.0625, 1.0));
+        assert!(periodic_overlap(10.25, 0.25, 0.375, 0.0625, 1.0));
+        assert!(periodic_overlap(0.375, 0.0625, 10.25, 0.25, 1.0));
+    }
+
+    #[test]
+    fn monostatic_nadir_ambiguities() {
+        // Nadir looking antenna flying North, with a Gaussian beam bounded at exactly half
+        // power: the common footprint is bounded by off-nadir angles of α along the axes
+        let (height_m, speed_m_s, alpha) = (3000.0, 100.0, 5f64.to_radians());
+        let carrier = CarrierState {
+            height_m,
+            velocity_m_s: DVec3::new(0.0, speed_m_s, 0.0),
+            ..Default::default()
+        };
+        let antenna = AntennaState { heading_deg: 0.0, elevation_deg: -90.0, bank_deg: 0.0 };
+        let beam = AntennaBeamState {
+            azimuth_beam_width_deg: 10.0,
+            elevation_beam_width_deg: 10.0,
+            cone_level_db: -10.0 * 2f64.log10(),
+            ..Default::default()
+        };
+        let platform = PlatformGeometry::new(&carrier, &antenna, &beam, &Default::default());
+        let geometry = BistaticGeometry::new(platform.clone(), platform, Earth::default());
+        let common = geometry.common_footprint(FOOTPRINT_SEGMENTS).unwrap();
+
+        let mut signal = SignalState {
+            carrier_frequency_hz: 10e9,
+            prf_hz: 1500.0,
+            pulse_duration_s: 10e-6,
+            ..Default::default()
+        };
+        let check = geometry.ambiguity_check(&common, &signal);
+        // Echoes from the nadir (sampled 12 m away at most) to the beam edge
+        assert!((check.min_range_sum_m - 2.0 * height_m).abs() < 0.1, "{:?}", check);
+        assert!((check.max_range_sum_m - 2.0 * height_m / alpha.cos()).abs() < 1e-3, "{:?}", check);
+        assert!((check.echo_start_s - 2.0 * height_m / SPEED_OF_LIGHT_M_S).abs() < 1e-9);
+        let echo_window_s = 2.0 * height_m * (1.0 / alpha.cos() - 1.0) / SPEED_OF_LIGHT_M_S + 10e-6;
+        assert!((check.echo_window_s() - echo_window_s).abs() < 1e-9, "{} s", check.echo_window_s());
+        assert_eq!(check.direct_delay_s, 0.0);
+        // Along track beam edges at ±α from the nadir: B = 4·v·sin(α)/λ (1163 Hz)
+        let doppler_bandwidth_hz = 4.0 * speed_m_s * alpha.sin() / signal.wavelength_m();
+        assert!((check.doppler_bandwidth_hz() / doppler_bandwidth_hz - 1.0).abs() < 1e-6, "{:?}", check);
+        assert!((check.max_doppler_hz + check.min_doppler_hz).abs() < 1e-6);
+        assert_eq!(check.pulses_in_flight, 0);
+        assert!(!check.is_ambiguous(), "{:?}", check);
+
+        // Doppler bandwidth above the PRF
+        signal.prf_hz = 1000.0;
+        let check = geometry.ambiguity_check(&common, &signal);
+        assert!(check.doppler_aliased && !check.range_ambiguous && !check.direct_signal_overlap);
+
+        // PRI of 25 µs within the 20 µs to 30.08 µs echo window: the direct signal of the
+        // next pulse is received during the echo
+        signal.prf_hz = 40e3;
+        let check = geometry.ambiguity_check(&common, &signal);
+        assert!(!check.doppler_aliased && !check.range_ambiguous && check.direct_signal_overlap);
+        assert_eq!(check.pulses_in_flight, 0);
+
+        // PRI of 5 µs shorter than the echo window, 4 pulses transmitted before its start
+        signal.prf_hz = 200e3;
+        let check = geometry.ambiguity_check(&common, &signal);
+        assert!(!check.doppler_aliased && check.range_ambiguous && check.direct_signal_overlap);
+        assert_eq!(check.pulses_in_flight, 4);
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::math::DVec3;
+
+use super::{Earth, PlatformGeometry};
+
+/// Transmitter and Receiver geometries of a bistatic configuration.
+#[derive(Clone, Debug)]
+pub struct BistaticGeometry {
+    pub tx: PlatformGeometry,
+    pub rx: PlatformGeometry,
+    /// Ground surface on which the bistatic quantities are evaluated
+    pub earth: Earth,
+}
+
+/// Bistatic angles seen from a ground point.
+#[derive(Clone, Copy, Debug)]
+pub struct BistaticAngles {
+    /// Angle between the point-to-Tx and point-to-Rx directions
+    pub bistatic_angle_rad: f64,
+    /// Unit vector bisecting the point-to-Tx and point-to-Rx directions
+    pub bisector: DVec3,
+    /// Angle between the bisector and the ground surface normal
+    pub incidence_angle_rad: f64,
+}
+
+impl BistaticGeometry {
+    pub fn new(tx: PlatformGeometry, rx: PlatformGeometry, earth: Earth) -> Self {
+        Self { tx, rx, earth }
+    }
+
+    /// Vector from Tx to Rx antenna phase centers
+    #[inline]
+    pub fn baseline_m(&self) -> DVec3 {
+        self.rx.antenna_position_m - self.tx.antenna_position_m
+    }
+
+    /// Bistatic angle, bisector and bistatic incidence angle at `point_m`
+    /// (World frame, on the ground surface).
+    pub fn angles_at(&self, point_m: DVec3) -> BistaticAngles {
+        let to_tx = (self.tx.antenna_position_m - point_m).normalize();
+        let to_rx = (self.rx.antenna_position_m - point_m).normalize();
+        let bisector = (to_tx + to_rx).normalize_or_zero(); // undefined on the baseline
+
+        BistaticAngles {
+            bistatic_angle_rad: to_tx.dot(to_rx).clamp(-1.0, 1.0).acos(),
+            bisector,
+            incidence_angle_rad: bisector.dot(self.earth.normal_at(point_m)).clamp(-1.0, 1.0).acos(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::state::{AntennaBeamState, AntennaState, CarrierState};
+
+    use super::*;
+
+    fn platform(position_m: DVec3) -> PlatformGeometry {
+        let carrier = CarrierState {
+            position_m: position_m.truncate(),
+            height_m: position_m.z,
+            ..Default::default()
+        };
+        PlatformGeometry::new(&carrier, &AntennaState::default(), &AntennaBeamState::default(), &Default::default())
+    }
+
+    #[test]
+    fn monostatic_angles() {
+        let position_m = DVec3::new(-3000.0, 0.0, 3000.0);
+        let geometry = BistaticGeometry::new(platform(position_m), platform(position_m), Earth::default());
+        let angles = geometry.angles_at(DVec3::ZERO);
+        assert!(angles.bistatic_angle_rad.abs() < 1e-7);
+        assert!(angles.bisector.abs_diff_eq(position_m.normalize(), 1e-12));
+        assert!((angles.incidence_angle_rad - 45f64.to_radians()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn symmetric_bistatic_angles() {
+        let geometry = BistaticGeometry::new(
+            platform(DVec3::new(-1000.0, 0.0, 1000.0)),
+            platform(DVec3::new(1000.0, 0.0, 1000.0)),
+            Earth::default()
+        );
+        let angles = geometry.angles_at(DVec3::ZERO);
+        assert!((angles.bistatic_angle_rad - FRAC_PI_2).abs() < 1e-12);
+        assert!(angles.bisector.abs_diff_eq(DVec3::Z, 1e-12));
+        assert!(angles.incidence_angle_rad.abs() < 1e-7);
+        assert_eq!(geometry.baseline_m(), DVec3::new(2000.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::math::{DVec2, DVec3};
+
+/// Regular sampling grid over a rectangle of the ground plane.
+#[derive(Clone, Copy, Debug)]
+pub struct GroundGrid {
+    /// South-West corner (East, North)
+    pub min_m: DVec2,
+    /// North-East corner (East, North)
+    pub max_m: DVec2,
+    /// Number of cells along East and North
+    pub cells: (usize, usize),
+}
+
+impl GroundGrid {
+    /// Square grid centered on the World origin
+    pub fn centered(half_size_m: f64, cells: usize) -> Self {
+        Self {
+            min_m: DVec2::splat(-half_size_m),
+            max_m: DVec2::splat(half_size_m),
+            cells: (cells, cells),
+        }
+    }
+
+    /// Ground point of the (i, j) grid node
+    #[inline]
+    pub fn node(&self, i: usize, j: usize) -> DVec3 {
+        let step = (self.max_m - self.min_m) / DVec2::new(self.cells.0 as f64, self.cells.1 as f64);
+        (self.min_m + DVec2::new(i as f64, j as f64) * step).extend(0.0)
+    }
+
+    /// Samples `field` on every grid node (row major, East index first)
+    pub fn sample<F: Fn(DVec3) -> f64>(&self, field: F) -> Vec<f64> {
+        let (ni, nj) = (self.cells.0 + 1, self.cells.1 + 1);
+        let mut values = Vec::with_capacity(ni * nj);
+        for j in 0..nj {
+            for i in 0..ni {
+                values.push(field(self.node(i, j)));
+            }
+        }
+        values
+    }
+}
+
+/// Smallest value of the 1-2-5 sequence (..., 0.5, 1, 2, 5, 10, ...) greater than `value`
+fn next_1_2_5_step(value: f64) -> f64 {
+    let decade = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|mantissa| mantissa * decade)
+        .find(|&step| step > value * (1.0 + 1e-9))
+        .unwrap_or(20.0 * decade)
+}
+
+/// Contour levels which are multiples of `spacing` in [`min`, `max`]. When there would be
+/// more than `max_levels`, the spacing is widened to the next 1-2-5 steps until they fit,
+/// so that the levels still cover the whole range.
+pub fn contour_levels(min: f64, max: f64, spacing: f64, max_levels: usize) -> Vec<f64> {
+    if spacing.is_nan() || spacing <= 0.0 || !min.is_finite() || !max.is_finite() || max_levels == 0 {
+        return Vec::new();
+    }
+    let count = |spacing: f64| (max / spacing).floor() - (min / spacing).ceil() + 1.0;
+    let mut spacing = spacing;
+    while count(spacing) > max_levels as f64 {
+        spacing = next_1_2_5_step(spacing);
+    }
+    let first = (min / spacing).ceil() as i64;
+    let last = (max / spacing).floor() as i64;
+    (first..=last)
+        .map(|k| k as f64 * spacing)
+        .collect()
+}
+
+/// Iso-contours of the sampled `values` (see [`GroundGrid::sample`]) for each level,
+/// computed by marching squares and returned as ground line segments.
+pub fn iso_contours(grid: &GroundGrid, values: &[f64], levels: &[f64]) -> Vec<(DVec3, DVec3)> {
+    let ni = grid.cells.0 + 1;
+    let value = |i: usize, j: usize| values[j * ni + i];
+    let mut segments = Vec::new();
+
+    for j in 0..grid.cells.1 {
+        for i in 0..grid.cells.0 {
+            // Corners in counterclockwise order, from South-West
+            let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+            let v = corners.map(|(i, j)| value(i, j));
+            if v.iter().any(|v| !v.is_finite()) {
+                continue;
+            }
+            let p = corners.map(|(i, j)| grid.node(i, j));
+            let (v_min, v_max) = v
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+
+            for &level in levels.iter().filter(|&&l| l > v_min && l <= v_max) {
+                // Level crossing on each edge k, from corner k to corner k+1
+                let crossing = |k: usize| {
+                    let (a, b) = (v[k], v[(k + 1) % 4]);
+                    if (a < level) != (b < level) {
+                        let t = (level - a) / (b - a);
+                        Some(p[k].lerp(p[(k + 1) % 4], t))
+                    } else {
+                        None
+                    }
+                };
+                let edges = [crossing(0), crossing(1), crossing(2), crossing(3)];
+                let points: Vec<DVec3> = edges.iter().flatten().copied().collect();
+                match points.len() {
+                    2 => segments.push((points[0], points[1])),
+                    4 => { // Saddle point, disambiguated with the cell center value
+                        let center = 0.25 * v.iter().sum::<f64>();
+                        let [e0, e1, e2, e3] = edges.map(Option::unwrap);
+                        if (center < level) == (v[0] < level) {
+                            segments.push((e0, e1));
+                            segments.push((e2, e3));
+                        } else {
+                            segments.push((e3, e0));
+                            segments.push((e1, e2));
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_multiples_of_the_spacing() {
+        assert_eq!(contour_levels(-2.5, 7.0, 2.0, 100), vec![-2.0, 0.0, 2.0, 4.0, 6.0]);
+        assert_eq!(contour_levels(1.0, 1.5, 2.0, 100), Vec::<f64>::new());
+        assert!(contour_levels(0.0, 1.0, 0.0, 100).is_empty());
+        assert!(contour_levels(0.0, f64::INFINITY, 1.0, 100).is_empty());
+    }
+
+    #[test]
+    fn spacing_widens_to_cover_the_range() {
+        // 1001 levels at 1 m, 201 at 5 m, 101 at 10 m
+        let levels = contour_levels(0.0, 1000.0, 1.0, 150);
+        assert_eq!(levels.len(), 101);
+        assert_eq!(levels.first(), Some(&0.0));
+        assert_eq!(levels.last(), Some(&1000.0));
+        assert!(levels.windows(2).all(|pair| pair[1] - pair[0] == 10.0));
+
+        // Spacings off the 1-2-5 sequence widen to its next step
+        let levels = contour_levels(0.0, 3000.0, 3.0, 500);
+        assert_eq!(levels.len(), 301);
+        assert_eq!(levels.last(), Some(&3000.0));
+    }
+
+    #[test]
+    fn next_steps() {
+        assert_eq!(next_1_2_5_step(1.0), 2.0);
+        assert_eq!(next_1_2_5_step(2.0), 5.0);
+        assert_eq!(next_1_2_5_step(5.0), 10.0);
+        assert_eq!(next_1_2_5_step(3.0), 5.0);
+        assert_eq!(next_1_2_5_step(0.2), 0.5);
+        assert_eq!(next_1_2_5_step(700.0), 1000.0);
+    }
+}
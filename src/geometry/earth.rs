@@ -0,0 +1,180 @@
+use bevy::math::{DVec2, DVec3};
+
+use crate::{
+    constants::{EARTH_MEAN_RADIUS_M, WGS84_FLATTENING, WGS84_SEMI_MAJOR_AXIS_M},
+    geodesy::LocalFrame,
+    state::{EarthModel, SceneOrigin}
+};
+
+use super::intersect_ground;
+
+/// Height above the World plane from which vertical rays are cast to find the ground below a point
+const GROUND_POINT_RAY_HEIGHT_M: f64 = 1000.0;
+
+/// Ground surface of the selected Earth model, expressed in World frame (ENU
+/// referential at the scene origin).
+///
+/// Curved surfaces pass through the World origin, so that the flat model is
+/// their tangent plane there.
+#[derive(Clone, Copy, Debug)]
+pub struct Earth {
+    pub model: EarthModel,
+    pub frame: LocalFrame,
+}
+
+impl Default for Earth {
+    fn default() -> Self {
+        Self::new(EarthModel::Flat, &SceneOrigin::default())
+    }
+}
+
+/// Smallest non negative `t` such that `|q0 + t·qd| = 1`, if any
+fn intersect_unit_sphere(q0: DVec3, qd: DVec3) -> Option<f64> {
+    let a = qd.length_squared();
+    let b = q0.dot(qd);
+    let c = q0.length_squared() - 1.0;
+    let delta = b * b - a * c;
+    if a == 0.0 || delta < 0.0 {
+        return None;
+    }
+    let sqrt_delta = delta.sqrt();
+    [(-b - sqrt_delta) / a, (-b + sqrt_delta) / a]
+        .into_iter()
+        .find(|&t| t >= 0.0)
+}
+
+impl Earth {
+    pub fn new(model: EarthModel, origin: &SceneOrigin) -> Self {
+        Self {
+            model,
+            frame: origin.frame(),
+        }
+    }
+
+    /// Center of the spherical model in World frame
+    #[inline]
+    fn sphere_center_m() -> DVec3 {
+        DVec3::new(0.0, 0.0, -EARTH_MEAN_RADIUS_M)
+    }
+
+    /// Semi-axes (equatorial, polar) of the ellipsoidal model, raised to the origin height
+    #[inline]
+    fn ellipsoid_axes_m(&self) -> (f64, f64) {
+        let height = self.frame.origin.height_m;
+        (
+            WGS84_SEMI_MAJOR_AXIS_M + height,
+            WGS84_SEMI_MAJOR_AXIS_M * (1.0 - WGS84_FLATTENING) + height
+        )
+    }
+
+    /// Intersection of the ray starting at `origin_m` along `direction` with the
+    /// ground. Returns `None` if the ray does not reach the ground.
+    pub fn intersect(&self, origin_m: DVec3, direction: DVec3) -> Option<DVec3> {
+        match self.model {
+            EarthModel::Flat => intersect_ground(origin_m, direction),
+            EarthModel::Sphere => {
+                let q0 = (origin_m - Self::sphere_center_m()) / EARTH_MEAN_RADIUS_M;
+                let t = intersect_unit_sphere(q0, direction / EARTH_MEAN_RADIUS_M)?;
+                Some(origin_m + t * direction)
+            },
+            EarthModel::Wgs84 => {
+                // Solved in ECEF frame, where the ellipsoid is scaled to the unit sphere
+                let (a, b) = self.ellipsoid_axes_m();
+                let scale = DVec3::new(1.0 / a, 1.0 / a, 1.0 / b);
+                let q0 = self.frame.enu_to_ecef(origin_m) * scale;
+                let qd = self.frame.enu_to_ecef * direction * scale;
+                let t = intersect_unit_sphere(q0, qd)?;
+                Some(origin_m + t * direction)
+            }
+        }
+    }
+
+    /// Ground point vertically below (or above) the World plane point `position_m` (East, North)
+    pub fn ground_point(&self, position_m: DVec2) -> DVec3 {
+        match self.model {
+            EarthModel::Flat => position_m.extend(0.0),
+            _ => self
+                .intersect(position_m.extend(GROUND_POINT_RAY_HEIGHT_M), DVec3::NEG_Z)
+                .unwrap_or(position_m.extend(0.0)) // Beyond the Earth silhouette
+        }
+    }
+
+    /// Upward unit normal of the ground surface at the ground point `point_m`
+    pub fn normal_at(&self, point_m: DVec3) -> DVec3 {
+        match self.model {
+            EarthModel::Flat => DVec3::Z,
+            EarthModel::Sphere => (point_m - Self::sphere_center_m()).normalize(),
+            EarthModel::Wgs84 => {
+                let (a, b) = self.ellipsoid_axes_m();
+                let p = self.frame.enu_to_ecef(point_m);
+                let normal_ecef = DVec3::new(p.x / (a * a), p.y / (a * a), p.z / (b * b));
+                (self.frame.enu_to_ecef.transpose() * normal_ecef).normalize()
+            }
+        }
+    }
+
+    /// Height of `point_m` above the ground surface
+    pub fn height_above_ground(&self, point_m: DVec3) -> f64 {
+        match self.model {
+            EarthModel::Flat => point_m.z,
+            EarthModel::Sphere => point_m.distance(Self::sphere_center_m()) - EARTH_MEAN_RADIUS_M,
+            EarthModel::Wgs84 => self.frame.enu_to_geodetic(point_m).height_m - self.frame.origin.height_m
+        }
+    }
+
+    /// Gradient with respect to the World plane coordinates (East, North) of a field whose
+    /// 3D gradient is `gradient`, the field being restricted to the ground surface at `point_m`
+    pub fn ground_gradient(&self, point_m: DVec3, gradient: DVec3) -> DVec2 {
+        let normal = self.normal_at(point_m);
+        // The surface Z(E, N) has the gradient -n_EN / n_U
+        gradient.truncate() - gradient.z * normal.truncate() / normal.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geodesy::GeodeticPosition;
+
+    use super::*;
+
+    const NADIR_RAY_ORIGIN_M: DVec3 = DVec3::new(0.0, 0.0, 1000.0);
+
+    fn earth(model: EarthModel) -> Earth {
+        Earth::new(model, &SceneOrigin(GeodeticPosition::new(45.0, 10.0, 0.0)))
+    }
+
+    #[test]
+    fn nadir_ray_hits_the_origin() {
+        for model in [EarthModel::Flat, EarthModel::Sphere, EarthModel::Wgs84] {
+            let earth = earth(model);
+            let point = earth.intersect(NADIR_RAY_ORIGIN_M, DVec3::NEG_Z).unwrap();
+            assert!(point.abs_diff_eq(DVec3::ZERO, 1e-6), "{:?}: {}", model, point);
+            assert!(earth.normal_at(point).abs_diff_eq(DVec3::Z, 1e-9), "{:?}", model);
+            assert!((earth.height_above_ground(NADIR_RAY_ORIGIN_M) - 1000.0).abs() < 1e-6, "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn sphere_drops_below_the_world_plane() {
+        let x = 100_000.0;
+        let point = earth(EarthModel::Sphere).ground_point(DVec2::new(x, 0.0));
+        let drop = (EARTH_MEAN_RADIUS_M * EARTH_MEAN_RADIUS_M - x * x).sqrt() - EARTH_MEAN_RADIUS_M;
+        assert!(point.abs_diff_eq(DVec3::new(x, 0.0, drop), 1e-6));
+    }
+
+    #[test]
+    fn wgs84_ground_points_lie_on_the_ellipsoid() {
+        let earth = earth(EarthModel::Wgs84);
+        for position_m in [DVec2::new(100_000.0, 0.0), DVec2::new(0.0, -100_000.0), DVec2::new(-50_000.0, 70_000.0)] {
+            let point = earth.ground_point(position_m);
+            assert!(earth.frame.enu_to_geodetic(point).height_m.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn upward_ray_misses_the_ground() {
+        for model in [EarthModel::Flat, EarthModel::Sphere, EarthModel::Wgs84] {
+            assert_eq!(earth(model).intersect(NADIR_RAY_ORIGIN_M, DVec3::Z), None, "{:?}", model);
+        }
+    }
+}
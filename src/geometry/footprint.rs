@@ -0,0 +1,414 @@
+use bevy::math::{DVec2, DVec3};
+use std::f64::consts::TAU;
+
+use crate::constants::{FOOTPRINT_MAX_GROUND_RANGE_M, FOOTPRINT_RANGE_PER_HEIGHT};
+
+use super::{
+    Earth,
+    polygon::{convex_hull, convex_polygon_intersection, polygon_centroid, polygon_contains, polygon_signed_area},
+    BistaticGeometry,
+    PlatformGeometry
+};
+
+/// Number of bisection iterations used to locate the footprint clipping points
+const CLIP_BISECTION_ITERATIONS: usize = 40;
+
+/// Ground footprint of an antenna beam: the intersection of the elliptical beam
+/// cone with the ground surface.
+///
+/// On a flat Earth, the footprint is an ellipse when the whole beam points below
+/// the horizon, otherwise a parabola or hyperbola branch which is clipped by a
+/// circle of maximum ground range around the antenna nadir. On a curved Earth,
+/// the footprint is described by its projection on the World plane.
+#[derive(Clone, Debug)]
+pub struct Footprint {
+    /// Footprint polygon vertices (East, North), counterclockwise, not closed
+    pub points: Vec<DVec2>,
+    /// True if the footprint has been clipped at the maximum ground range
+    pub clipped: bool,
+    pub area_m2: f64,
+    /// Minimum and maximum ground ranges from the antenna nadir
+    pub near_ground_range_m: f64,
+    pub far_ground_range_m: f64,
+    /// Minimum and maximum slant ranges from the antenna phase center
+    pub near_slant_range_m: f64,
+    pub far_slant_range_m: f64,
+}
+
+impl Footprint {
+    /// Builds a footprint from its polygon, `antenna_position_m` being used for the extents
+    pub fn from_polygon(mut points: Vec<DVec2>, clipped: bool, antenna_position_m: DVec3, earth: &Earth) -> Self {
+        if polygon_signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+        let nadir = antenna_position_m.truncate();
+        let slant_range = |p: DVec2| earth.ground_point(p).distance(antenna_position_m);
+        let (mut near, far) = points
+            .iter()
+            .map(|&p| (p.distance(nadir), p))
+            .fold(((f64::INFINITY, nadir), (0.0f64, nadir)), |(a, b), d| {
+                (if d.0 < a.0 { d } else { a }, if d.0 > b.0 { d } else { b })
+            });
+        // The nearest point may lie inside the polygon (beam containing the nadir)
+        if polygon_contains(&points, nadir) {
+            near = (0.0, nadir);
+        }
+
+        Self {
+            area_m2: polygon_signed_area(&points),
+            clipped,
+            near_ground_range_m: near.0,
+            far_ground_range_m: far.0,
+            near_slant_range_m: slant_range(near.1),
+            far_slant_range_m: slant_range(far.1),
+            points,
+        }
+    }
+
+    /// Closed ground polyline of the footprint (first point repeated at the end)
+    pub fn polyline(&self, earth: &Earth) -> Vec<DVec3> {
+        self.points
+            .iter()
+            .chain(self.points.first())
+            .map(|&p| earth.ground_point(p))
+            .collect()
+    }
+
+    /// Centroid of the footprint polygon
+    pub fn centroid(&self) -> DVec2 {
+        polygon_centroid(&self.points)
+    }
+
+    /// Common area of two footprints, `None` if they do not overlap.
+    ///
+    /// The footprints of analytic patterns are convex (intersections of a convex beam cone,
+    /// and possibly of the clipping disc, with the ground plane), but the beam contours of
+    /// tabulated cuts and gain grids may be concave. The common area is the intersection
+    /// of the footprints convex hulls: exact for convex footprints, and an outer
+    /// approximation filling the notches of concave ones. It is convex as well, so that
+    /// its mesh is a triangle fan.
+    pub fn intersection(&self, other: &Footprint) -> Option<CommonFootprint> {
+        let points = convex_polygon_intersection(&convex_hull(&self.points), &convex_hull(&other.points));
+        let area_m2 = polygon_signed_area(&points);
+        if points.len() < 3 || area_m2 <= 0.0 {
+            return None;
+        }
+        let (min_m, max_m) = points
+            .iter()
+            .fold((DVec2::INFINITY, DVec2::NEG_INFINITY), |(a, b), &p| (a.min(p), b.max(p)));
+
+        Some(CommonFootprint {
+            centroid_m: polygon_centroid(&points),
+            area_m2,
+            min_m,
+            max_m,
+            points,
+        })
+    }
+}
+
+/// Ground area commonly illuminated by the Tx and Rx antennas beams.
+#[derive(Clone, Debug)]
+pub struct CommonFootprint {
+    /// Common area polygon vertices (East, North), counterclockwise, not closed
+    pub points: Vec<DVec2>,
+    pub area_m2: f64,
+    pub centroid_m: DVec2,
+    /// Bounding box South-West and North-East corners (East, North)
+    pub min_m: DVec2,
+    pub max_m: DVec2,
+}
+
+impl PlatformGeometry {
+    /// Beam contour at the polar angle `t` of the beam cross-section (tangent of its
+    /// off-boresight angle), linearly interpolated between the contour samples
+    fn beam_contour_at(&self, t: f64) -> f64 {
+        let n = self.beam_contour.len();
+        let x = t.rem_euclid(TAU) / TAU * n as f64;
+        let k = x.floor() as usize % n;
+        let f = x - x.floor();
+        (1.0 - f) * self.beam_contour[k] + f * self.beam_contour[(k + 1) % n]
+    }
+
+    /// True if the World direction `direction` lies inside the antenna beam cone
+    pub fn beam_contains(&self, direction: DVec3) -> bool {
+        let d = self.antenna_rotation.inverse() * direction;
+        if d.x <= 0.0 {
+            return false;
+        }
+        let (y, z) = (d.y / d.x, d.z / d.x);
+        y.hypot(z) <= self.beam_contour_at(z.atan2(y))
+    }
+
+    /// World direction of the beam cone edge at the polar angle `t` of the beam cross-section
+    /// (0 along the antenna azimuth axis, π/2 along its elevation axis)
+    #[inline]
+    pub fn beam_edge(&self, t: f64) -> DVec3 {
+        let (sin, cos) = t.sin_cos();
+        let r = self.beam_contour_at(t);
+        self.antenna_rotation * DVec3::new(1.0, r * cos, r * sin)
+    }
+
+    /// Ground range from the antenna nadir at which its footprint is clipped, extended
+    /// with the antenna height so that the footprints of spaceborne antennas fit
+    #[inline]
+    pub fn footprint_clip_range_m(&self, earth: &Earth) -> f64 {
+        FOOTPRINT_MAX_GROUND_RANGE_M
+            .max(FOOTPRINT_RANGE_PER_HEIGHT * earth.height_above_ground(self.antenna_position_m))
+    }
+
+    /// Analytic ground footprint of the antenna beam on the `earth` surface, sampled with
+    /// `segments` edge directions and clipped at `max_ground_range_m` from the antenna nadir.
+    /// Returns `None` if the beam does not illuminate the ground.
+    pub fn footprint(&self, earth: &Earth, max_ground_range_m: f64, segments: usize) -> Option<Footprint> {
+        let origin = self.antenna_position_m;
+        let nadir = origin.truncate();
+        // Ground intersection of the beam edge at `t`, if within the maximum ground range
+        let hit = |t: f64| -> Option<DVec2> {
+            let point = earth.intersect(origin, self.beam_edge(t))?.truncate();
+            (point.distance(nadir) <= max_ground_range_m).then_some(point)
+        };
+        let ts: Vec<f64> = (0..segments).map(|k| TAU * k as f64 / segments as f64).collect();
+        let hits: Vec<Option<DVec2>> = ts.iter().map(|&t| hit(t)).collect();
+
+        let Some(start) = hits.iter().position(Option::is_some) else {
+            // No edge reaches the ground within range: either the beam contains the whole
+            // clipping disc or it does not illuminate the ground at all
+            let below = earth.ground_point(nadir) - origin;
+            if earth.height_above_ground(origin) > 0.0 && self.beam_contains(below) {
+                let circle = (0..segments)
+                    .map(|k| nadir + max_ground_range_m * DVec2::from_angle(TAU * k as f64 / segments as f64))
+                    .collect();
+                return Some(Footprint::from_polygon(circle, true, origin, earth));
+            }
+            return None;
+        };
+
+        let mut points = Vec::with_capacity(segments);
+        let mut clipped = false;
+        let mut exit_angle = 0.0;
+        for i in 0..segments {
+            let k = (start + i) % segments;
+            let next = (k + 1) % segments;
+            let t_k = ts[k];
+            let t_next = if next == 0 { TAU } else { ts[next] };
+            match (hits[k], hits[next]) {
+                (Some(p), None) => { // Leaving the clipping disc
+                    points.push(p);
+                    let (mut a, mut b) = (t_k, t_next);
+                    for _ in 0..CLIP_BISECTION_ITERATIONS {
+                        let m = 0.5 * (a + b);
+                        if hit(m).is_some() { a = m } else { b = m }
+                    }
+                    let exit = hit(a).unwrap_or(p);
+                    points.push(exit);
+                    exit_angle = (exit - nadir).to_angle();
+                    clipped = true;
+                },
+                (None, Some(_)) => { // Entering back the clipping disc
+                    let (mut a, mut b) = (t_k, t_next);
+                    for _ in 0..CLIP_BISECTION_ITERATIONS {
+                        let m = 0.5 * (a + b);
+                        if hit(m).is_some() { b = m } else { a = m }
+                    }
+                    let entry = hit(b).unwrap_or_else(|| hits[next].unwrap());
+                    let entry_angle = (entry - nadir).to_angle();
+                    // Follow the clipping circle along the arc lying inside the beam
+                    let mut sweep = (entry_angle - exit_angle).rem_euclid(TAU);
+                    let mid = nadir + max_ground_range_m * DVec2::from_angle(exit_angle + 0.5 * sweep);
+                    if !self.beam_contains(earth.ground_point(mid) - origin) {
+                        sweep -= TAU;
+                    }
+                    let arc_segments = ((sweep.abs() / TAU * segments as f64).ceil() as usize).max(1);
+                    for j in 1..arc_segments {
+                        let angle = exit_angle + sweep * j as f64 / arc_segments as f64;
+                        points.push(nadir + max_ground_range_m * DVec2::from_angle(angle));
+                    }
+                    points.push(entry);
+                },
+                (Some(p), Some(_)) => points.push(p),
+                (None, None) => {}
+            }
+        }
+
+        (points.len() >= 3).then(|| Footprint::from_polygon(points, clipped, origin, earth))
+    }
+}
+
+impl BistaticGeometry {
+    /// Ground area commonly illuminated by the Tx and Rx beams, `None` if a beam does not
+    /// reach the ground or if the footprints do not overlap. Each footprint is clipped at
+    /// its own `PlatformGeometry::footprint_clip_range_m`.
+    pub fn common_footprint(&self, segments: usize) -> Option<CommonFootprint> {
+        let tx = self.tx.footprint(&self.earth, self.tx.footprint_clip_range_m(&self.earth), segments)?;
+        let rx = self.rx.footprint(&self.earth, self.rx.footprint_clip_range_m(&self.earth), segments)?;
+        tx.intersection(&rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f64::consts::PI, sync::Arc};
+
+    use crate::{
+        constants::FOOTPRINT_SEGMENTS,
+        pattern::{AntennaPattern, GainGrid, GridPattern},
+        state::{AntennaBeamState, AntennaState, CarrierState}
+    };
+
+    use super::*;
+
+    /// Antenna at `position_m` looking North and `elevation_deg` below the horizon, with
+    /// a Gaussian beam of 3 dB widths `widths_deg` (azimuth, elevation) whose cone is
+    /// bounded at exactly half power
+    fn platform(position_m: DVec3, elevation_deg: f64, widths_deg: DVec2) -> PlatformGeometry {
+        platform_with_pattern(position_m, elevation_deg, widths_deg, &AntennaPattern::Gaussian)
+    }
+
+    fn platform_with_pattern(
+        position_m: DVec3,
+        elevation_deg: f64,
+        widths_deg: DVec2,
+        pattern: &AntennaPattern
+    ) -> PlatformGeometry {
+        let carrier = CarrierState {
+            position_m: position_m.truncate(),
+            height_m: position_m.z,
+            ..Default::default()
+        };
+        let antenna = AntennaState {
+            heading_deg: 0.0,
+            elevation_deg: -elevation_deg,
+            bank_deg: 0.0,
+        };
+        let beam = AntennaBeamState {
+            azimuth_beam_width_deg: widths_deg.x,
+            elevation_beam_width_deg: widths_deg.y,
+            cone_level_db: -10.0 * 2f64.log10(),
+            ..Default::default()
+        };
+        PlatformGeometry::new(&carrier, &antenna, &beam, pattern)
+    }
+
+    #[test]
+    fn nadir_footprint() {
+        // Flat Earth: the footprint is about the ellipse of semi-axes h·tan(θ/2) for beam
+        // widths θ, the Gaussian beam edge being an ellipse of angles rather than tangents
+        let height_m = 1000.0;
+        let earth = Earth::default();
+        for widths_deg in [DVec2::splat(4.0), DVec2::new(2.0, 6.0)] {
+            let geometry = platform(DVec3::new(100.0, -200.0, height_m), 90.0, widths_deg);
+            let footprint = geometry.footprint(&earth, 1e5, FOOTPRINT_SEGMENTS).unwrap();
+            let semi_axes_m = height_m * DVec2::new((0.5 * widths_deg.x).to_radians().tan(), (0.5 * widths_deg.y).to_radians().tan());
+            let area_m2 = PI * semi_axes_m.x * semi_axes_m.y;
+            assert!((footprint.area_m2 / area_m2 - 1.0).abs() < 0.002, "{} m² instead of {} m²", footprint.area_m2, area_m2);
+            assert!(footprint.centroid().abs_diff_eq(DVec2::new(100.0, -200.0), 1e-6));
+            assert!(!footprint.clipped);
+            assert_eq!(footprint.near_ground_range_m, 0.0);
+            assert!((footprint.near_slant_range_m - height_m).abs() < 1e-9);
+            let far_m = semi_axes_m.max_element();
+            assert!((footprint.far_ground_range_m / far_m - 1.0).abs() < 0.002, "{}", footprint.far_ground_range_m);
+        }
+    }
+
+    #[test]
+    fn near_horizontal_footprint_is_clipped() {
+        // The upper beam edge points above the horizon
+        let geometry = platform(DVec3::new(0.0, 0.0, 1000.0), 2.0, DVec2::splat(10.0));
+        let earth = Earth::default();
+        let clip_range_m = geometry.footprint_clip_range_m(&earth);
+        assert_eq!(clip_range_m, FOOTPRINT_MAX_GROUND_RANGE_M);
+        for max_ground_range_m in [clip_range_m, 20_000.0] {
+            let footprint = geometry.footprint(&earth, max_ground_range_m, FOOTPRINT_SEGMENTS).unwrap();
+            assert!(footprint.clipped);
+            assert!((footprint.far_ground_range_m - max_ground_range_m).abs() < 1e-6 * max_ground_range_m);
+            assert!(footprint.points.iter().all(|p| p.length() <= max_ground_range_m * (1.0 + 1e-9)));
+            // Lower beam edge 7° below the horizon
+            let near_m = 1000.0 / 7f64.to_radians().tan();
+            assert!((footprint.near_ground_range_m / near_m - 1.0).abs() < 0.01, "{}", footprint.near_ground_range_m);
+            assert!(footprint.area_m2 > 0.0);
+        }
+
+        // Beams missing the ground
+        let upward = platform(DVec3::new(0.0, 0.0, 1000.0), -20.0, DVec2::splat(10.0));
+        assert!(upward.footprint(&earth, clip_range_m, FOOTPRINT_SEGMENTS).is_none());
+    }
+
+    #[test]
+    fn beam_edge_bounds_beam() {
+        let geometry = platform(DVec3::new(0.0, 0.0, 1000.0), 40.0, DVec2::new(12.0, 25.0));
+        assert!(geometry.beam_contains(geometry.boresight()));
+        assert!(!geometry.beam_contains(-geometry.boresight()));
+        for k in 0..36 {
+            let edge = geometry.beam_edge(TAU * k as f64 / 36.0);
+            // Edge directions have a unit component along the boresight
+            let offset = edge - geometry.boresight();
+            assert!(offset.dot(geometry.boresight()).abs() < 1e-12);
+            assert!(geometry.beam_contains(geometry.boresight() + 0.99 * offset), "{}", k);
+            assert!(!geometry.beam_contains(geometry.boresight() + 1.01 * offset), "{}", k);
+        }
+    }
+
+    #[test]
+    fn overlapping_nadir_footprints() {
+        // Two discs of radius r whose centers are r apart: lens of area r²·(2π/3 - √3/2)
+        let height_m = 1000.0;
+        let radius_m = height_m * 10f64.to_radians().tan();
+        let geometry = BistaticGeometry::new(
+            platform(DVec3::new(0.0, 0.0, height_m), 90.0, DVec2::splat(20.0)),
+            platform(DVec3::new(radius_m, 0.0, height_m), 90.0, DVec2::splat(20.0)),
+            Earth::default()
+        );
+        let common = geometry.common_footprint(FOOTPRINT_SEGMENTS).unwrap();
+        let area_m2 = radius_m * radius_m * (2.0 * PI / 3.0 - 0.75f64.sqrt());
+        assert!((common.area_m2 / area_m2 - 1.0).abs() < 0.02, "{} m² instead of {} m²", common.area_m2, area_m2);
+        assert!(common.centroid_m.abs_diff_eq(DVec2::new(0.5 * radius_m, 0.0), 1e-6 * radius_m));
+        assert!((common.max_m.x - radius_m).abs() < 0.01 * radius_m && common.min_m.x.abs() < 0.01 * radius_m);
+
+        let apart = BistaticGeometry::new(
+            platform(DVec3::new(0.0, 0.0, height_m), 90.0, DVec2::splat(20.0)),
+            platform(DVec3::new(3.0 * radius_m, 0.0, height_m), 90.0, DVec2::splat(20.0)),
+            Earth::default()
+        );
+        assert!(apart.common_footprint(FOOTPRINT_SEGMENTS).is_none());
+    }
+
+    #[test]
+    fn concave_grid_footprints() {
+        // Plus-shaped measured beam: arms 4° wide and 20° long at 0 dB, -20 dB elsewhere
+        let angles = (-15..=15).map(|k| k.to_string()).collect::<Vec<_>>().join(" ");
+        let mut matrix = format!("azimuth {}\nelevation {}\n", angles, angles);
+        for el in -15..=15i32 {
+            let row = (-15..=15i32).map(|az| {
+                let arm = (az.abs() <= 2 && el.abs() <= 10) || (el.abs() <= 2 && az.abs() <= 10);
+                if arm { "0" } else { "-20" }
+            });
+            matrix += &row.collect::<Vec<_>>().join(" ");
+            matrix += "\n";
+        }
+        let pattern = AntennaPattern::Grid(GridPattern {
+            path: "plus.txt".into(),
+            grid: Arc::new(GainGrid::from_matrix_str(&matrix).unwrap()),
+        });
+        let height_m = 1000.0;
+        let platform = || platform_with_pattern(DVec3::new(0.0, 0.0, height_m), 90.0, DVec2::splat(4.0), &pattern);
+
+        // The footprint follows the notches between the arms
+        let footprint = platform().footprint(&Earth::default(), 1e5, FOOTPRINT_SEGMENTS).unwrap();
+        let hull_area_m2 = polygon_signed_area(&convex_hull(&footprint.points));
+        assert!(footprint.area_m2 < 0.8 * hull_area_m2, "{} m² for a hull of {} m²", footprint.area_m2, hull_area_m2);
+        let arm_tip_m = 0.9 * height_m * 10f64.to_radians().tan();
+        assert!(polygon_contains(&footprint.points, DVec2::new(arm_tip_m, 0.0)));
+        assert!(polygon_contains(&footprint.points, DVec2::new(0.0, -arm_tip_m)));
+        let notch = DVec2::splat(0.5 * arm_tip_m);
+        assert!(!polygon_contains(&footprint.points, notch));
+
+        // The common area of identical beams is the footprint convex hull
+        let common = BistaticGeometry::new(platform(), platform(), Earth::default())
+            .common_footprint(FOOTPRINT_SEGMENTS)
+            .unwrap();
+        assert!((common.area_m2 / hull_area_m2 - 1.0).abs() < 1e-9, "{} m² instead of {} m²", common.area_m2, hull_area_m2);
+        assert!((polygon_signed_area(&convex_hull(&common.points)) / common.area_m2 - 1.0).abs() < 1e-9);
+        assert!(polygon_contains(&common.points, notch));
+    }
+}
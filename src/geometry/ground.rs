@@ -0,0 +1,32 @@
+use bevy::math::DVec3;
+
+/// Intersection of the ray starting at `origin_m` along `direction` with the
+/// ground plane (Z = 0). Returns `None` if the ray does not reach the ground.
+pub fn intersect_ground(origin_m: DVec3, direction: DVec3) -> Option<DVec3> {
+    if direction.z == 0.0 {
+        return None;
+    }
+    let t = -origin_m.z / direction.z;
+    if t < 0.0 {
+        return None;
+    }
+    let point = origin_m + t * direction;
+    Some(DVec3::new(point.x, point.y, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_towards_the_ground() {
+        let point = intersect_ground(DVec3::new(10.0, -20.0, 300.0), DVec3::new(1.0, 0.0, -1.0)).unwrap();
+        assert!(point.abs_diff_eq(DVec3::new(310.0, -20.0, 0.0), 1e-9));
+    }
+
+    #[test]
+    fn ray_missing_the_ground() {
+        assert_eq!(intersect_ground(DVec3::new(0.0, 0.0, 300.0), DVec3::Z), None);
+        assert_eq!(intersect_ground(DVec3::new(0.0, 0.0, 300.0), DVec3::X), None);
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::math::{DQuat, DVec2, DVec3, EulerRot};
+
+use crate::{
+    constants::{BEAM_CONTOUR_SEGMENTS, ENU_TO_NED_DROT},
+    pattern::AntennaPattern,
+    state::{AntennaBeamState, AntennaState, CarrierState}
+};
+
+use super::db_to_linear;
+
+/// Position and orientation of a carrier and of its antenna in World frame.
+#[derive(Clone, Debug)]
+pub struct PlatformGeometry {
+    /// Carrier reference point position in World frame
+    pub carrier_position_m: DVec3,
+    /// Carrier orientation in World frame (rotation from World axes to Carrier NED axes)
+    pub carrier_rotation: DQuat,
+    /// Antenna phase center position in World frame
+    pub antenna_position_m: DVec3,
+    /// Antenna orientation in World frame (X: boresight, Y: azimuth, Z: elevation)
+    pub antenna_rotation: DQuat,
+    /// Carrier (and Antenna phase center) velocity in World frame
+    pub velocity_m_s: DVec3,
+    /// Antenna beam half 3 dB widths
+    pub azimuth_half_beam_width_rad: f64,
+    pub elevation_half_beam_width_rad: f64,
+    /// Antenna peak gain (linear)
+    pub peak_gain: f64,
+    pub pattern: AntennaPattern,
+    /// Beam edge at the antenna cone level, as the tangents of its off-boresight angles
+    /// at `BEAM_CONTOUR_SEGMENTS` regularly spaced polar angles of the beam cross-section
+    pub beam_contour: Vec<f64>,
+}
+
+impl PlatformGeometry {
+    pub fn new(
+        carrier: &CarrierState,
+        antenna: &AntennaState,
+        beam: &AntennaBeamState,
+        pattern: &AntennaPattern
+    ) -> Self {
+        let half_beam_widths_rad = 0.5 * DVec2::new(
+            beam.azimuth_beam_width_deg.to_radians(),
+            beam.elevation_beam_width_deg.to_radians()
+        );
+
+        let mut geometry = Self {
+            carrier_position_m: DVec3::ZERO,
+            carrier_rotation: DQuat::IDENTITY,
+            antenna_position_m: DVec3::ZERO,
+            antenna_rotation: DQuat::IDENTITY,
+            velocity_m_s: DVec3::ZERO,
+            azimuth_half_beam_width_rad: half_beam_widths_rad.x,
+            elevation_half_beam_width_rad: half_beam_widths_rad.y,
+            peak_gain: db_to_linear(beam.gain_dbi),
+            beam_contour: pattern.beam_contour(half_beam_widths_rad, beam.cone_level_db, BEAM_CONTOUR_SEGMENTS),
+            pattern: pattern.clone(),
+        };
+        geometry.set_carrier(carrier, antenna);
+        geometry
+    }
+
+    /// Moves the platform to the carrier state `carrier`, its antenna being mounted as
+    /// `antenna`. The beam and pattern are kept, which avoids recomputing the beam contour
+    /// along a carrier motion.
+    pub fn set_carrier(&mut self, carrier: &CarrierState, antenna: &AntennaState) {
+        self.carrier_position_m = carrier.position_enu_m();
+        self.carrier_rotation = *ENU_TO_NED_DROT * DQuat::from_euler(
+            EulerRot::ZYX,
+            carrier.heading_deg.to_radians(),
+            carrier.elevation_deg.to_radians(),
+            carrier.bank_deg.to_radians()
+        );
+        self.antenna_position_m = self.carrier_position_m + self.carrier_rotation * carrier.lever_arms_m;
+        self.antenna_rotation = self.carrier_rotation * DQuat::from_euler(
+            EulerRot::ZYX,
+            antenna.heading_deg.to_radians(),
+            antenna.elevation_deg.to_radians(),
+            antenna.bank_deg.to_radians()
+        );
+        self.velocity_m_s = carrier.velocity_m_s;
+    }
+
+    /// Antenna beam half 3 dB widths (x: azimuth, y: elevation)
+    #[inline]
+    pub fn half_beam_widths_rad(&self) -> DVec2 {
+        DVec2::new(self.azimuth_half_beam_width_rad, self.elevation_half_beam_width_rad)
+    }
+
+    /// Antenna pointing direction (beam axis) in World frame
+    #[inline]
+    pub fn boresight(&self) -> DVec3 {
+        self.antenna_rotation * DVec3::X
+    }
+
+    /// Antenna azimuth axis in World frame (direction swept by the azimuth beam width)
+    #[inline]
+    pub fn azimuth_axis(&self) -> DVec3 {
+        self.antenna_rotation * DVec3::Y
+    }
+
+    /// Antenna elevation axis in World frame (direction swept by the elevation beam width)
+    #[inline]
+    pub fn elevation_axis(&self) -> DVec3 {
+        self.antenna_rotation * DVec3::Z
+    }
+
+    /// Antenna orientation relative to the Carrier frame
+    #[inline]
+    pub fn antenna_local_rotation(&self) -> DQuat {
+        self.carrier_rotation.inverse() * self.antenna_rotation
+    }
+
+    /// Antenna phase center position relative to the Carrier frame (lever arms)
+    #[inline]
+    pub fn antenna_local_position_m(&self) -> DVec3 {
+        self.carrier_rotation.inverse() * (self.antenna_position_m - self.carrier_position_m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn antenna_pose_from_carrier_state() {
+        // Carrier heading East, the antenna looking right (South) and 60° down
+        let carrier = CarrierState {
+            heading_deg: 90.0,
+            height_m: 1000.0,
+            position_m: DVec2::new(100.0, 200.0),
+            velocity_m_s: DVec3::new(100.0, 0.0, 0.0),
+            lever_arms_m: DVec3::new(2.0, 0.0, 1.0),
+            ..Default::default()
+        };
+        let antenna = AntennaState {
+            heading_deg: 90.0,
+            elevation_deg: -60.0,
+            bank_deg: 0.0,
+        };
+        let geometry = PlatformGeometry::new(&carrier, &antenna, &AntennaBeamState::default(), &AntennaPattern::default());
+
+        assert_eq!(geometry.carrier_position_m, DVec3::new(100.0, 200.0, 1000.0));
+        // Lever arms 2 m forward (East) and 1 m down
+        assert!(geometry.antenna_position_m.abs_diff_eq(DVec3::new(102.0, 200.0, 999.0), 1e-9));
+        let (sin, cos) = 60f64.to_radians().sin_cos();
+        assert!(geometry.boresight().abs_diff_eq(DVec3::new(0.0, -cos, -sin), 1e-12));
+        // Right-handed antenna axes: azimuth to the right of the boresight (West)
+        assert!(geometry.azimuth_axis().abs_diff_eq(DVec3::NEG_X, 1e-12));
+        assert!(geometry.elevation_axis().abs_diff_eq(DVec3::new(0.0, sin, -cos), 1e-12));
+        assert_eq!(geometry.velocity_m_s, carrier.velocity_m_s);
+
+        assert!(geometry.antenna_local_position_m().abs_diff_eq(carrier.lever_arms_m, 1e-9));
+        let local = DQuat::from_euler(EulerRot::ZYX, FRAC_PI_2, -60f64.to_radians(), 0.0);
+        assert!(geometry.antenna_local_rotation().abs_diff_eq(local, 1e-12));
+        let beam_widths_rad = 2.0 * geometry.half_beam_widths_rad();
+        assert!((beam_widths_rad.x.to_degrees() - AntennaBeamState::default().azimuth_beam_width_deg).abs() < 1e-12);
+        assert!((beam_widths_rad.y.to_degrees() - AntennaBeamState::default().elevation_beam_width_deg).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::math::DVec2;
+
+/// Signed area of a polygon (positive when counterclockwise)
+pub fn polygon_signed_area(points: &[DVec2]) -> f64 {
+    let n = points.len();
+    0.5 * (0..n)
+        .map(|i| points[i].perp_dot(points[(i + 1) % n]))
+        .sum::<f64>()
+}
+
+/// Centroid of a simple polygon
+pub fn polygon_centroid(points: &[DVec2]) -> DVec2 {
+    let n = points.len();
+    let area = polygon_signed_area(points);
+    if area == 0.0 {
+        return points.iter().sum::<DVec2>() / n.max(1) as f64;
+    }
+    let sum: DVec2 = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            (a + b) * a.perp_dot(b)
+        })
+        .sum();
+    sum / (6.0 * area)
+}
+
+/// Even-odd rule point in polygon test
+pub fn polygon_contains(points: &[DVec2], point: DVec2) -> bool {
+    let n = points.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Convex hull of `points`, counterclockwise, without collinear points (monotone chain
+/// algorithm)
+pub fn convex_hull(points: &[DVec2]) -> Vec<DVec2> {
+    // Appends `p` to the chain starting at `start`, first dropping its last points while
+    // they do not turn left
+    fn push(hull: &mut Vec<DVec2>, start: usize, p: DVec2) {
+        while hull.len() >= start + 2 {
+            let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+            if (b - a).perp_dot(p - b) > 0.0 {
+                break;
+            }
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    // Lower chain from left to right, then upper chain back to the first point
+    let mut hull = Vec::with_capacity(sorted.len() + 1);
+    for &p in &sorted {
+        push(&mut hull, 0, p);
+    }
+    let upper = hull.len() - 1;
+    for &p in sorted.iter().rev().skip(1) {
+        push(&mut hull, upper, p);
+    }
+    hull.pop();
+    hull
+}
+
+/// Intersection of the `subject` polygon with the convex `clip` polygon (Sutherland-Hodgman
+/// algorithm), counterclockwise whatever the orientation of the inputs
+pub fn convex_polygon_intersection(subject: &[DVec2], clip: &[DVec2]) -> Vec<DVec2> {
+    let mut output = subject.to_vec();
+    let n = clip.len();
+    let orientation = polygon_signed_area(clip).signum();
+    for i in 0..n {
+        if output.is_empty() {
+            break;
+        }
+        let (a, b) = (clip[i], clip[(i + 1) % n]);
+        let edge = b - a;
+        // Positive on the inner side of the clip edge (left when counterclockwise)
+        let side = |p: DVec2| orientation * edge.perp_dot(p - a);
+        let input = std::mem::take(&mut output);
+        for j in 0..input.len() {
+            let (p, q) = (input[j], input[(j + 1) % input.len()]);
+            let (sp, sq) = (side(p), side(q));
+            if sp >= 0.0 {
+                output.push(p);
+            }
+            if (sp >= 0.0) != (sq >= 0.0) {
+                output.push(p + (q - p) * (sp / (sp - sq)));
+            }
+        }
+    }
+    if polygon_signed_area(&output) < 0.0 {
+        output.reverse();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: DVec2, size: f64) -> Vec<DVec2> {
+        vec![min, min + DVec2::new(size, 0.0), min + DVec2::splat(size), min + DVec2::new(0.0, size)]
+    }
+
+    fn reversed(points: &[DVec2]) -> Vec<DVec2> {
+        points.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn square_area_and_centroid() {
+        let unit = square(DVec2::ZERO, 1.0);
+        assert_eq!(polygon_signed_area(&unit), 1.0);
+        assert_eq!(polygon_centroid(&unit), DVec2::splat(0.5));
+        assert_eq!(polygon_signed_area(&reversed(&unit)), -1.0);
+        assert_eq!(polygon_centroid(&reversed(&unit)), DVec2::splat(0.5));
+
+        let offset = square(DVec2::new(10.0, -4.0), 2.0);
+        assert_eq!(polygon_signed_area(&offset), 4.0);
+        assert!(polygon_centroid(&offset).abs_diff_eq(DVec2::new(11.0, -3.0), 1e-12));
+        // Degenerate polygons fall back to the mean of their points
+        assert_eq!(polygon_centroid(&[DVec2::ZERO, DVec2::X, DVec2::new(2.0, 0.0)]), DVec2::X);
+    }
+
+    #[test]
+    fn point_in_concave_polygon() {
+        // L shape, missing the upper right quarter of the [0, 2]² square
+        let shape = [
+            DVec2::ZERO,
+            DVec2::new(2.0, 0.0),
+            DVec2::new(2.0, 1.0),
+            DVec2::new(1.0, 1.0),
+            DVec2::new(1.0, 2.0),
+            DVec2::new(0.0, 2.0),
+        ];
+        assert_eq!(polygon_signed_area(&shape), 3.0);
+        assert!(polygon_centroid(&shape).abs_diff_eq(DVec2::splat(5.0 / 6.0), 1e-12));
+        assert!(polygon_contains(&shape, DVec2::splat(0.5)));
+        assert!(polygon_contains(&shape, DVec2::new(1.5, 0.5)));
+        assert!(!polygon_contains(&shape, DVec2::splat(1.5)));
+        assert!(!polygon_contains(&shape, DVec2::new(-0.5, 0.5)));
+    }
+
+    #[test]
+    fn offset_squares_intersection() {
+        let (a, b) = (square(DVec2::ZERO, 1.0), square(DVec2::splat(0.5), 1.0));
+        for common in [convex_polygon_intersection(&a, &b), convex_polygon_intersection(&b, &a)] {
+            assert!((polygon_signed_area(&common) - 0.25).abs() < 1e-12);
+            assert!(polygon_centroid(&common).abs_diff_eq(DVec2::splat(0.75), 1e-12));
+        }
+    }
+
+    #[test]
+    fn disjoint_and_contained_intersections() {
+        let a = square(DVec2::ZERO, 1.0);
+        assert_eq!(polygon_signed_area(&convex_polygon_intersection(&a, &square(DVec2::new(3.0, 0.0), 1.0))), 0.0);
+
+        let inner = square(DVec2::splat(0.25), 0.5);
+        for common in [convex_polygon_intersection(&a, &inner), convex_polygon_intersection(&inner, &a)] {
+            assert!((polygon_signed_area(&common) - 0.25).abs() < 1e-12);
+            assert!(polygon_centroid(&common).abs_diff_eq(DVec2::splat(0.5), 1e-12));
+        }
+    }
+
+    #[test]
+    fn hull_of_concave_polygon() {
+        // L shape and inner points: the hull drops the notch corner and the collinear points
+        let points = [
+            DVec2::ZERO,
+            DVec2::new(1.0, 0.0),
+            DVec2::new(2.0, 0.0),
+            DVec2::new(2.0, 1.0),
+            DVec2::new(1.0, 1.0),
+            DVec2::new(1.0, 2.0),
+            DVec2::new(0.0, 2.0),
+            DVec2::splat(0.5),
+            DVec2::ZERO,
+        ];
+        let hull = convex_hull(&points);
+        assert_eq!(hull, vec![DVec2::ZERO, DVec2::new(2.0, 0.0), DVec2::new(2.0, 1.0), DVec2::new(1.0, 2.0), DVec2::new(0.0, 2.0)]);
+        assert_eq!(polygon_signed_area(&hull), 3.5);
+        assert_eq!(convex_hull(&reversed(&hull)), hull);
+        assert_eq!(convex_hull(&[DVec2::X, DVec2::ZERO]), vec![DVec2::ZERO, DVec2::X]);
+    }
+
+    #[test]
+    fn clockwise_intersection() {
+        let (a, b) = (square(DVec2::ZERO, 1.0), square(DVec2::splat(0.5), 1.0));
+        // The result is counterclockwise whatever the orientation of the inputs
+        for (subject, clip) in [(reversed(&a), b.clone()), (a.clone(), reversed(&b)), (reversed(&a), reversed(&b))] {
+            let common = convex_polygon_intersection(&subject, &clip);
+            assert!((polygon_signed_area(&common) - 0.25).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::math::{DVec2, DVec3};
+use std::f64::consts::PI;
+
+use crate::{
+    constants::{BOLTZMANN_CONSTANT_J_K, REFERENCE_NOISE_TEMPERATURE_K},
+    state::{LinkBudgetState, SignalState}
+};
+
+use super::{BistaticGeometry, PlatformGeometry};
+
+/// Linear power ratio of `db` decibels
+#[inline]
+pub fn db_to_linear(db: f64) -> f64 {
+    10f64.powf(0.1 * db)
+}
+
+/// Decibels of the linear power ratio `value`
+#[inline]
+pub fn linear_to_db(value: f64) -> f64 {
+    10.0 * value.log10()
+}
+
+impl PlatformGeometry {
+    /// One-way antenna power gain (linear) in the World direction `direction`, null
+    /// behind the antenna
+    pub fn gain(&self, direction: DVec3) -> f64 {
+        let d = self.antenna_rotation.inverse() * direction;
+        if d.x <= 0.0 {
+            return 0.0;
+        }
+        let angles_rad = DVec2::new(d.y.atan2(d.x), d.z.atan2(d.x));
+        self.peak_gain * self.pattern.relative_gain(self.half_beam_widths_rad(), angles_rad)
+    }
+}
+
+impl BistaticGeometry {
+    /// Two-way (Tx times Rx) antenna power gain (linear) at `point_m`
+    pub fn two_way_gain(&self, point_m: DVec3) -> f64 {
+        self.tx.gain(point_m - self.tx.antenna_position_m) * self.rx.gain(point_m - self.rx.antenna_position_m)
+    }
+
+    /// Peak power received by Rx from a point target of radar cross-section `rcs_m2` at
+    /// `point_m`, from the bistatic radar equation:
+    ///
+    /// P_r = P · G_tx · G_rx · λ² · σ / ((4π)³ · R_tx² · R_rx² · L)
+    pub fn echo_power_w(&self, point_m: DVec3, rcs_m2: f64, signal: &SignalState, budget: &LinkBudgetState) -> f64 {
+        let wavelength_m = signal.wavelength_m();
+        budget.transmit_power_w * self.two_way_gain(point_m) * wavelength_m * wavelength_m * rcs_m2
+            / ((4.0 * PI).powi(3)
+                * point_m.distance_squared(self.tx.antenna_position_m)
+                * point_m.distance_squared(self.rx.antenna_position_m)
+                * db_to_linear(budget.losses_db))
+    }
+
+    /// Noise-equivalent sigma zero (linear) at `point_m`, from the bistatic radar equation
+    /// after range compression and azimuth integration:
+    ///
+    /// NESZ = (4π)³ · R_tx² · R_rx² · k · T0 · F · L / (P · d · T_int · G_tx · G_rx · λ² · A)
+    ///
+    /// with P the peak power, d the duty cycle and A the resolution cell area.
+    /// `None` where the resolution cell is undefined or a point is out of an antenna pattern.
+    pub fn nesz_at(&self, point_m: DVec3, signal: &SignalState, budget: &LinkBudgetState) -> Option<f64> {
+        let cell_area_m2 = self.resolution_at(point_m, signal).ellipse?.area_m2();
+        let gains = self.two_way_gain(point_m);
+        let wavelength_m = signal.wavelength_m();
+        let received_energy = budget.transmit_power_w
+            * signal.duty_cycle()
+            * signal.integration_time_s
+            * gains
+            * wavelength_m * wavelength_m
+            * cell_area_m2;
+        if received_energy <= 0.0 {
+            return None;
+        }
+        let noise = (4.0 * PI).powi(3)
+            * point_m.distance_squared(self.tx.antenna_position_m)
+            * point_m.distance_squared(self.rx.antenna_position_m)
+            * BOLTZMANN_CONSTANT_J_K
+            * REFERENCE_NOISE_TEMPERATURE_K
+            * db_to_linear(budget.noise_figure_db + budget.losses_db);
+        Some(noise / received_energy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::SPEED_OF_LIGHT_M_S,
+        geometry::Earth,
+        state::{AntennaBeamState, AntennaState, CarrierState}
+    };
+
+    use super::*;
+
+    #[test]
+    fn db_conversions() {
+        assert!((db_to_linear(-10.0) - 0.1).abs() < 1e-15);
+        assert!((db_to_linear(10.0 * 2f64.log10()) - 2.0).abs() < 1e-15);
+        for value in [1e-12, 0.5, 1.0, 3.0, 1e9] {
+            assert!((db_to_linear(linear_to_db(value)) / value - 1.0).abs() < 1e-12);
+        }
+        for db in [-120.0, -3.0, 0.0, 18.0] {
+            assert!((linear_to_db(db_to_linear(db)) - db).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn monostatic_radar_equation() {
+        // Side-looking platform flying North, whose boresight hits the origin at 45° incidence
+        let carrier = CarrierState {
+            position_m: DVec2::new(-3000.0, 0.0),
+            height_m: 3000.0,
+            velocity_m_s: DVec3::new(0.0, 100.0, 0.0),
+            ..Default::default()
+        };
+        let antenna = AntennaState { elevation_deg: -45.0, ..Default::default() };
+        let beam = AntennaBeamState::default();
+        let platform = PlatformGeometry::new(&carrier, &antenna, &beam, &Default::default());
+        let geometry = BistaticGeometry::new(platform.clone(), platform, Earth::default());
+        let signal = SignalState::default();
+        let budget = LinkBudgetState::default();
+
+        let range_m = 3000.0 * 2f64.sqrt();
+        let wavelength_m = SPEED_OF_LIGHT_M_S / signal.carrier_frequency_hz;
+        let two_way_gain = db_to_linear(2.0 * beam.gain_dbi);
+        let losses = db_to_linear(budget.losses_db);
+        assert!((geometry.two_way_gain(DVec3::ZERO) / two_way_gain - 1.0).abs() < 1e-12);
+
+        let echo_power_w = budget.transmit_power_w * two_way_gain * wavelength_m.powi(2) * 2.0
+            / ((4.0 * PI).powi(3) * range_m.powi(4) * losses);
+        let power_w = geometry.echo_power_w(DVec3::ZERO, 2.0, &signal, &budget);
+        assert!((power_w / echo_power_w - 1.0).abs() < 1e-12, "{} W instead of {} W", power_w, echo_power_w);
+
+        // Ground range resolution c/(B·2·sin 45°), azimuth resolution λ·R/(2·v·T_int), and
+        // resolution ellipse of these diameters
+        let range_resolution_m = SPEED_OF_LIGHT_M_S / (signal.bandwidth_hz * 2f64.sqrt());
+        let azimuth_resolution_m = wavelength_m * range_m / (2.0 * 100.0 * signal.integration_time_s);
+        let cell_area_m2 = 0.25 * PI * range_resolution_m * azimuth_resolution_m;
+        let duty_cycle = signal.pulse_duration_s * signal.prf_hz;
+        let nesz = (4.0 * PI).powi(3) * range_m.powi(4) * BOLTZMANN_CONSTANT_J_K * REFERENCE_NOISE_TEMPERATURE_K
+            * db_to_linear(budget.noise_figure_db) * losses
+            / (budget.transmit_power_w * duty_cycle * signal.integration_time_s
+                * two_way_gain * wavelength_m.powi(2) * cell_area_m2);
+        let computed = geometry.nesz_at(DVec3::ZERO, &signal, &budget).unwrap();
+        assert!((computed / nesz - 1.0).abs() < 1e-9, "{} dB instead of {} dB", linear_to_db(computed), linear_to_db(nesz));
+
+        // Behind the antenna
+        let behind_m = DVec3::new(-9000.0, 0.0, 0.0);
+        assert_eq!(geometry.echo_power_w(behind_m, 1.0, &signal, &budget), 0.0);
+        assert_eq!(geometry.nesz_at(behind_m, &signal, &budget), None);
+    }
+}
@@ -0,0 +1,216 @@
+use bevy::math::{DMat2, DVec2, DVec3};
+use std::f64::consts::{PI, TAU};
+
+use crate::state::SignalState;
+
+use super::BistaticGeometry;
+
+/// Bistatic resolutions at a ground point.
+#[derive(Clone, Copy, Debug)]
+pub struct BistaticResolution {
+    /// Ground-projected gradient of the range sum R_tx + R_rx (m/m)
+    pub range_gradient: DVec2,
+    /// Ground-projected gradient of the bistatic Doppler frequency (Hz/m)
+    pub doppler_gradient_hz_m: DVec2,
+    /// Ground range resolution: c / (B · |∇(R_tx + R_rx)|)
+    pub ground_range_resolution_m: f64,
+    /// Doppler resolution: 1 / T_int
+    pub doppler_resolution_hz: f64,
+    /// Ground Doppler resolution: 1 / (T_int · |∇f_D|)
+    pub ground_doppler_resolution_m: f64,
+    /// Angle between the range and Doppler ground gradients
+    pub gradients_angle_rad: f64,
+    /// Resolution cell ellipse, `None` if range and Doppler gradients are colinear
+    pub ellipse: Option<ResolutionEllipse>,
+}
+
+/// Ground resolution cell ellipse.
+///
+/// The ellipse is the image of the circle of diameter 1 in the (B/c · ∇R, T_int · ∇f_D)
+/// space, so that its widths along the range and Doppler gradients are the
+/// ground range and Doppler resolutions.
+#[derive(Clone, Copy, Debug)]
+pub struct ResolutionEllipse {
+    pub center_m: DVec3,
+    pub semi_major_axis_m: f64,
+    pub semi_minor_axis_m: f64,
+    /// Angle of the major axis from East (counterclockwise)
+    pub orientation_rad: f64,
+}
+
+impl ResolutionEllipse {
+    #[inline]
+    pub fn area_m2(&self) -> f64 {
+        PI * self.semi_major_axis_m * self.semi_minor_axis_m
+    }
+
+    /// Closed polyline of the ellipse on the ground (first point repeated at the end)
+    pub fn points(&self, segments: usize) -> Vec<DVec3> {
+        let (sin_o, cos_o) = self.orientation_rad.sin_cos();
+        let major = DVec3::new(cos_o, sin_o, 0.0) * self.semi_major_axis_m;
+        let minor = DVec3::new(-sin_o, cos_o, 0.0) * self.semi_minor_axis_m;
+        (0..=segments)
+            .map(|i| {
+                let (sin, cos) = (TAU * i as f64 / segments as f64).sin_cos();
+                self.center_m + cos * major + sin * minor
+            })
+            .collect()
+    }
+}
+
+impl BistaticGeometry {
+    /// Bistatic range sum R_tx + R_rx at `point_m`
+    pub fn range_sum_m(&self, point_m: DVec3) -> f64 {
+        point_m.distance(self.tx.antenna_position_m) + point_m.distance(self.rx.antenna_position_m)
+    }
+
+    /// Gradient of the range sum R_tx + R_rx at `point_m` (m/m)
+    pub fn range_sum_gradient(&self, point_m: DVec3) -> DVec3 {
+        (point_m - self.tx.antenna_position_m).normalize()
+            + (point_m - self.rx.antenna_position_m).normalize()
+    }
+
+    /// Bistatic Doppler frequency at `point_m`: f_D = -(1/λ)·d(R_tx + R_rx)/dt
+    pub fn doppler_hz(&self, point_m: DVec3, wavelength_m: f64) -> f64 {
+        let u_tx = (point_m - self.tx.antenna_position_m).normalize();
+        let u_rx = (point_m - self.rx.antenna_position_m).normalize();
+        (u_tx.dot(self.tx.velocity_m_s) + u_rx.dot(self.rx.velocity_m_s)) / wavelength_m
+    }
+
+    /// Gradient of the bistatic Doppler frequency at `point_m` (Hz/m)
+    pub fn doppler_gradient_hz_m(&self, point_m: DVec3, wavelength_m: f64) -> DVec3 {
+        let gradient = |position_m: DVec3, velocity_m_s: DVec3| {
+            let los = point_m - position_m;
+            let range = los.length();
+            let u = los / range;
+            (velocity_m_s - u.dot(velocity_m_s) * u) / range
+        };
+        (gradient(self.tx.antenna_position_m, self.tx.velocity_m_s)
+            + gradient(self.rx.antenna_position_m, self.rx.velocity_m_s)) / wavelength_m
+    }
+
+    /// Ground range and Doppler resolutions at `point_m` for the transmitted `signal`
+    pub fn resolution_at(&self, point_m: DVec3, signal: &SignalState) -> BistaticResolution {
+        // Gradients of the fields restricted to the ground surface
+        let range_gradient = self.earth.ground_gradient(point_m, self.range_sum_gradient(point_m));
+        let doppler_gradient_hz_m = self.earth.ground_gradient(
+            point_m,
+            self.doppler_gradient_hz_m(point_m, signal.wavelength_m())
+        );
+
+        // Rows of M map a ground offset to (range sum, Doppler) offsets in resolution units
+        let range_row = range_gradient / signal.range_sum_resolution_m();
+        let doppler_row = signal.integration_time_s * doppler_gradient_hz_m;
+        let m = DMat2::from_cols(range_row, doppler_row).transpose();
+        let det = m.determinant();
+        let ellipse = if det.abs() > 1e-12 * range_row.length() * doppler_row.length() {
+            let a = m.inverse();
+            let s = a * a.transpose(); // Symmetric: the ellipse is { 0.5·A·u, |u| = 1 }
+            let trace = s.x_axis.x + s.y_axis.y;
+            let delta = (trace * trace - 4.0 * s.determinant()).max(0.0).sqrt();
+            Some(ResolutionEllipse {
+                center_m: point_m,
+                semi_major_axis_m: 0.5 * (0.5 * (trace + delta)).sqrt(),
+                semi_minor_axis_m: 0.5 * (0.5 * (trace - delta)).max(0.0).sqrt(),
+                orientation_rad: 0.5 * (2.0 * s.x_axis.y).atan2(s.x_axis.x - s.y_axis.y),
+            })
+        } else {
+            None
+        };
+
+        BistaticResolution {
+            range_gradient,
+            doppler_gradient_hz_m,
+            ground_range_resolution_m: signal.range_sum_resolution_m() / range_gradient.length(),
+            doppler_resolution_hz: 1.0 / signal.integration_time_s,
+            ground_doppler_resolution_m: 1.0
+                / (signal.integration_time_s * doppler_gradient_hz_m.length()),
+            gradients_angle_rad: range_gradient.angle_between(doppler_gradient_hz_m).abs(),
+            ellipse,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::{
+        constants::SPEED_OF_LIGHT_M_S,
+        geometry::{Earth, PlatformGeometry},
+        state::{AntennaBeamState, AntennaState, CarrierState}
+    };
+
+    use super::*;
+
+    /// Finite difference step
+    const STEP_M: f64 = 1e-2;
+
+    fn platform(position_m: DVec3, velocity_m_s: DVec3) -> PlatformGeometry {
+        let carrier = CarrierState {
+            position_m: position_m.truncate(),
+            height_m: position_m.z,
+            velocity_m_s,
+            ..Default::default()
+        };
+        PlatformGeometry::new(&carrier, &AntennaState::default(), &AntennaBeamState::default(), &Default::default())
+    }
+
+    /// Central finite difference gradient of `field` at `point_m`
+    fn numerical_gradient(field: impl Fn(DVec3) -> f64, point_m: DVec3) -> DVec3 {
+        let derivative = |axis: DVec3| (field(point_m + STEP_M * axis) - field(point_m - STEP_M * axis)) / (2.0 * STEP_M);
+        DVec3::new(derivative(DVec3::X), derivative(DVec3::Y), derivative(DVec3::Z))
+    }
+
+    fn bistatic() -> BistaticGeometry {
+        BistaticGeometry::new(
+            platform(DVec3::new(-2000.0, -500.0, 1500.0), DVec3::new(0.0, 100.0, 0.0)),
+            platform(DVec3::new(1500.0, 800.0, 800.0), DVec3::new(30.0, -80.0, 5.0)),
+            Earth::default()
+        )
+    }
+
+    #[test]
+    fn range_sum_gradient_matches_finite_differences() {
+        let geometry = bistatic();
+        let point_m = DVec3::new(200.0, 300.0, 0.0);
+        let expected = numerical_gradient(|point| geometry.range_sum_m(point), point_m);
+        assert!(geometry.range_sum_gradient(point_m).abs_diff_eq(expected, 1e-7));
+    }
+
+    #[test]
+    fn doppler_gradient_matches_finite_differences() {
+        let geometry = bistatic();
+        let point_m = DVec3::new(200.0, 300.0, 0.0);
+        let wavelength_m = SignalState::default().wavelength_m();
+        let expected = numerical_gradient(|point| geometry.doppler_hz(point, wavelength_m), point_m);
+        let gradient = geometry.doppler_gradient_hz_m(point_m, wavelength_m);
+        assert!(gradient.abs_diff_eq(expected, 1e-7 * expected.length()));
+    }
+
+    #[test]
+    fn monostatic_resolutions() {
+        // Side-looking platform flying North, the point being broadside at 45° incidence
+        let (position_m, velocity_m_s) = (DVec3::new(-3000.0, 0.0, 3000.0), DVec3::new(0.0, 100.0, 0.0));
+        let geometry = BistaticGeometry::new(
+            platform(position_m, velocity_m_s),
+            platform(position_m, velocity_m_s),
+            Earth::default()
+        );
+        let signal = SignalState::default();
+        let resolution = geometry.resolution_at(DVec3::ZERO, &signal);
+
+        let incidence_rad = 45f64.to_radians();
+        let ground_range_m = SPEED_OF_LIGHT_M_S / (2.0 * signal.bandwidth_hz * incidence_rad.sin());
+        assert!((resolution.ground_range_resolution_m - ground_range_m).abs() < 1e-9 * ground_range_m);
+        let ground_doppler_m = signal.wavelength_m() * position_m.length()
+            / (2.0 * velocity_m_s.length() * signal.integration_time_s);
+        assert!((resolution.ground_doppler_resolution_m - ground_doppler_m).abs() < 1e-9 * ground_doppler_m);
+        assert!((resolution.gradients_angle_rad - FRAC_PI_2).abs() < 1e-9);
+
+        // Orthogonal gradients: the ellipse axes are the resolutions
+        let ellipse = resolution.ellipse.unwrap();
+        assert!((2.0 * ellipse.semi_major_axis_m - ground_range_m.max(ground_doppler_m)).abs() < 1e-6);
+        assert!((2.0 * ellipse.semi_minor_axis_m - ground_range_m.min(ground_doppler_m)).abs() < 1e-6);
+    }
+}
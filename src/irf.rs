@@ -0,0 +1,48 @@
+use bsarconf::{
+    focusing::{FocusedImage, IrfReport},
+    scenario::Scenario
+};
+
+use crate::{
+    cli::{IrfArgs, ReportFormat},
+    compute::EXIT_ERROR
+};
+
+/// Headless impulse response analysis: loads the image and its scenario, prints the
+/// analysis on stdout and returns the process exit status
+pub fn run(args: &IrfArgs) -> i32 {
+    let image = match FocusedImage::load(&args.image) {
+        Ok(image) => image,
+        Err(err) => {
+            eprintln!("Can't load image {}: {}", args.image.display(), err);
+            return EXIT_ERROR;
+        }
+    };
+    let scenario = match Scenario::load(&args.scenario) {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            eprintln!("Can't load scenario {}: {}", args.scenario.display(), err);
+            return EXIT_ERROR;
+        }
+    };
+    let report = match IrfReport::new(&image, &scenario, args.target_m) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Can't analyze the impulse response: {}", err);
+            return EXIT_ERROR;
+        }
+    };
+
+    let output = match args.format {
+        ReportFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => json,
+            Err(err) => {
+                eprintln!("Can't serialize report: {}", err);
+                return EXIT_ERROR;
+            }
+        },
+        ReportFormat::Csv => report.to_csv()
+    };
+    println!("{}", output.trim_end());
+    0
+}
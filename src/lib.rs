@@ -0,0 +1,15 @@
+//! Bistatic SAR configurator core: carrier/antenna states and the headless
+//! geometry engine, usable without opening a window (scripts, parameter sweeps).
+
+pub mod constants;
+pub mod focusing;
+pub mod geodesy;
+pub mod geometry;
+pub mod navigation;
+pub mod orbit;
+pub mod pattern;
+pub mod report;
+pub mod scenario;
+pub mod simulation;
+pub mod state;
+pub mod trajectory;
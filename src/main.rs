@@ -1,107 +1,65 @@
-mod constants;
+mod cli;
+mod compute;
+mod focus;
+mod irf;
 mod mesh;
 mod scene;
+mod simulate;
+mod ui;
+
+use bsarconf::{constants, geodesy, geometry, navigation, orbit, pattern, scenario, state, trajectory};
 
 use scene::{
     pan_orbit_camera, PanOrbitCameraBundle, PanOrbitState,
-    entities::{spawn_world, spawn_axis_helper}
-};
-
-use bevy::{
-    prelude::*,
-    math::DVec3,
-    render::mesh::ConeAnchor
+    pick_ground_point, reproject_ground_pick, spawn_ground_pick_text, update_ground_pick_text, GroundPick,
+    spawn_resolution_ellipses, update_resolution_ellipses,
+    overlay_controls, IsoDopplerOverlay, IsoRangeOverlay,
+    spawn_iso_doppler_overlay, spawn_iso_range_overlay, spawn_velocity_vectors,
+    update_iso_doppler_overlay, update_iso_range_overlay, update_velocity_vector,
+    spawn_footprints, update_common_footprint, update_footprint,
+    platform_geometry, PlatformStates,
+    handle_scenario_events, pan_orbit_state, InitialScenario, ScenarioEvent, ScenarioFile,
+    update_orbit_carrier, advance_timeline, update_carrier_motion, Timeline,
+    spawn_navigation_paths, update_navigation_path,
+    spawn_nesz_map, update_nesz_map, NeszMap,
+    update_ambiguities, Ambiguities, TimingDiagram,
+    entities::{spawn_world, spawn_axis_helper, update_world_surface}
 };
-use bevy_mod_picking::prelude::*;
-use std::f32::consts::FRAC_PI_2;
-
-use crate::constants::ENU_TO_NED_ROT;
-
-// The internal state of the Carrier
-#[derive(Component)]
-pub struct CarrierState {
-    /// Carrier orientation in World frame (NED referential)
-    pub heading_deg: f64,
-    pub elevation_deg: f64,
-    pub bank_deg: f64,
-    ///
-    pub height_m: f64,
-    /// Carrier to Antenna phase center lever arms (in NED Carier frame)
-    pub lever_arms_m: DVec3,
-
-    // pub position_m: f64
-}
-
-
-
-// The internal state of the Antenna
-#[derive(Component)]
-pub struct AntennaState {
-    /// Antenna orientation relative to Carrier
-    pub heading_deg: f64,
-    pub elevation_deg: f64,
-    pub bank_deg: f64,
-}
-
-// The internal state of the Antenna
-#[derive(Component)]
-pub struct AntennaBeamState {
-    /// Antenna 3d beam widths
-    pub elevation_beam_width_deg: f64,
-    pub azimuth_beam_width_deg: f64,
-}
 
-impl Default for CarrierState {
-    fn default() -> Self {
-        Self {
-            heading_deg: 0.0,
-            elevation_deg: 0.0,
-            bank_deg: 0.0,
-            height_m: 300.0,
-            lever_arms_m: DVec3::ZERO,
-        }
-    }
-}
+use std::f64::consts::TAU;
 
-impl Default for AntennaState {
-    fn default() -> Self {
-        Self {
-            heading_deg: 90.0,
-            elevation_deg: -60.0,
-            bank_deg: 0.0,
-        }
-    }
-}
+use bevy::{math::DVec2, prelude::*};
+use bevy_egui::EguiPlugin;
+use bevy_mod_picking::prelude::*;
 
-impl Default for AntennaBeamState {
-    fn default() -> Self {
-        Self {
-            elevation_beam_width_deg: 18.0,
-            azimuth_beam_width_deg: 22.0
-        }
-    }
-}
+use crate::{
+    cli::{parse_args, Command, USAGE},
+    constants::BEAM_CONTOUR_SEGMENTS,
+    mesh::AntennaCone,
+    pattern::AntennaPattern,
+    scenario::Scenario,
+    state::{AntennaBeamState, AntennaPatternState, AntennaState, CarrierNavigation, CarrierOrbit, CarrierState, CarrierTrajectory, InitialCarrierState, Rx, Tx},
+    ui::{keyboard_over_ui, menu_bar, nesz_legend, parameters_panel, pointer_over_ui, timeline_panel, timing_diagram}
+};
 
-//
-#[derive(Component)]
-struct Tx;
+// Antenna cone mesh length along the beam axis
+const ANTENNA_CONE_HEIGHT: f32 = 1e7;
 
+// Markers of the Carrier, Antenna and Antenna Cone entities, used together with `Tx` or `Rx`
 #[derive(Component)]
-struct Rx;
+struct CarrierRefMarker;
 
-
-//
 #[derive(Component)]
-struct TxCarrierRefMarker;
+struct AntennaRefMarker;
 
 #[derive(Component)]
-struct TxAntennaRefMarker;
+struct AntennaConeMarker;
 
-#[derive(Component)]
-struct TxAntennaConeMarker;
+// Filter matching platforms whose carrier or antenna state changed, which moves the antenna
+type AntennaMoved = Or<(Changed<CarrierState>, Changed<AntennaState>)>;
 
-// #[derive(Component)]
-// struct RxCarrierRefMarker;
+// Filter matching platforms whose beam widths or antenna pattern changed, which reshapes the cone
+type BeamChanged = Or<(Changed<AntennaBeamState>, Changed<AntennaPatternState>)>;
 
 // We can use a dynamic highlight that builds a material based on the entity's base material. This
 // allows us to "tint" a material by leaving all other properties - like the texture - unchanged,
@@ -129,6 +87,36 @@ const HIGHLIGHT_TINT: Highlight<StandardMaterial> = Highlight {
 
 
 fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Gui(args)) => args,
+        Ok(Command::Compute(args)) => std::process::exit(compute::run(&args)), // No window
+        Ok(Command::Simulate(args)) => std::process::exit(simulate::run(&args)),
+        Ok(Command::Focus(args)) => std::process::exit(focus::run(&args)),
+        Ok(Command::Irf(args)) => std::process::exit(irf::run(&args)),
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(compute::EXIT_ERROR);
+        }
+    };
+    let scenario = match &args.scenario {
+        Some(path) => Scenario::load(path).unwrap_or_else(|err| {
+            eprintln!("Can't load scenario {}: {}", path.display(), err);
+            std::process::exit(compute::EXIT_ERROR);
+        }),
+        None => Scenario::default()
+    };
+    let scenario_file = ScenarioFile {
+        path: args
+            .scenario
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
     App::new()
         .insert_resource(Msaa::default())
         .insert_resource(ClearColor(Color::BLACK))
@@ -145,7 +133,7 @@ fn main() {
                 WindowPlugin {
                     primary_window: Some(Window {
                         position: WindowPosition::Automatic,
-                        resolution: [800.0, 600.0].into(),
+                        resolution: [1280.0, 800.0].into(),
                         title: "BSAR Configurator".to_string(),
                         ..Default::default()
                         }),
@@ -160,27 +148,99 @@ fn main() {
             )
         )
         .add_plugins(DefaultPickingPlugins) // Includes a mesh raycasting backend by default
-        .add_systems(Startup, setup_scene)
-        .add_systems(PostStartup,
+        .add_plugins(EguiPlugin)
+        .init_resource::<GroundPick>()
+        .init_resource::<IsoRangeOverlay>()
+        .init_resource::<IsoDopplerOverlay>()
+        .init_resource::<NeszMap>()
+        .init_resource::<TimingDiagram>()
+        .init_resource::<Ambiguities>()
+        .init_resource::<Timeline>()
+        .insert_resource(scenario.origin)
+        .insert_resource(scenario.earth)
+        .insert_resource(InitialScenario(scenario))
+        .insert_resource(scenario_file)
+        .add_event::<ScenarioEvent>()
+        .add_systems(Startup,
             (
-                init_tx_carrier_transform,
-                init_tx_antenna_transform,
-                init_tx_antenna_cone_opening,
-                init_carrier
+                setup_scene,
+                spawn_ground_pick_text,
+                spawn_resolution_ellipses,
+                spawn_iso_range_overlay,
+                spawn_iso_doppler_overlay,
+                spawn_velocity_vectors,
+                spawn_footprints,
+                spawn_navigation_paths,
+                spawn_nesz_map
             )
         )
-        .add_systems(Update, pan_orbit_camera.run_if(any_with_component::<PanOrbitState>))
+        .add_systems(Update,
+            (
+                advance_timeline,
+                (menu_bar, timeline_panel, parameters_panel, nesz_legend, timing_diagram, handle_scenario_events).chain(), // The top and bottom panels are laid out first
+                (update_orbit_carrier::<Tx>, update_orbit_carrier::<Rx>), // Orbits drive the initial carrier states
+                (update_carrier_motion::<Tx>, update_carrier_motion::<Rx>) // Carriers move to the timeline time
+            ).chain()
+        )
+        .add_systems(Update, // States changes are propagated to transforms
+            (
+                update_carrier_transform::<Tx>,
+                update_antenna_transform::<Tx>,
+                update_antenna_cone::<Tx>,
+                update_carrier_transform::<Rx>,
+                update_antenna_transform::<Rx>,
+                update_antenna_cone::<Rx>
+            ).after(update_carrier_motion::<Tx>)
+            .after(update_carrier_motion::<Rx>)
+        )
+        .add_systems(Update,
+            pan_orbit_camera
+                .run_if(any_with_component::<PanOrbitState>)
+                .run_if(not(pointer_over_ui))
+        )
+        .add_systems(Update,
+            (
+                pick_ground_point.run_if(not(pointer_over_ui)),
+                reproject_ground_pick,
+                (update_ground_pick_text, update_resolution_ellipses)
+            ).chain()
+        )
+        .add_systems(Update,
+            (
+                overlay_controls.run_if(not(keyboard_over_ui)),
+                (
+                    update_iso_range_overlay,
+                    update_iso_doppler_overlay,
+                    update_world_surface,
+                    update_velocity_vector::<Tx>,
+                    update_velocity_vector::<Rx>,
+                    update_footprint::<Tx>,
+                    update_footprint::<Rx>,
+                    update_common_footprint,
+                    update_navigation_path::<Tx>,
+                    update_navigation_path::<Rx>,
+                    update_nesz_map,
+                    update_ambiguities
+                )
+            ).chain()
+        )
         .run();
 }
 
 fn setup_scene(
+    scenario: Res<InitialScenario>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     // asset_server: Res<AssetServer>
 ) {
+    let InitialScenario(scenario) = scenario.as_ref();
+
     // Camera
-    commands.spawn(PanOrbitCameraBundle::default());
+    commands.spawn(PanOrbitCameraBundle {
+        state: pan_orbit_state(&scenario.camera.unwrap_or_default()),
+        ..Default::default()
+    });
 
     // let _world = spawn_world(&mut commands, &mut meshes, &mut materials);
     spawn_world(&mut commands, &mut meshes, &mut materials);
@@ -191,159 +251,152 @@ fn setup_scene(
     let tx_antenna_beam = commands.spawn(
         (
             PbrBundle {
-                mesh: meshes.add(Cone {
-                    radius: 1e6,
-                    height: 1e7
-                }.mesh()
-                .resolution(360)
-                .anchor(ConeAnchor::Tip)),
+                mesh: meshes.add(antenna_cone(&scenario.tx.beam, &scenario.tx.pattern)), // Cone along X-axis
                 material: materials.add(
                     StandardMaterial {
                         base_color: Color::srgba(1.0, 1.0, 1.0, 0.3),
                         alpha_mode: AlphaMode::Blend,
                         unlit: true,
+                        double_sided: true,
+                        cull_mode: None,
                         ..Default::default()
                     }
                 ),
-                transform: Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_2)), // Cone along X-axis
                 ..Default::default()
             },
             PickableBundle::default(), // <- Makes the mesh pickable.
             HIGHLIGHT_TINT,            // Override the global highlighting settings for this mesh
-            TxAntennaConeMarker // Add a marker component to Tx Antenna Cone entity
+            (AntennaConeMarker, Tx)    // Add marker components to Tx Antenna Cone entity
         )
     ).id();
 
     commands // Antenna cone is the child of tx_antenna_ref...
         .entity(tx_antenna)
-        .insert(TxAntennaRefMarker) // Add a marker component to Tx Antenna entity
+        .insert((AntennaRefMarker, Tx)) // Add marker components to Tx Antenna entity
         .add_child(tx_antenna_beam);
     commands // Which is the child of 
         .entity(tx_carrier)
-        .insert(TxCarrierRefMarker) // Add a marker component to Tx Carrier entity
+        .insert((CarrierRefMarker, Tx)) // Add marker components to Tx Carrier entity
+        .insert(( // Tx states are held by the Tx Carrier entity
+            scenario.tx.carrier,
+            InitialCarrierState(scenario.tx.carrier),
+            scenario.tx.antenna,
+            scenario.tx.beam,
+            AntennaPatternState(scenario.tx.pattern.clone()),
+            CarrierOrbit(scenario.tx.orbit.clone()),
+            CarrierTrajectory(scenario.tx.trajectory.clone()),
+            CarrierNavigation(scenario.tx.navigation.clone()),
+            scenario.signal, // The transmitted signal and the link budget are held by Tx
+            scenario.link_budget
+        ))
         .add_child(tx_antenna);
 
 
-    // Transmitter
+    // Receiver
     let rx_carrier = spawn_axis_helper(&mut commands, &mut meshes, &mut materials, 150.0);
     let rx_antenna = spawn_axis_helper(&mut commands, &mut meshes, &mut materials, 100.0);
     let rx_antenna_beam = commands.spawn(
         (
             PbrBundle {
-                mesh: meshes.add(Cone {
-                    radius: 1e6,
-                    height: 1e7
-                }.mesh()
-                .resolution(360)
-                .anchor(ConeAnchor::Tip)),
+                mesh: meshes.add(antenna_cone(&scenario.rx.beam, &scenario.rx.pattern)), // Cone along X-axis
                 material: materials.add(
                     StandardMaterial {
                         base_color: Color::srgba(0.0, 0.0, 0.0, 0.3),
                         alpha_mode: AlphaMode::Blend,
                         unlit: true,
+                        double_sided: true,
+                        cull_mode: None,
                         ..Default::default()
                     }
                 ),
-                transform: Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_2)), // Cone along X-axis
                 ..Default::default()
             },
-            AntennaBeamState::default()
+            (AntennaConeMarker, Rx)
         )
     ).id();
     
     commands
         .entity(rx_antenna)
-        .insert(AntennaState::default())
+        .insert((AntennaRefMarker, Rx))
         .add_child(rx_antenna_beam);
     commands
         .entity(rx_carrier)
-        .insert(
-            CarrierState {
-                heading_deg: 90.0,
-                elevation_deg: 10.0,
-                ..Default::default()
-            }
-        )
-        .insert(Rx)
+        .insert((CarrierRefMarker, Rx))
+        .insert(( // Rx states are held by the Rx Carrier entity
+            scenario.rx.carrier,
+            InitialCarrierState(scenario.rx.carrier),
+            scenario.rx.antenna,
+            scenario.rx.beam,
+            AntennaPatternState(scenario.rx.pattern.clone()),
+            CarrierOrbit(scenario.rx.orbit.clone()),
+            CarrierTrajectory(scenario.rx.trajectory.clone()),
+            CarrierNavigation(scenario.rx.navigation.clone())
+        ))
         .add_child(rx_antenna);
 }
 
 
-fn init_tx_carrier_transform(
-    mut query: Query<
-        &mut Transform,
-        With<TxCarrierRefMarker>
-    >
+fn update_carrier_transform<P: Component>(
+    query_state: Query<PlatformStates, With<P>>,
+    query_changed: Query<(), (With<P>, Changed<CarrierState>)>,
+    mut query: Query<&mut Transform, (With<P>, With<CarrierRefMarker>)>
 ) {
+    if query_changed.is_empty() {
+        return;
+    }
+    let geometry = platform_geometry(&query_state);
     let mut transform = query
         .get_single_mut()
-        .expect("Can't get `TxCarrierRef` transform");
+        .expect("Can't get `CarrierRef` transform");
 
-    transform.translation = Vec3::new(-5000.0, 0.0, 3000.0);
-    transform.rotation = ENU_TO_NED_ROT.clone();
+    transform.translation = geometry.carrier_position_m.as_vec3();
+    transform.rotation = geometry.carrier_rotation.as_quat();
 }
 
-fn init_tx_antenna_transform(mut query: Query<&mut Transform, With<TxAntennaRefMarker>>) {
-    let mut transform = query
-        .get_single_mut()
-        .expect("Can't get `TxCarrierRef` transform");
-    transform.translation = Vec3::new(0.0, 0.0, -0.0);
-    transform.rotation = Quat::from_euler(
-        EulerRot::ZYX,
-        45.0f32.to_radians(),  // Heading
-        -60.0f32.to_radians(), // Elevation
-        0.0                    // Bank
-    );
-}
-
-fn init_tx_antenna_cone_opening(
-    mut query: Query<&mut Transform, With<TxAntennaConeMarker>>,
+fn update_antenna_transform<P: Component>(
+    query_state: Query<PlatformStates, With<P>>,
+    query_changed: Query<(), (With<P>, AntennaMoved)>,
+    mut query: Query<&mut Transform, (With<P>, With<AntennaRefMarker>)>
 ) {
+    if query_changed.is_empty() {
+        return;
+    }
+    let geometry = platform_geometry(&query_state);
     let mut transform = query
         .get_single_mut()
-        .expect("Can't get `TxCarrierRef` transform");
+        .expect("Can't get `AntennaRef` transform");
 
-    transform.scale = Vec3::new(
-        1.0, // Azimuth aperture
-        1.0,
-        0.5  // Elevation aperture
-    );
+    transform.translation = geometry.antenna_local_position_m().as_vec3();
+    transform.rotation = geometry.antenna_local_rotation().as_quat();
 }
 
-
-
-fn init_carrier(
-    mut query_carrier: Query<(&CarrierState, &mut Transform), With<Rx>>,
-    // mut query_antenna: Query<(&AntennaState, &mut Transform), With<Rx>>,
-    // mut query_antenna_beam: Query<(&AntennaBeamState, &mut Transform), With<Rx>>,
-) {
-    let (carrier, mut carrier_transform) = query_carrier.get_single_mut().expect("Can't get `Rx Carrier` transform");
-    // let (antenna, mut antenna_transform) = query_antenna.get_single_mut().expect("Can't get `Rx Antenna` transform");
-    // let (antenna_beam, mut antenna_beam_transform) = query_antenna_beam.get_single_mut().expect("Can't get `Rx Antenna` transform");
-
-    // Carrier transform
-    carrier_transform.translation = Vec3::new(0.0, 0.0, 3000.0);
-    carrier_transform.rotation = ENU_TO_NED_ROT.to_owned() * 
-        Quat::from_euler(
-            EulerRot::ZYX,
-            carrier.heading_deg.to_radians() as f32,
-            carrier.elevation_deg.to_radians() as f32,
-            carrier.bank_deg.to_radians() as f32
+/// Antenna cone whose cross-section is the beam contour at the beam cone level
+fn antenna_cone(beam: &AntennaBeamState, pattern: &AntennaPattern) -> AntennaCone {
+    let half_beam_widths_rad = 0.5 * DVec2::new(
+        beam.azimuth_beam_width_deg.to_radians(),
+        beam.elevation_beam_width_deg.to_radians()
     );
+    let contour = pattern.beam_contour(half_beam_widths_rad, beam.cone_level_db, BEAM_CONTOUR_SEGMENTS);
+    AntennaCone {
+        cross_section: contour
+            .iter()
+            .enumerate()
+            .map(|(k, &r)| (r * DVec2::from_angle(TAU * k as f64 / contour.len() as f64)).as_vec2())
+            .collect(),
+        height: ANTENNA_CONE_HEIGHT,
+        ..Default::default()
+    }
+}
 
-    // // Antenna transform
-    // antenna_transform.translation = carrier.lever_arms_m.as_vec3();
-    // antenna_transform.rotation = Quat::from_euler(
-    //     EulerRot::ZYX,
-    //     antenna.heading_deg.to_radians() as f32,
-    //     antenna.elevation_deg.to_radians() as f32,
-    //     antenna.bank_deg.to_radians() as f32
-    // );
+fn update_antenna_cone<P: Component>(
+    query_beam: Query<(&AntennaBeamState, &AntennaPatternState), (With<P>, BeamChanged)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query: Query<&mut Handle<Mesh>, (With<P>, With<AntennaConeMarker>)>,
+) {
+    let Ok((beam, pattern)) = query_beam.get_single() else { return };
+    let mut mesh = query
+        .get_single_mut()
+        .expect("Can't get `AntennaCone` mesh");
 
-    // // Antenna beam transform
-    // antenna_beam_transform.scale = Vec3::new(
-    //     1.0, // Azimuth aperture
-    //     1.0,
-    //     0.5  // Elevation aperture
-    // );
-}
\ No newline at end of file
+    *mesh = meshes.add(antenna_cone(beam, &pattern.0));
+}
@@ -5,4 +5,10 @@ mod lines;
 pub use lines::{
     LineList,
     LineStrip
-};
\ No newline at end of file
+};
+
+mod polygon;
+pub use polygon::ConvexPolygon;
+
+mod surface;
+pub use surface::GridSurface;
@@ -1,16 +1,21 @@
-use bevy::render::{mesh::{Indices, Mesh, PrimitiveTopology}, render_asset::RenderAssetUsages};
+use bevy::{
+    math::{Vec2, Vec3},
+    render::{mesh::{Indices, Mesh, PrimitiveTopology}, render_asset::RenderAssetUsages}
+};
 
 /// A cone which stands on the YZ plane with
 /// vertical axis in the X axis and cone apex at 
 /// the origin.
-#[derive(Clone, Copy, Debug)]
+/// Its cross-section is a closed contour, such as
+/// the edge of an antenna beam.
+#[derive(Clone, Debug)]
 pub struct AntennaCone {
-    /// Base radius of the cone in the XY plane.
-    pub radius: f32,
-    /// Height of the cone in the Z axis.
+    /// Cross-section points at X = 1 (Y: azimuth, Z: elevation), turning counterclockwise
+    /// around the X axis. Their number is the number of radial segments and must be
+    /// greater or equal to 3.
+    pub cross_section: Vec<Vec2>,
+    /// Height of the cone in the X axis.
     pub height: f32,
-    /// Number of radial segments of the cone circle(s). Must be greater or equal to 3.
-    pub radial_segments: u32,
     /// Number of height segments. Must be greater or equal to 1.
     pub height_segments: u32,
     /// If true, the cone will be drawn as wireframe
@@ -19,10 +24,12 @@ pub struct AntennaCone {
 
 impl Default for AntennaCone {
     fn default() -> Self {
+        // Circular cross-section of half opening angle π/4
         Self {
-            radius: 1.0,
+            cross_section: (0..36)
+                .map(|i| Vec2::from_angle(std::f32::consts::TAU * i as f32 / 36.0))
+                .collect(),
             height: 1.0,
-            radial_segments: 36,
             height_segments: 1,
             wireframe: false
         }
@@ -31,16 +38,16 @@ impl Default for AntennaCone {
 
 impl From<AntennaCone> for Mesh {
     fn from(cone: AntennaCone) -> Self {        
-        debug_assert!(cone.radius > 0.0, "Cone 'radius' must be strictly positive");
+        let radial_segments = cone.cross_section.len() as u32;
         debug_assert!(cone.height > 0.0, "Cone 'height' must be strictly positive");
-        debug_assert!(cone.radial_segments >= 3, "Cone 'radial_segments' must be greater or equal to 3");
+        debug_assert!(radial_segments >= 3, "Cone 'cross_section' must hold at least 3 points");
         debug_assert!(cone.height_segments >= 1, "Cone 'height_segments' must be greater or equal to 1");
 
-        let num_vertices = (cone.radial_segments + 1) * cone.height_segments + 1;
+        let num_vertices = (radial_segments + 1) * cone.height_segments + 1;
         let num_indices  = if cone.wireframe {
-            2 * ((num_vertices - 1) + cone.height_segments * (cone.radial_segments - 1))
+            2 * ((num_vertices - 1) + cone.height_segments * (radial_segments - 1))
         } else {
-            3 * cone.radial_segments * (1 + 2 * (cone.height_segments - 1))
+            3 * radial_segments * (1 + 2 * (cone.height_segments - 1))
         };
         let mut vertices: Vec<[f32; 3]> = Vec::with_capacity(num_vertices as usize);
         let mut normals:  Vec<[f32; 3]> = Vec::with_capacity(num_vertices as usize);
@@ -50,15 +57,12 @@ impl From<AntennaCone> for Mesh {
         // Helper variables
             // Inverse segments numbers
         let inv_height_segments = 1.0 / (cone.height_segments as f32);
-        let inv_radial_segments = 1.0 / (cone.radial_segments as f32);
+        let inv_radial_segments = 1.0 / (radial_segments as f32);
             // loop steps
         let height_step = cone.height * inv_height_segments;
-        let theta_step = std::f32::consts::TAU * inv_radial_segments;
-            // 
-        let tan_alpha  = cone.radius / cone.height; // Cone tangent of its half opening angle (= sin_alpha / cos_alpha)
-        let inv_radial_length = 1.0 / (cone.height.hypot(cone.radius)); // 1/sqrt(H² + R²)
-        let cos_alpha  = cone.height * inv_radial_length;
-        let sin_alpha  = cone.radius * inv_radial_length;
+            // Cross-section point, the last one closing the contour on the first one
+        let n = cone.cross_section.len();
+        let point = |i: usize| cone.cross_section[i % n];
 
         // Apex
         vertices.push([0.0, 0.0, 0.0]);
@@ -67,21 +71,22 @@ impl From<AntennaCone> for Mesh {
         // vertices, normals and uvs
         for k in 1..=cone.height_segments {
             let v = (k as f32) * inv_height_segments; // (u,V) coordinate
-            let height = (k as f32) * height_step; // radius of the current height segment
-            let radius = tan_alpha * height;
+            let height = (k as f32) * height_step; // height of the current height segment
 
-            for i in 0..=cone.radial_segments {
+            for i in 0..=radial_segments {
                 let u = (i as f32) * inv_radial_segments; // (U,v) coordinate
-                let (sin, cos) = ((i as f32) * theta_step).sin_cos();
+                let p = point(i as usize);
 
                 // Vertex
                 vertices.push([height,
-                               radius * cos,
-                               radius * sin]);
-                // Normal            
-                normals.push([-sin_alpha,
-                              cos_alpha * cos,
-                              cos_alpha * sin]);
+                               height * p.x,
+                               height * p.y]);
+                // Normal: cross product of the cross-section tangent and of the cone generatrix
+                let tangent = point(i as usize + 1) - point(i as usize + n - 1);
+                let normal = Vec3::new(0.0, tangent.x, tangent.y)
+                    .cross(Vec3::new(1.0, p.x, p.y))
+                    .normalize();
+                normals.push(normal.to_array());
                 // uv
                 uvs.push([u, v]);
             }
@@ -93,24 +98,24 @@ impl From<AntennaCone> for Mesh {
                 indices.extend_from_slice(&[i, i+1]);
             }
 
-            let nrs = cone.radial_segments + 1;
-            for i in 2..=cone.radial_segments {
+            let nrs = radial_segments + 1;
+            for i in 2..=radial_segments {
                 indices.extend_from_slice(&[0, i]);
                 for k in 0..cone.height_segments - 1 {
                     indices.extend_from_slice(&[i + k * nrs, i + (k + 1) * nrs]);
                 }
             }
         } else {
-            for i in 1..=cone.radial_segments {
+            for i in 1..=radial_segments {
                 indices.extend_from_slice(&[0, i, i + 1]);
             }
             
             if cone.height_segments >= 2 {
-                let ring_step     = cone.radial_segments + 1;
+                let ring_step     = radial_segments + 1;
                 let mut ring      = 1;
                 let mut next_ring = ring + ring_step;
                 for _k in 1..cone.height_segments {            
-                    for i in 0..cone.radial_segments {
+                    for i in 0..radial_segments {
                         indices.extend_from_slice(&[
                             ring + i,
                             next_ring + i,
@@ -121,7 +126,7 @@ impl From<AntennaCone> for Mesh {
                         ]);
                     }
                     ring       = next_ring;
-                    next_ring += cone.radial_segments + 1;
+                    next_ring += radial_segments + 1;
                 }
             }
         }
@@ -0,0 +1,30 @@
+use bevy::{
+    math::Vec3,
+    render::{
+        mesh::{Indices, Mesh, PrimitiveTopology},
+        render_asset::RenderAssetUsages
+    }
+};
+
+/// A filled planar convex polygon, triangulated as a fan around its first point
+#[derive(Debug, Clone)]
+pub struct ConvexPolygon {
+    pub points: Vec<Vec3>,
+    /// Polygon plane normal
+    pub normal: Vec3,
+}
+
+impl From<ConvexPolygon> for Mesh {
+    fn from(polygon: ConvexPolygon) -> Self {
+        let num_points = polygon.points.len() as u32;
+        let indices: Vec<u32> = (1..num_points.saturating_sub(1))
+            .flat_map(|i| [0, i, i + 1])
+            .collect();
+        let normals = vec![polygon.normal.to_array(); polygon.points.len()];
+
+        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, polygon.points)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_indices(Indices::U32(indices))
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::{
+    math::Vec3,
+    render::{
+        mesh::{Indices, Mesh, PrimitiveTopology},
+        render_asset::RenderAssetUsages
+    }
+};
+
+/// A surface sampled on a regular grid of points, each grid cell being split in two triangles.
+/// Texture coordinates span [0, 1] along the grid columns (U) and rows (V).
+#[derive(Debug, Clone)]
+pub struct GridSurface {
+    /// Number of cells along the grid columns and rows
+    pub cells: (usize, usize),
+    /// `(cells.0 + 1) * (cells.1 + 1)` grid points, row major (column index first)
+    pub points: Vec<Vec3>,
+}
+
+impl From<GridSurface> for Mesh {
+    fn from(surface: GridSurface) -> Self {
+        let (ni, nj) = (surface.cells.0 + 1, surface.cells.1 + 1);
+        let index = |i: usize, j: usize| j * ni + i;
+        let point = |i: usize, j: usize| surface.points[index(i, j)];
+
+        // Normals from the central differences along the grid lines
+        let normals: Vec<[f32; 3]> = (0..nj)
+            .flat_map(|j| (0..ni).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let du = point((i + 1).min(ni - 1), j) - point(i.saturating_sub(1), j);
+                let dv = point(i, (j + 1).min(nj - 1)) - point(i, j.saturating_sub(1));
+                du.cross(dv).normalize_or_zero().to_array()
+            })
+            .collect();
+
+        let uvs: Vec<[f32; 2]> = (0..nj)
+            .flat_map(|j| (0..ni).map(move |i| [i as f32 / (ni - 1) as f32, j as f32 / (nj - 1) as f32]))
+            .collect();
+
+        let mut indices = Vec::with_capacity(6 * surface.cells.0 * surface.cells.1);
+        for j in 0..nj - 1 {
+            for i in 0..ni - 1 {
+                let (a, b, c, d) = (index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1));
+                indices.extend([a, b, c, a, c, d].map(|k| k as u32));
+            }
+        }
+
+        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, surface.points)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_indices(Indices::U32(indices))
+    }
+}
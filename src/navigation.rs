@@ -0,0 +1,373 @@
+//! Measured carrier trajectories: time-stamped positions and attitudes read from
+//! GNSS/INS navigation logs in CSV format, interpolated in time.
+//!
+//! The CSV file has a header row naming its columns, in any order, separated by
+//! commas or semicolons. Lines starting with `#` are comments. The required
+//! columns are (with accepted aliases):
+//! - `time` (`time_s`, `t`): seconds, in any time scale
+//! - `lat` (`latitude`, `latitude_deg`, `lat_deg`), `lon` (`long`, `longitude`,
+//!   `longitude_deg`, `lon_deg`): WGS84 degrees
+//! - `height` (`height_m`, `h`, `alt`, `altitude`, `altitude_m`): ellipsoidal height in meters
+//! - `heading` (`heading_deg`, `yaw`, `yaw_deg`), `pitch` (`pitch_deg`), `roll`
+//!   (`roll_deg`): degrees, in the local NED frame of the carrier position
+
+use bevy::math::{DQuat, DVec3, EulerRot};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc
+};
+
+use crate::{
+    constants::ENU_TO_NED_DROT,
+    geodesy::{GeodeticPosition, LocalFrame},
+    state::CarrierState
+};
+
+/// Column names accepted for each field, in `NavigationRecord` order
+const COLUMNS: [(&str, &[&str]); 7] = [
+    ("time", &["time", "time_s", "t"]),
+    ("lat", &["lat", "latitude", "latitude_deg", "lat_deg"]),
+    ("lon", &["lon", "long", "longitude", "longitude_deg", "lon_deg"]),
+    ("height", &["height", "height_m", "h", "alt", "altitude", "altitude_m"]),
+    ("heading", &["heading", "heading_deg", "yaw", "yaw_deg"]),
+    ("pitch", &["pitch", "pitch_deg"]),
+    ("roll", &["roll", "roll_deg"]),
+];
+
+/// A navigation sample
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NavigationRecord {
+    pub time_s: f64,
+    pub position: GeodeticPosition,
+    /// Attitude in the local NED frame of `position`
+    pub heading_deg: f64,
+    pub pitch_deg: f64,
+    pub roll_deg: f64,
+}
+
+/// Navigation records sorted by increasing time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NavigationTrack {
+    pub records: Vec<NavigationRecord>,
+}
+
+#[derive(Debug)]
+pub enum NavigationError {
+    Io(io::Error),
+    /// Invalid content at a 1-based line number
+    Parse { line: usize, message: String },
+}
+
+/// Navigation file driving a carrier
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Navigation {
+    /// CSV navigation file, relative paths being resolved from the scenario file directory
+    pub path: PathBuf,
+    /// Navigation time at the start of the acquisition, the first record time if not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time_s: Option<f64>,
+    /// Records loaded from the file
+    #[serde(skip)]
+    pub track: Arc<NavigationTrack>,
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for NavigationError {}
+
+impl From<io::Error> for NavigationError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Interpolates linearly between the angles `a` and `b` along the shortest arc
+#[inline]
+fn lerp_angle_deg(a: f64, b: f64, t: f64) -> f64 {
+    a + t * ((b - a + 180.0).rem_euclid(360.0) - 180.0)
+}
+
+impl NavigationRecord {
+    /// Record interpolated at `t` (0 at `self`, 1 at `other`)
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + t * (b - a);
+        Self {
+            time_s: lerp(self.time_s, other.time_s),
+            position: GeodeticPosition {
+                latitude_deg: lerp(self.position.latitude_deg, other.position.latitude_deg),
+                longitude_deg: lerp_angle_deg(self.position.longitude_deg, other.position.longitude_deg, t),
+                height_m: lerp(self.position.height_m, other.position.height_m),
+            },
+            heading_deg: lerp_angle_deg(self.heading_deg, other.heading_deg, t),
+            pitch_deg: lerp(self.pitch_deg, other.pitch_deg),
+            roll_deg: lerp_angle_deg(self.roll_deg, other.roll_deg, t),
+        }
+    }
+
+    /// Carrier rotation from the World axes of `frame` to the Carrier NED axes
+    pub fn rotation(&self, frame: &LocalFrame) -> DQuat {
+        let local_to_ecef = LocalFrame::new(self.position).enu_to_ecef;
+        let local_to_world = DQuat::from_mat3(&(frame.enu_to_ecef.transpose() * local_to_ecef));
+        local_to_world * *ENU_TO_NED_DROT * DQuat::from_euler(
+            EulerRot::ZYX,
+            self.heading_deg.to_radians(),
+            self.pitch_deg.to_radians(),
+            self.roll_deg.to_radians()
+        )
+    }
+}
+
+impl NavigationTrack {
+    pub fn from_csv_str(s: &str) -> Result<Self, NavigationError> {
+        let error = |line: usize, message: String| NavigationError::Parse { line, message };
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(k, line)| (k + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let separator = |line: &str| if line.contains(';') { ';' } else { ',' };
+
+        let (header_line, header) = lines.next().ok_or_else(|| error(1, "missing header row".to_string()))?;
+        let names: Vec<String> = header
+            .split(separator(header))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let mut indices = [0; 7];
+        let mut missing = Vec::new();
+        for (index, (field, aliases)) in indices.iter_mut().zip(COLUMNS) {
+            match names.iter().position(|name| aliases.contains(&name.as_str())) {
+                Some(column) => *index = column,
+                None => missing.push(field),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(error(header_line, format!("missing column(s): {}", missing.join(", "))));
+        }
+
+        let mut records = Vec::new();
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(separator(line)).map(str::trim).collect();
+            let mut values = [0.0; 7];
+            for (value, (&column, (field, _))) in values.iter_mut().zip(indices.iter().zip(COLUMNS)) {
+                let text = fields
+                    .get(column)
+                    .ok_or_else(|| error(line_number, format!("missing {} value", field)))?;
+                *value = text
+                    .parse()
+                    .ok()
+                    .filter(|value: &f64| value.is_finite())
+                    .ok_or_else(|| error(line_number, format!("invalid {} value `{}`", field, text)))?;
+            }
+            let [time_s, latitude_deg, longitude_deg, height_m, heading_deg, pitch_deg, roll_deg] = values;
+            if records.last().is_some_and(|last: &NavigationRecord| time_s <= last.time_s) {
+                return Err(error(line_number, format!("time {} does not increase", time_s)));
+            }
+            records.push(NavigationRecord {
+                time_s,
+                position: GeodeticPosition::new(latitude_deg, longitude_deg, height_m),
+                heading_deg,
+                pitch_deg,
+                roll_deg,
+            });
+        }
+        if records.is_empty() {
+            return Err(error(header_line, "no navigation record".to_string()));
+        }
+        Ok(Self { records })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, NavigationError> {
+        Self::from_csv_str(&fs::read_to_string(path)?)
+    }
+
+    /// Time span of the records, `None` if there is none
+    pub fn time_span_s(&self) -> Option<(f64, f64)> {
+        Some((self.records.first()?.time_s, self.records.last()?.time_s))
+    }
+
+    /// Indices of the records framing `time_s`, clamped to the first or last records
+    fn segment(&self, time_s: f64) -> Option<(usize, usize)> {
+        match self.records.len() {
+            0 => None,
+            1 => Some((0, 0)),
+            n => {
+                let next = self.records.partition_point(|record| record.time_s <= time_s);
+                Some((next.clamp(1, n - 1) - 1, next.clamp(1, n - 1)))
+            }
+        }
+    }
+
+    /// Record interpolated at `time_s`, held at the first or last record outside the track
+    pub fn sample(&self, time_s: f64) -> Option<NavigationRecord> {
+        let (a, b) = self.segment(time_s)?;
+        let (a, b) = (&self.records[a], &self.records[b]);
+        if time_s <= a.time_s {
+            return Some(*a);
+        }
+        if time_s >= b.time_s {
+            return Some(*b);
+        }
+        Some(a.lerp(b, (time_s - a.time_s) / (b.time_s - a.time_s)))
+    }
+
+    /// Velocity in the World frame of `frame` at `time_s`, from the positions of the
+    /// records framing it
+    pub fn velocity_m_s(&self, frame: &LocalFrame, time_s: f64) -> DVec3 {
+        match self.segment(time_s) {
+            Some((a, b)) if a != b => {
+                let (a, b) = (&self.records[a], &self.records[b]);
+                (frame.geodetic_to_enu(&b.position) - frame.geodetic_to_enu(&a.position)) / (b.time_s - a.time_s)
+            },
+            _ => DVec3::ZERO
+        }
+    }
+}
+
+impl PartialEq for Navigation {
+    /// Tracks are compared by identity, as they are replaced rather than modified
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && self.start_time_s == other.start_time_s
+            && Arc::ptr_eq(&self.track, &other.track)
+    }
+}
+
+impl Navigation {
+    /// Loads the navigation file `path`, resolved from `base_dir` when relative
+    pub fn load(path: PathBuf, base_dir: &Path) -> Result<Self, NavigationError> {
+        let track = NavigationTrack::load(base_dir.join(&path))?;
+        Ok(Self {
+            path,
+            start_time_s: None,
+            track: Arc::new(track),
+        })
+    }
+
+    /// Navigation time of the start of the acquisition
+    #[inline]
+    pub fn start_time_s(&self) -> f64 {
+        self.start_time_s
+            .or_else(|| self.track.time_span_s().map(|(first, _)| first))
+            .unwrap_or(0.0)
+    }
+
+    /// Carrier state in the World frame `frame`, `time_s` after the start of the
+    /// acquisition, keeping the lever arms of `carrier`. `None` if the track is empty.
+    pub fn carrier_state(&self, frame: &LocalFrame, carrier: &CarrierState, time_s: f64) -> Option<CarrierState> {
+        let time_s = self.start_time_s() + time_s;
+        let record = self.track.sample(time_s)?;
+        let mut state = CarrierState {
+            velocity_m_s: self.track.velocity_m_s(frame, time_s),
+            ..*carrier
+        };
+        state.set_rotation(record.rotation(frame));
+        state.set_position_enu_m(frame.geodetic_to_enu(&record.position));
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error_line(s: &str) -> usize {
+        match NavigationTrack::from_csv_str(s) {
+            Err(NavigationError::Parse { line, .. }) => line,
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    #[test]
+    fn header_aliases_and_separators() {
+        let commas = "# GNSS/INS log\n\
+            time,lat,lon,height,heading,pitch,roll\n\
+            0.0,43.5,1.25,1000.0,90.0,1.0,-2.0\n\
+            1.0,43.6,1.26,1010.0,91.0,1.5,-2.5\n";
+        // Reordered columns, aliases in mixed case and semicolon separators
+        let semicolons = "Roll_deg; Pitch_Deg; YAW; Altitude_m; Longitude; Latitude; T\n\
+            -2.0; 1.0; 90.0; 1000.0; 1.25; 43.5; 0.0\n\
+            \n\
+            -2.5; 1.5; 91.0; 1010.0; 1.26; 43.6; 1.0\n";
+        let track = NavigationTrack::from_csv_str(commas).unwrap();
+        assert_eq!(track.records.len(), 2);
+        assert_eq!(
+            track.records[1],
+            NavigationRecord {
+                time_s: 1.0,
+                position: GeodeticPosition::new(43.6, 1.26, 1010.0),
+                heading_deg: 91.0,
+                pitch_deg: 1.5,
+                roll_deg: -2.5,
+            }
+        );
+        assert_eq!(NavigationTrack::from_csv_str(semicolons).unwrap(), track);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let header = "time,lat,lon,height,heading,pitch,roll\n";
+        assert_eq!(parse_error_line(""), 1);
+        assert_eq!(parse_error_line("time,lat,lon,height,heading,pitch\n"), 1);
+        assert_eq!(parse_error_line(header), 1);
+        assert_eq!(parse_error_line(&format!("{}0,1,2,3,4,5\n", header)), 2);
+        assert_eq!(parse_error_line(&format!("{}0,1,2,3,4,5,x\n", header)), 2);
+        assert_eq!(parse_error_line(&format!("{}0,1,2,3,4,5,6\n0,1,2,3,4,5,6\n", header)), 3);
+        for value in ["nan", "NaN", "inf", "-inf", "infinity"] {
+            assert_eq!(parse_error_line(&format!("{}0,1,2,3,4,5,6\n1,1,{},3,4,5,6\n", header, value)), 3, "{}", value);
+        }
+    }
+
+    #[test]
+    fn interpolation() {
+        let track = NavigationTrack::from_csv_str(
+            "time,lat,lon,height,heading,pitch,roll\n\
+            10.0,43.0,1.0,1000.0,350.0,0.0,-10.0\n\
+            12.0,44.0,2.0,1200.0,10.0,4.0,10.0\n"
+        ).unwrap();
+        assert_eq!(track.time_span_s(), Some((10.0, 12.0)));
+
+        let record = track.sample(10.5).unwrap();
+        assert!((record.position.latitude_deg - 43.25).abs() < 1e-12);
+        assert!((record.position.longitude_deg - 1.25).abs() < 1e-12);
+        assert!((record.position.height_m - 1050.0).abs() < 1e-9);
+        // Angles interpolated along the shortest arc
+        assert!((record.heading_deg.rem_euclid(360.0) - 355.0).abs() < 1e-9);
+        assert!((record.pitch_deg - 1.0).abs() < 1e-12);
+        assert!((record.roll_deg + 5.0).abs() < 1e-12);
+
+        // Held at the first or last record outside the track
+        assert_eq!(track.sample(0.0), Some(track.records[0]));
+        assert_eq!(track.sample(20.0), Some(track.records[1]));
+        assert_eq!(NavigationTrack::default().sample(0.0), None);
+    }
+
+    #[test]
+    fn longitude_wraps_at_antimeridian() {
+        let track = NavigationTrack::from_csv_str(
+            "time,lat,lon,height,heading,pitch,roll\n\
+            0.0,0.0,179.9,0.0,90.0,0.0,0.0\n\
+            1.0,0.0,-179.9,0.0,90.0,0.0,0.0\n"
+        ).unwrap();
+        let longitude_deg = track.sample(0.25).unwrap().position.longitude_deg;
+        assert!(((longitude_deg + 180.0).rem_euclid(360.0) - 180.0 - 179.95).abs() < 1e-9, "{}", longitude_deg);
+
+        // Eastward velocity across the antimeridian: 0.2° of equator per second
+        let frame = LocalFrame::new(GeodeticPosition::new(0.0, 180.0, 0.0));
+        let velocity_m_s = track.velocity_m_s(&frame, 0.5);
+        let expected_m_s = 0.2_f64.to_radians() * crate::constants::WGS84_SEMI_MAJOR_AXIS_M;
+        assert!((velocity_m_s.x - expected_m_s).abs() < 1.0, "{:?}", velocity_m_s);
+        assert!(velocity_m_s.y.abs() < 1e-6 && velocity_m_s.z.abs() < 1.0, "{:?}", velocity_m_s);
+    }
+}
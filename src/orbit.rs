@@ -0,0 +1,105 @@
+//! Spaceborne carriers: orbital elements (Keplerian or Two-Line Element sets),
+//! their propagation to an epoch and the resulting carrier position, velocity
+//! and attitude in World frame.
+//!
+//! Propagation is analytic: Keplerian motion with the secular drifts of the node,
+//! perigee and mean anomaly caused by the Earth oblateness (J2). TLE mean elements
+//! are propagated with this model, not with the full SGP4 theory, which is
+//! accurate enough to place a beam footprint but not for precise orbit work.
+//! The inertial frame is the true-of-date equatorial frame, rotated to ECEF with
+//! the Greenwich mean sidereal time only (no precession, nutation or polar motion).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{geodesy::LocalFrame, state::CarrierState};
+
+mod epoch;
+pub use epoch::Epoch;
+
+mod kepler;
+pub use kepler::{KeplerianElements, OrbitalState};
+
+mod tle;
+
+mod attitude;
+pub use attitude::AttitudeMode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrbitError {
+    /// The epoch text is not a valid ISO 8601 UTC date and time
+    Epoch(String),
+    /// The Two-Line Element set is malformed
+    Tle(String),
+    /// The elements do not describe a closed orbit
+    Elements(String),
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Epoch(msg) => write!(f, "{}", msg),
+            Self::Tle(msg) => write!(f, "invalid TLE: {}", msg),
+            Self::Elements(msg) => write!(f, "invalid orbital elements: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OrbitError {}
+
+/// Orbit description: explicit Keplerian elements or a Two-Line Element set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OrbitElements {
+    Keplerian(KeplerianElements),
+    Tle { line1: String, line2: String },
+}
+
+/// Orbit of a spaceborne carrier and the epoch at which the carrier is placed on it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct OrbitState {
+    pub elements: OrbitElements,
+    pub epoch: Epoch,
+    pub attitude: AttitudeMode,
+}
+
+impl Default for OrbitElements {
+    fn default() -> Self {
+        Self::Keplerian(KeplerianElements::default())
+    }
+}
+
+impl Default for OrbitState {
+    fn default() -> Self {
+        let elements = KeplerianElements::default();
+        Self {
+            epoch: elements.epoch,
+            elements: OrbitElements::Keplerian(elements),
+            attitude: AttitudeMode::default(),
+        }
+    }
+}
+
+impl OrbitElements {
+    pub fn keplerian(&self) -> Result<KeplerianElements, OrbitError> {
+        match self {
+            Self::Keplerian(elements) => Ok(*elements),
+            Self::Tle { line1, line2 } => KeplerianElements::from_tle(line1, line2),
+        }
+    }
+}
+
+impl OrbitState {
+    /// Spacecraft position and velocities at the orbit epoch
+    pub fn orbital_state(&self) -> Result<OrbitalState, OrbitError> {
+        self.elements.keplerian()?.propagate(&self.epoch)
+    }
+
+    /// Carrier state in the World frame `frame`, `time_s` after the orbit epoch, keeping
+    /// the lever arms of `carrier`
+    pub fn carrier_state(&self, frame: &LocalFrame, carrier: &CarrierState, time_s: f64) -> Result<CarrierState, OrbitError> {
+        let state = self.elements.keplerian()?.propagate(&self.epoch.offset(time_s))?;
+        Ok(state.carrier_state(frame, &self.attitude, carrier))
+    }
+}
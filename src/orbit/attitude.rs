@@ -0,0 +1,98 @@
+use bevy::math::{DMat3, DQuat, DVec3};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geodesy::{GeodeticPosition, LocalFrame},
+    state::CarrierState
+};
+
+use super::OrbitalState;
+
+/// Attitude law of a spaceborne carrier. In both modes the carrier Z (down) axis
+/// points to the geodetic nadir.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum AttitudeMode {
+    /// The carrier X axis follows the horizontal velocity relative to the rotating Earth,
+    /// so that a beam pointed across track (antenna heading ±90°) looks at approximately
+    /// zero Doppler: the radial velocity of an eccentric orbit is not compensated
+    #[default]
+    ZeroDopplerSteering,
+    /// The carrier X axis follows the inertial velocity, rotated about the nadir by `yaw_deg`
+    FixedYaw { yaw_deg: f64 },
+}
+
+impl AttitudeMode {
+    /// Carrier axes (forward, right, down) in ECEF frame, as the columns of a rotation matrix
+    pub fn carrier_axes_ecef(&self, state: &OrbitalState) -> DMat3 {
+        let sub_point = GeodeticPosition::from_ecef(state.position_ecef_m);
+        let down = -LocalFrame::new(sub_point).enu_to_ecef.z_axis;
+        let horizontal = |v: DVec3| (v - v.dot(down) * down).normalize();
+        let forward = match self {
+            Self::ZeroDopplerSteering => horizontal(state.velocity_ecef_m_s),
+            Self::FixedYaw { yaw_deg } => DQuat::from_axis_angle(down, yaw_deg.to_radians())
+                * horizontal(state.inertial_velocity_ecef_m_s),
+        };
+        DMat3::from_cols(forward, down.cross(forward), down)
+    }
+}
+
+impl OrbitalState {
+    /// Carrier state in the World frame `frame` of the spacecraft flying with the
+    /// `attitude` law. The lever arms are taken from `carrier`.
+    pub fn carrier_state(&self, frame: &LocalFrame, attitude: &AttitudeMode, carrier: &CarrierState) -> CarrierState {
+        let ecef_to_enu = frame.enu_to_ecef.transpose();
+        let mut state = CarrierState {
+            velocity_m_s: ecef_to_enu * self.velocity_ecef_m_s,
+            ..*carrier
+        };
+        state.set_rotation(DQuat::from_mat3(&(ecef_to_enu * attitude.carrier_axes_ecef(self))));
+        state.set_position_enu_m(frame.ecef_to_enu(self.position_ecef_m));
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        geometry::{BistaticGeometry, Earth, PlatformGeometry},
+        orbit::OrbitState,
+        state::{AntennaBeamState, AntennaState, EarthModel, SceneOrigin, SignalState}
+    };
+
+    use super::*;
+
+    /// Monostatic Doppler at the ground point of a beam looking 30° off nadir across track
+    /// (right), and Doppler of a point ahead of the spacecraft, `time_s` after the epoch of
+    /// the default orbit flown with `attitude`
+    fn boresight_doppler_hz(attitude: AttitudeMode, time_s: f64) -> (f64, f64) {
+        let orbit = OrbitState { attitude, ..Default::default() };
+        let state = orbit.elements.keplerian().unwrap().propagate(&orbit.epoch.offset(time_s)).unwrap();
+        let origin = SceneOrigin(GeodeticPosition { height_m: 0.0, ..GeodeticPosition::from_ecef(state.position_ecef_m) });
+        let earth = Earth::new(EarthModel::Wgs84, &origin);
+        let carrier = orbit.carrier_state(&earth.frame, &CarrierState::default(), time_s).unwrap();
+        let antenna = AntennaState { heading_deg: 90.0, elevation_deg: -60.0, bank_deg: 0.0 };
+        let platform = PlatformGeometry::new(&carrier, &antenna, &AntennaBeamState::default(), &Default::default());
+        let geometry = BistaticGeometry::new(platform.clone(), platform, earth);
+        let wavelength_m = SignalState::default().wavelength_m();
+        let point_m = geometry.earth.intersect(geometry.rx.antenna_position_m, geometry.rx.boresight()).unwrap();
+        let ahead_m = geometry.rx.antenna_position_m + carrier.velocity_m_s;
+        (geometry.doppler_hz(point_m, wavelength_m), geometry.doppler_hz(ahead_m, wavelength_m))
+    }
+
+    #[test]
+    fn zero_doppler_steering() {
+        // At the perigee, over the northernmost point of the orbit, the Earth-relative and
+        // inertial velocities are aligned and the steering is exact
+        let (doppler_hz, _) = boresight_doppler_hz(AttitudeMode::ZeroDopplerSteering, 0.0);
+        assert!(doppler_hz.abs() < 1e-3, "{} Hz", doppler_hz);
+
+        // A quarter of the 95 min orbit later, over the equator, the radial velocity
+        // (7.6 m/s) dropped by the steering is the largest
+        let (doppler_hz, max_doppler_hz) = boresight_doppler_hz(AttitudeMode::ZeroDopplerSteering, 1425.0);
+        assert!(doppler_hz.abs() < 1e-3 * max_doppler_hz, "{} Hz", doppler_hz);
+        // while the inertial velocity is off the Earth-relative one by the Earth rotation
+        let (doppler_hz, _) = boresight_doppler_hz(AttitudeMode::FixedYaw { yaw_deg: 0.0 }, 1425.0);
+        assert!(doppler_hz.abs() > 1e-2 * max_doppler_hz, "{} Hz", doppler_hz);
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::OrbitError;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+/// Days from 1970-01-01 to 2000-01-01
+const UNIX_DAYS_AT_J2000: i64 = 10_957;
+
+/// UTC instant, stored as seconds elapsed since 2000-01-01T12:00:00 (J2000) and written
+/// in ISO 8601 form (`2024-05-01T12:00:00Z`) in scenario files.
+///
+/// Leap seconds and the UTC/UT1/TT differences are neglected.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[serde(try_from = "String", into = "String")]
+pub struct Epoch {
+    pub seconds_since_j2000: f64,
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (H. Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Proleptic Gregorian date (year, month, day) of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+impl Epoch {
+    pub fn from_seconds_since_j2000(seconds_since_j2000: f64) -> Self {
+        Self { seconds_since_j2000 }
+    }
+
+    /// Epoch of a calendar date and time of day (UTC)
+    pub fn from_calendar(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: f64) -> Self {
+        let days = days_from_civil(year, month, day) - UNIX_DAYS_AT_J2000;
+        Self::from_seconds_since_j2000(
+            days as f64 * SECONDS_PER_DAY + (hour * 3600 + minute * 60) as f64 + second - 0.5 * SECONDS_PER_DAY
+        )
+    }
+
+    /// Epoch of a fractional day of year (1.0 being January 1st, 0h), as found in TLEs
+    pub fn from_day_of_year(year: i64, day_of_year: f64) -> Self {
+        let start = Self::from_calendar(year, 1, 1, 0, 0, 0.0);
+        start.offset(SECONDS_PER_DAY * (day_of_year - 1.0))
+    }
+
+    /// Epoch `seconds` later
+    #[inline]
+    pub fn offset(&self, seconds: f64) -> Self {
+        Self::from_seconds_since_j2000(self.seconds_since_j2000 + seconds)
+    }
+
+    /// Seconds elapsed from `other` to `self`
+    #[inline]
+    pub fn seconds_since(&self, other: &Epoch) -> f64 {
+        self.seconds_since_j2000 - other.seconds_since_j2000
+    }
+
+    /// Days elapsed since J2000
+    #[inline]
+    pub fn days_since_j2000(&self) -> f64 {
+        self.seconds_since_j2000 / SECONDS_PER_DAY
+    }
+
+    /// Parses an ISO 8601 UTC date and time: `YYYY-MM-DDTHH:MM:SS[.fff][Z]`
+    pub fn parse(s: &str) -> Result<Self, OrbitError> {
+        let invalid = || OrbitError::Epoch(format!("invalid epoch `{}` (expected YYYY-MM-DDTHH:MM:SS[.fff]Z)", s));
+        let s = s.trim();
+        let s = s.strip_suffix('Z').unwrap_or(s);
+        let (date, time) = s.split_once(['T', ' ']).ok_or_else(invalid)?;
+
+        let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+        let mut time = time.splitn(3, ':');
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) = (date.next(), date.next(), date.next()) else {
+            return Err(invalid());
+        };
+        let (Some(Ok(hour)), Some(Ok(minute)), Some(Ok(second))) = (
+            time.next().map(str::parse::<i64>),
+            time.next().map(str::parse::<i64>),
+            time.next().map(str::parse::<f64>)
+        ) else {
+            return Err(invalid());
+        };
+        let valid = (1..=12).contains(&month)
+            && (1..=31).contains(&day)
+            && (0..24).contains(&hour)
+            && (0..60).contains(&minute)
+            && (0.0..61.0).contains(&second);
+        if !valid {
+            return Err(invalid());
+        }
+        Ok(Self::from_calendar(year, month, day, hour, minute, second))
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Rounded to the microsecond
+        let micros = ((self.seconds_since_j2000 + 0.5 * SECONDS_PER_DAY) * 1e6).round() as i64;
+        let (days, micros) = (micros.div_euclid(86_400_000_000), micros.rem_euclid(86_400_000_000));
+        let (year, month, day) = civil_from_days(days + UNIX_DAYS_AT_J2000);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+            year, month, day,
+            micros / 3_600_000_000,
+            micros / 60_000_000 % 60,
+            micros / 1_000_000 % 60,
+            micros % 1_000_000
+        )
+    }
+}
+
+impl TryFrom<String> for Epoch {
+    type Error = OrbitError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Epoch> for String {
+    fn from(epoch: Epoch) -> Self {
+        epoch.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 1, 1), UNIX_DAYS_AT_J2000);
+        assert_eq!(days_from_civil(2000, 3, 1), UNIX_DAYS_AT_J2000 + 60); // Leap year
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        for days in (-800_000..800_000).step_by(997) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+    }
+
+    #[test]
+    fn j2000_epoch() {
+        assert_eq!(Epoch::parse("2000-01-01T12:00:00Z").unwrap().seconds_since_j2000, 0.0);
+        assert_eq!(Epoch::default().to_string(), "2000-01-01T12:00:00.000000Z");
+        assert_eq!(Epoch::parse("2000-01-02T12:00:00").unwrap().days_since_j2000(), 1.0);
+    }
+
+    #[test]
+    fn parse_display_round_trip() {
+        for text in ["2024-05-01T08:30:15.250000Z", "1957-10-04T19:28:34.000000Z", "2100-12-31T23:59:59.999999Z"] {
+            assert_eq!(Epoch::parse(text).unwrap().to_string(), text);
+        }
+        assert_eq!(
+            Epoch::parse("2024-05-01 08:30:15").unwrap(),
+            Epoch::from_calendar(2024, 5, 1, 8, 30, 15.0)
+        );
+    }
+
+    #[test]
+    fn invalid_epochs() {
+        for text in ["", "2024-05-01", "2024-13-01T00:00:00Z", "2024-05-01T24:00:00Z", "2024-05-01T12:00Z", "2024-05-xxT12:00:00Z"] {
+            assert!(matches!(Epoch::parse(text), Err(OrbitError::Epoch(_))), "{}", text);
+        }
+    }
+
+    #[test]
+    fn day_of_year() {
+        assert_eq!(Epoch::from_day_of_year(2024, 1.0), Epoch::from_calendar(2024, 1, 1, 0, 0, 0.0));
+        assert_eq!(Epoch::from_day_of_year(2024, 60.5), Epoch::from_calendar(2024, 2, 29, 12, 0, 0.0));
+    }
+}
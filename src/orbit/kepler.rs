@@ -0,0 +1,184 @@
+use bevy::math::{DQuat, DVec3};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    EARTH_GM_M3_S2,
+    EARTH_J2,
+    EARTH_ROTATION_RATE_RAD_S,
+    WGS84_SEMI_MAJOR_AXIS_M
+};
+
+use super::{Epoch, OrbitError};
+
+/// Maximum number of Newton iterations solving the Kepler equation
+const KEPLER_ITERATIONS: usize = 30;
+
+/// Mean orbital elements at their epoch
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct KeplerianElements {
+    /// Epoch at which the elements are given
+    pub epoch: Epoch,
+    pub semi_major_axis_m: f64,
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+    /// Right ascension of the ascending node
+    pub raan_deg: f64,
+    pub argument_of_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+}
+
+/// Position and velocities of a spacecraft in ECEF frame at an epoch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalState {
+    pub epoch: Epoch,
+    pub position_ecef_m: DVec3,
+    /// Velocity relative to the rotating Earth
+    pub velocity_ecef_m_s: DVec3,
+    /// Inertial velocity, expressed along the ECEF axes
+    pub inertial_velocity_ecef_m_s: DVec3,
+}
+
+/// Greenwich mean sidereal time at `epoch`
+pub fn greenwich_sidereal_angle_rad(epoch: &Epoch) -> f64 {
+    (280.460_618_37 + 360.985_647_366_29 * epoch.days_since_j2000())
+        .rem_euclid(360.0)
+        .to_radians()
+}
+
+/// Eccentric anomaly solving the Kepler equation `E - e·sin(E) = M`
+fn eccentric_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mut e = if eccentricity < 0.8 { mean_anomaly } else { std::f64::consts::PI };
+    for _ in 0..KEPLER_ITERATIONS {
+        let step = (e - eccentricity * e.sin() - mean_anomaly) / (1.0 - eccentricity * e.cos());
+        e -= step;
+        if step.abs() < 1e-13 {
+            break;
+        }
+    }
+    e
+}
+
+impl Default for KeplerianElements {
+    fn default() -> Self {
+        // Sun-synchronous dawn-dusk orbit at about 514 km altitude
+        Self {
+            epoch: Epoch::default(),
+            semi_major_axis_m: 6_892_137.0,
+            eccentricity: 0.001,
+            inclination_deg: 97.44,
+            raan_deg: 0.0,
+            argument_of_perigee_deg: 90.0,
+            mean_anomaly_deg: 0.0,
+        }
+    }
+}
+
+impl KeplerianElements {
+    /// Checks that the elements describe a closed orbit above the Earth center
+    pub fn validate(&self) -> Result<(), OrbitError> {
+        if !(0.0..1.0).contains(&self.eccentricity) {
+            return Err(OrbitError::Elements(format!("eccentricity {} is not in [0, 1)", self.eccentricity)));
+        }
+        if self.semi_major_axis_m.is_nan() || self.semi_major_axis_m <= 0.0 {
+            return Err(OrbitError::Elements(format!(
+                "semi-major axis {} m is not positive",
+                self.semi_major_axis_m
+            )));
+        }
+        Ok(())
+    }
+
+    /// Unperturbed mean motion
+    #[inline]
+    pub fn mean_motion_rad_s(&self) -> f64 {
+        (EARTH_GM_M3_S2 / self.semi_major_axis_m.powi(3)).sqrt()
+    }
+
+    /// Secular rates of the node, perigee and mean anomaly under J2
+    fn secular_rates_rad_s(&self) -> (f64, f64, f64) {
+        let n = self.mean_motion_rad_s();
+        let e2 = self.eccentricity * self.eccentricity;
+        let p = self.semi_major_axis_m * (1.0 - e2);
+        let k = 1.5 * EARTH_J2 * (WGS84_SEMI_MAJOR_AXIS_M / p).powi(2) * n;
+        let (sin_i, cos_i) = self.inclination_deg.to_radians().sin_cos();
+        let sin2_i = sin_i * sin_i;
+        (
+            -k * cos_i,
+            k * (2.0 - 2.5 * sin2_i),
+            n + k * (1.0 - e2).sqrt() * (1.0 - 1.5 * sin2_i)
+        )
+    }
+
+    /// Position and velocity of the spacecraft at `epoch`
+    pub fn propagate(&self, epoch: &Epoch) -> Result<OrbitalState, OrbitError> {
+        self.validate()?;
+        let dt = epoch.seconds_since(&self.epoch);
+        let (raan_rate, perigee_rate, mean_anomaly_rate) = self.secular_rates_rad_s();
+        let raan = self.raan_deg.to_radians() + raan_rate * dt;
+        let perigee = self.argument_of_perigee_deg.to_radians() + perigee_rate * dt;
+        let mean_anomaly = (self.mean_anomaly_deg.to_radians() + mean_anomaly_rate * dt)
+            .rem_euclid(std::f64::consts::TAU);
+
+        // Perifocal frame: X towards the perigee, Z along the orbit angular momentum
+        let (a, e) = (self.semi_major_axis_m, self.eccentricity);
+        let ecc_anomaly = eccentric_anomaly(mean_anomaly, e);
+        let (sin_e, cos_e) = ecc_anomaly.sin_cos();
+        let b_over_a = (1.0 - e * e).sqrt();
+        let radius = a * (1.0 - e * cos_e);
+        let position = DVec3::new(a * (cos_e - e), a * b_over_a * sin_e, 0.0);
+        let velocity = (EARTH_GM_M3_S2 * a).sqrt() / radius * DVec3::new(-sin_e, b_over_a * cos_e, 0.0);
+
+        let perifocal_to_ecef = DQuat::from_rotation_z(-greenwich_sidereal_angle_rad(epoch))
+            * DQuat::from_rotation_z(raan)
+            * DQuat::from_rotation_x(self.inclination_deg.to_radians())
+            * DQuat::from_rotation_z(perigee);
+        let position_ecef_m = perifocal_to_ecef * position;
+        let inertial_velocity_ecef_m_s = perifocal_to_ecef * velocity;
+        let earth_rotation = DVec3::new(0.0, 0.0, EARTH_ROTATION_RATE_RAD_S);
+
+        Ok(OrbitalState {
+            epoch: *epoch,
+            position_ecef_m,
+            velocity_ecef_m_s: inertial_velocity_ecef_m_s - earth_rotation.cross(position_ecef_m),
+            inertial_velocity_ecef_m_s,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Node rate of a Sun-synchronous orbit: one turn per tropical year
+    const SUN_SYNCHRONOUS_NODE_RATE_DEG_DAY: f64 = 360.0 / 365.2422;
+
+    #[test]
+    fn default_orbit_is_sun_synchronous() {
+        let (raan_rate, _, _) = KeplerianElements::default().secular_rates_rad_s();
+        let raan_rate_deg_day = raan_rate.to_degrees() * 86_400.0;
+        assert!((raan_rate_deg_day - SUN_SYNCHRONOUS_NODE_RATE_DEG_DAY).abs() < 0.005, "{}", raan_rate_deg_day);
+    }
+
+    #[test]
+    fn perigee_radius_at_epoch() {
+        let elements = KeplerianElements::default();
+        let state = elements.propagate(&elements.epoch).unwrap();
+        let perigee_m = elements.semi_major_axis_m * (1.0 - elements.eccentricity);
+        assert!((state.position_ecef_m.length() - perigee_m).abs() < 1e-6);
+        // Velocity normal to the radius at perigee
+        assert!(state.inertial_velocity_ecef_m_s.dot(state.position_ecef_m).abs() < 1e-6 * perigee_m);
+    }
+
+    #[test]
+    fn invalid_elements() {
+        for elements in [
+            KeplerianElements { eccentricity: 1.0, ..Default::default() },
+            KeplerianElements { eccentricity: -0.1, ..Default::default() },
+            KeplerianElements { semi_major_axis_m: 0.0, ..Default::default() },
+            KeplerianElements { semi_major_axis_m: f64::NAN, ..Default::default() }
+        ] {
+            assert!(matches!(elements.validate(), Err(OrbitError::Elements(_))), "{:?}", elements);
+        }
+    }
+}
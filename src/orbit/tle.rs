@@ -0,0 +1,115 @@
+use crate::constants::EARTH_GM_M3_S2;
+
+use super::{Epoch, KeplerianElements, OrbitError};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Columns (1-based, inclusive, as in the format specification) of a TLE field
+fn field<'a>(line: &'a str, first: usize, last: usize, name: &str) -> Result<&'a str, OrbitError> {
+    line.get(first - 1..last)
+        .map(str::trim)
+        .ok_or_else(|| OrbitError::Tle(format!("line too short to hold the {}", name)))
+}
+
+fn number(line: &str, first: usize, last: usize, name: &str) -> Result<f64, OrbitError> {
+    let text = field(line, first, last, name)?;
+    text.parse()
+        .map_err(|_| OrbitError::Tle(format!("invalid {} `{}`", name, text)))
+}
+
+/// Checks the line number and the modulo 10 checksum of the last column
+fn check_line(line: &str, number: char) -> Result<(), OrbitError> {
+    if !line.is_ascii() || line.len() != 69 {
+        return Err(OrbitError::Tle(format!("line {} must hold 69 ASCII characters", number)));
+    }
+    if !line.starts_with(number) {
+        return Err(OrbitError::Tle(format!("line {} must start with `{}`", number, number)));
+    }
+    let (data, checksum) = line.split_at(68);
+    let sum: u32 = data
+        .chars()
+        .map(|c| match c {
+            '-' => 1,
+            _ => c.to_digit(10).unwrap_or(0),
+        })
+        .sum();
+    if checksum.parse::<u32>().ok() != Some(sum % 10) {
+        return Err(OrbitError::Tle(format!("checksum mismatch on line {}", number)));
+    }
+    Ok(())
+}
+
+impl KeplerianElements {
+    /// Mean elements of a NORAD Two-Line Element set. The Kozai mean motion is used
+    /// as is to derive the semi-major axis.
+    pub fn from_tle(line1: &str, line2: &str) -> Result<Self, OrbitError> {
+        let (line1, line2) = (line1.trim_end(), line2.trim_end());
+        check_line(line1, '1')?;
+        check_line(line2, '2')?;
+        if field(line1, 3, 7, "catalog number")? != field(line2, 3, 7, "catalog number")? {
+            return Err(OrbitError::Tle("lines describe different satellites".to_string()));
+        }
+
+        let two_digit_year = number(line1, 19, 20, "epoch year")? as i64;
+        // Years 57 to 99 belong to the 20th century (first satellites)
+        let year = if two_digit_year < 57 { 2000 + two_digit_year } else { 1900 + two_digit_year };
+        let epoch = Epoch::from_day_of_year(year, number(line1, 21, 32, "epoch day")?);
+
+        let eccentricity_digits = field(line2, 27, 33, "eccentricity")?;
+        let eccentricity = format!("0.{}", eccentricity_digits)
+            .parse()
+            .map_err(|_| OrbitError::Tle(format!("invalid eccentricity `{}`", eccentricity_digits)))?;
+        let revs_per_day = number(line2, 53, 63, "mean motion")?;
+        if revs_per_day <= 0.0 {
+            return Err(OrbitError::Tle(format!("invalid mean motion {}", revs_per_day)));
+        }
+        let mean_motion_rad_s = revs_per_day * std::f64::consts::TAU / SECONDS_PER_DAY;
+
+        let elements = Self {
+            epoch,
+            semi_major_axis_m: (EARTH_GM_M3_S2 / (mean_motion_rad_s * mean_motion_rad_s)).cbrt(),
+            eccentricity,
+            inclination_deg: number(line2, 9, 16, "inclination")?,
+            raan_deg: number(line2, 18, 25, "right ascension of the ascending node")?,
+            argument_of_perigee_deg: number(line2, 35, 42, "argument of perigee")?,
+            mean_anomaly_deg: number(line2, 44, 51, "mean anomaly")?,
+        };
+        elements.validate()?;
+        Ok(elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// International Space Station TLE (epoch 2008-09-20)
+    const ISS_LINE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const ISS_LINE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn iss_elements() {
+        let elements = KeplerianElements::from_tle(ISS_LINE1, ISS_LINE2).unwrap();
+        let epoch = Epoch::from_calendar(2008, 9, 20, 0, 0, 0.0).offset(0.517_825_28 * SECONDS_PER_DAY);
+        assert!(elements.epoch.seconds_since(&epoch).abs() < 1e-3);
+        assert_eq!(elements.inclination_deg, 51.6416);
+        assert_eq!(elements.raan_deg, 247.4627);
+        assert_eq!(elements.eccentricity, 0.000_670_3);
+        assert_eq!(elements.argument_of_perigee_deg, 130.536);
+        assert_eq!(elements.mean_anomaly_deg, 325.0288);
+        assert!((elements.semi_major_axis_m - 6_730_960.7).abs() < 1.0);
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let line2 = ISS_LINE2.replace("15.72125391563537", "15.72125391563538");
+        let err = KeplerianElements::from_tle(ISS_LINE1, &line2).unwrap_err();
+        assert_eq!(err, OrbitError::Tle("checksum mismatch on line 2".to_string()));
+    }
+
+    #[test]
+    fn malformed_lines() {
+        assert!(matches!(KeplerianElements::from_tle(&ISS_LINE1[..60], ISS_LINE2), Err(OrbitError::Tle(_))));
+        assert!(matches!(KeplerianElements::from_tle(ISS_LINE2, ISS_LINE1), Err(OrbitError::Tle(_))));
+    }
+}
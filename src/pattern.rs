@@ -0,0 +1,499 @@
+//! Antenna radiation patterns: analytic aperture models scaled to the antenna 3 dB
+//! beam widths, principal plane cuts tabulated in a file, or two-dimensional gain
+//! grids measured over azimuth and elevation.
+//!
+//! The azimuth angle of a direction is its angle in the antenna XY plane and its
+//! elevation angle its angle in the antenna XZ plane. Analytic and tabulated cut
+//! patterns are separable: the gain in a direction is the product of the azimuth cut
+//! gain at its azimuth angle and of the elevation cut gain at its elevation angle.
+//! Gains are relative to the antenna peak gain, and null behind the antenna.
+//!
+//! Tabulated cuts are read from CSV files with a header row naming the columns, in any
+//! order, separated by commas or semicolons. Lines starting with `#` are comments.
+//! The columns are (with accepted aliases):
+//! - `angle` (`angle_deg`): off-boresight angle in degrees, strictly increasing
+//! - `azimuth` (`azimuth_db`, `azimuth_gain_db`), `elevation` (`elevation_db`,
+//!   `elevation_gain_db`): cut gains in dB, left empty where a cut is not sampled
+//!
+//! Each cut is normalized to its maximum and interpolated linearly in dB, and held at
+//! its first or last sample outside of the tabulated angles.
+//!
+//! Gain grids are read by [`GainGrid`], and the 3 dB beam widths of file patterns are
+//! derived from their samples through the pattern peak.
+
+use bevy::math::DVec2;
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::{PI, TAU},
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc
+};
+
+mod grid;
+pub use grid::GainGrid;
+
+/// Values `x` of the uniform (`sinc(x)²`) and cosine-tapered aperture cuts at half power
+const SINC2_HALF_POWER_X: f64 = 1.391_557_378_251_510_3;
+const COSINE_HALF_POWER_X: f64 = 1.867_621_510_577_969_9;
+/// Off-boresight angle step with which the beam contour is searched for
+const CONTOUR_SEARCH_STEP_RAD: f64 = 0.5 * PI / 180.0;
+/// Largest off-boresight angle of the beam contour
+const CONTOUR_MAX_ANGLE_RAD: f64 = 89.5 * PI / 180.0;
+const CONTOUR_BISECTION_ITERATIONS: usize = 30;
+/// Relative gain in dB at half power
+const HALF_POWER_DB: f64 = -3.010_299_956_639_812;
+
+/// Column names accepted for the angle and for each cut
+const COLUMNS: [(&str, &[&str]); 3] = [
+    ("angle", &["angle", "angle_deg"]),
+    ("azimuth", &["azimuth", "azimuth_db", "azimuth_gain_db"]),
+    ("elevation", &["elevation", "elevation_db", "elevation_gain_db"]),
+];
+
+/// A pattern cut: (angle in radians, gain in dB) samples sorted by increasing angle,
+/// normalized to a 0 dB maximum
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternCut {
+    pub samples: Vec<(f64, f64)>,
+}
+
+/// Azimuth and elevation cuts of a tabulated pattern
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternCuts {
+    pub azimuth: PatternCut,
+    pub elevation: PatternCut,
+    /// Equivalent 3 dB beam widths of the cuts (x: azimuth, y: elevation)
+    pub beam_widths_rad: DVec2,
+}
+
+#[derive(Debug)]
+pub enum PatternError {
+    Io(io::Error),
+    /// Invalid content at a 1-based line number
+    Parse { line: usize, message: String },
+}
+
+/// Pattern cuts file
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TabulatedPattern {
+    /// CSV pattern file, relative paths being resolved from the scenario file directory
+    pub path: PathBuf,
+    /// Cuts loaded from the file
+    #[serde(skip)]
+    pub cuts: Arc<PatternCuts>,
+}
+
+/// Gain grid file
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct GridPattern {
+    /// Gain grid file, relative paths being resolved from the scenario file directory
+    pub path: PathBuf,
+    /// Grid loaded from the file
+    #[serde(skip)]
+    pub grid: Arc<GainGrid>,
+}
+
+/// Antenna radiation pattern model
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum AntennaPattern {
+    /// Uniformly illuminated rectangular aperture: sinc² cuts
+    Sinc2,
+    /// Gaussian beam
+    #[default]
+    Gaussian,
+    /// Rectangular aperture with a cosine illumination taper: wider beam than the
+    /// uniform aperture of same size, with lower sidelobes
+    Cosine,
+    /// Cuts tabulated in a file, independent of the antenna beam widths
+    Tabulated(TabulatedPattern),
+    /// Gain grid measured over azimuth and elevation, independent of the antenna beam
+    /// widths
+    Grid(GridPattern),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<io::Error> for PatternError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+fn parse_error(line: usize, message: String) -> PatternError {
+    PatternError::Parse { line, message }
+}
+
+/// Field separator of a CSV line
+fn separator(line: &str) -> char {
+    if line.contains(';') { ';' } else { ',' }
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+}
+
+/// Width between the half power crossings on each side of the peak of a cut given as
+/// (angle, gain in dB) samples, normalized to 0 dB and linearly interpolated in dB.
+/// `None` if the cut does not fall to half power on both sides.
+fn half_power_width(samples: &[(f64, f64)]) -> Option<f64> {
+    let peak = samples.iter().position(|&(_, gain)| gain >= 0.0)?;
+    let crossing = |(a0, g0): (f64, f64), (a1, g1): (f64, f64)| a0 + (HALF_POWER_DB - g0) / (g1 - g0) * (a1 - a0);
+    let left = samples[..=peak]
+        .windows(2)
+        .rev()
+        .find(|pair| pair[0].1 <= HALF_POWER_DB)
+        .map(|pair| crossing(pair[0], pair[1]))?;
+    let right = samples[peak..]
+        .windows(2)
+        .find(|pair| pair[1].1 <= HALF_POWER_DB)
+        .map(|pair| crossing(pair[0], pair[1]))?;
+    Some(right - left)
+}
+
+impl PatternCut {
+    /// Gain in dB at `angle_rad`
+    pub fn gain_db(&self, angle_rad: f64) -> f64 {
+        let samples = &self.samples;
+        let next = samples.partition_point(|&(angle, _)| angle <= angle_rad);
+        match next {
+            0 => samples.first().map_or(0.0, |&(_, gain)| gain),
+            n if n == samples.len() => samples[n - 1].1,
+            n => {
+                let ((a0, g0), (a1, g1)) = (samples[n - 1], samples[n]);
+                g0 + (angle_rad - a0) / (a1 - a0) * (g1 - g0)
+            }
+        }
+    }
+
+    /// Shifts the gains so that their maximum is 0 dB
+    fn normalize(&mut self) {
+        let max = self.samples.iter().fold(f64::NEG_INFINITY, |max, &(_, gain)| max.max(gain));
+        for (_, gain) in &mut self.samples {
+            *gain -= max;
+        }
+    }
+}
+
+impl PatternCuts {
+    pub fn from_csv_str(s: &str) -> Result<Self, PatternError> {
+        let error = parse_error;
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(k, line)| (k + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (header_line, header) = lines.next().ok_or_else(|| error(1, "missing header row".to_string()))?;
+        let names: Vec<String> = header
+            .split(separator(header))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let mut indices = [0; 3];
+        let mut missing = Vec::new();
+        for (index, (field, aliases)) in indices.iter_mut().zip(COLUMNS) {
+            match names.iter().position(|name| aliases.contains(&name.as_str())) {
+                Some(column) => *index = column,
+                None => missing.push(field),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(error(header_line, format!("missing column(s): {}", missing.join(", "))));
+        }
+        let [angle_column, azimuth_column, elevation_column] = indices;
+
+        let mut cuts = Self::default();
+        let mut last_angle = f64::NEG_INFINITY;
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(separator(line)).map(str::trim).collect();
+            let value = |column: usize, field: &str| -> Result<Option<f64>, PatternError> {
+                match fields.get(column).copied() {
+                    None | Some("") => Ok(None),
+                    Some(text) => text
+                        .parse()
+                        .ok()
+                        .filter(|value: &f64| value.is_finite())
+                        .map(Some)
+                        .ok_or_else(|| error(line_number, format!("invalid {} value `{}`", field, text))),
+                }
+            };
+            let angle_deg = value(angle_column, "angle")?
+                .ok_or_else(|| error(line_number, "missing angle value".to_string()))?;
+            if angle_deg <= last_angle {
+                return Err(error(line_number, format!("angle {} does not increase", angle_deg)));
+            }
+            if angle_deg.abs() > 180.0 {
+                return Err(error(line_number, format!("angle {} out of [-180, 180]", angle_deg)));
+            }
+            last_angle = angle_deg;
+            for (column, field, cut) in [
+                (azimuth_column, "azimuth", &mut cuts.azimuth),
+                (elevation_column, "elevation", &mut cuts.elevation),
+            ] {
+                if let Some(gain_db) = value(column, field)? {
+                    cut.samples.push((angle_deg.to_radians(), gain_db));
+                }
+            }
+        }
+        for (field, cut) in [("azimuth", &mut cuts.azimuth), ("elevation", &mut cuts.elevation)] {
+            if cut.samples.len() < 2 {
+                return Err(error(header_line, format!("the {} cut needs at least 2 samples", field)));
+            }
+            cut.normalize();
+        }
+        let width = |field: &str, cut: &PatternCut| half_power_width(&cut.samples).ok_or_else(|| error(
+            header_line,
+            format!("the {} cut does not fall 3 dB below its peak on both sides", field)
+        ));
+        cuts.beam_widths_rad = DVec2::new(width("azimuth", &cuts.azimuth)?, width("elevation", &cuts.elevation)?);
+        Ok(cuts)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PatternError> {
+        Self::from_csv_str(&fs::read_to_string(path)?)
+    }
+}
+
+impl PartialEq for TabulatedPattern {
+    /// Cuts are compared by identity, as they are replaced rather than modified
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && Arc::ptr_eq(&self.cuts, &other.cuts)
+    }
+}
+
+impl PartialEq for GridPattern {
+    /// Grids are compared by identity, as they are replaced rather than modified
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && Arc::ptr_eq(&self.grid, &other.grid)
+    }
+}
+
+impl GridPattern {
+    /// Loads the gain grid file `path`, resolved from `base_dir` when relative
+    pub fn load(path: PathBuf, base_dir: &Path) -> Result<Self, PatternError> {
+        let grid = GainGrid::load(base_dir.join(&path))?;
+        Ok(Self {
+            path,
+            grid: Arc::new(grid),
+        })
+    }
+}
+
+impl TabulatedPattern {
+    /// Loads the pattern file `path`, resolved from `base_dir` when relative
+    pub fn load(path: PathBuf, base_dir: &Path) -> Result<Self, PatternError> {
+        let cuts = PatternCuts::load(base_dir.join(&path))?;
+        Ok(Self {
+            path,
+            cuts: Arc::new(cuts),
+        })
+    }
+}
+
+/// Power gain of an aperture cut `(f(x) / f(0))²` at the off-boresight angle `angle_rad`,
+/// `f` reaching half power at `x_half_power` for the angle `half_width_rad`
+fn aperture_cut(f: fn(f64) -> f64, x_half_power: f64, angle_rad: f64, half_width_rad: f64) -> f64 {
+    let x = x_half_power * angle_rad.sin() / half_width_rad.sin();
+    let value = f(x);
+    value * value
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 { 1.0 } else { x.sin() / x }
+}
+
+/// Far field of a cosine illumination, normalized to 1 on boresight
+fn cosine_taper(x: f64) -> f64 {
+    let d = 1.0 - (2.0 * x / PI).powi(2);
+    if d.abs() < 1e-9 { 0.25 * PI } else { x.cos() / d }
+}
+
+impl AntennaPattern {
+    /// Human readable name of the model
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sinc2 => "Uniform (sinc²)",
+            Self::Gaussian => "Gaussian",
+            Self::Cosine => "Cosine taper",
+            Self::Tabulated(_) => "Tabulated cuts",
+            Self::Grid(_) => "Gain grid",
+        }
+    }
+
+    /// Imports a pattern file: CSV files with an `angle` column hold pattern cuts, other
+    /// files a gain grid
+    pub fn import(path: PathBuf, base_dir: &Path) -> Result<Self, PatternError> {
+        let cuts = is_csv(&path) && fs::read_to_string(base_dir.join(&path))?
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|header| header
+                .split(separator(header))
+                .any(|name| COLUMNS[0].1.contains(&name.trim().to_ascii_lowercase().as_str()))
+            );
+        if cuts {
+            TabulatedPattern::load(path, base_dir).map(Self::Tabulated)
+        } else {
+            GridPattern::load(path, base_dir).map(Self::Grid)
+        }
+    }
+
+    /// File path of a pattern loaded from a file
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Tabulated(pattern) => Some(&pattern.path),
+            Self::Grid(pattern) => Some(&pattern.path),
+            _ => None,
+        }
+    }
+
+    /// 3 dB beam widths (x: azimuth, y: elevation) set by a pattern file, analytic
+    /// patterns being scaled to the antenna beam widths instead
+    pub fn beam_widths_rad(&self) -> Option<DVec2> {
+        match self {
+            Self::Tabulated(pattern) => Some(pattern.cuts.beam_widths_rad),
+            Self::Grid(pattern) => Some(pattern.grid.beam_widths_rad),
+            _ => None,
+        }
+    }
+
+    /// Relative power gain of a cut at `angle_rad`, `half_width_rad` being its half 3 dB
+    /// beam width and `tabulated` selecting the cut of a tabulated pattern. Gain grids
+    /// have no cuts: their cut gains are 1.
+    fn cut_gain(&self, angle_rad: f64, half_width_rad: f64, tabulated: fn(&PatternCuts) -> &PatternCut) -> f64 {
+        match self {
+            Self::Sinc2 => aperture_cut(sinc, SINC2_HALF_POWER_X, angle_rad, half_width_rad),
+            Self::Gaussian => 0.5f64.powf((angle_rad / half_width_rad).powi(2)),
+            Self::Cosine => aperture_cut(cosine_taper, COSINE_HALF_POWER_X, angle_rad, half_width_rad),
+            Self::Tabulated(pattern) => 10f64.powf(0.1 * tabulated(&pattern.cuts).gain_db(angle_rad)),
+            Self::Grid(_) => 1.0,
+        }
+    }
+
+    /// Relative power gain at the azimuth and elevation angles `angles_rad` (x: azimuth,
+    /// y: elevation, both in ]-π/2, π/2[), for the half 3 dB beam widths `half_widths_rad`
+    pub fn relative_gain(&self, half_widths_rad: DVec2, angles_rad: DVec2) -> f64 {
+        if let Self::Grid(pattern) = self {
+            return 10f64.powf(0.1 * pattern.grid.gain_db(angles_rad));
+        }
+        self.cut_gain(angles_rad.x, half_widths_rad.x, |cuts| &cuts.azimuth)
+            * self.cut_gain(angles_rad.y, half_widths_rad.y, |cuts| &cuts.elevation)
+    }
+
+    /// Beam contour at `level_db` below the peak gain, as the tangents of its off-boresight
+    /// angles at `segments` polar angles of the beam cross-section (0 along the azimuth
+    /// axis, π/2 along the elevation axis). The contour is the edge of the main lobe:
+    /// sidelobes above the level are not included.
+    pub fn beam_contour(&self, half_widths_rad: DVec2, level_db: f64, segments: usize) -> Vec<f64> {
+        let level = 10f64.powf(0.1 * level_db);
+        (0..segments)
+            .map(|k| {
+                let (sin, cos) = (TAU * k as f64 / segments as f64).sin_cos();
+                // Gain at the off-boresight angle `psi` along the polar angle
+                let gain = |psi: f64| {
+                    let r = psi.tan();
+                    self.relative_gain(half_widths_rad, DVec2::new((r * cos).atan(), (r * sin).atan()))
+                };
+                let mut outside = CONTOUR_SEARCH_STEP_RAD;
+                while outside < CONTOUR_MAX_ANGLE_RAD && gain(outside) >= level {
+                    outside += CONTOUR_SEARCH_STEP_RAD;
+                }
+                if outside >= CONTOUR_MAX_ANGLE_RAD {
+                    return CONTOUR_MAX_ANGLE_RAD.tan();
+                }
+                let (mut a, mut b) = (outside - CONTOUR_SEARCH_STEP_RAD, outside);
+                for _ in 0..CONTOUR_BISECTION_ITERATIONS {
+                    let m = 0.5 * (a + b);
+                    if gain(m) >= level { a = m } else { b = m }
+                }
+                a.tan()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error_line(result: Result<PatternCuts, PatternError>) -> usize {
+        match result {
+            Err(PatternError::Parse { line, .. }) => line,
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    /// Gaussian cut of 3 dB width `width_deg`, sampled every `step_deg` over ±`extent_deg`
+    fn gaussian_cut(width_deg: f64, step_deg: f64, extent_deg: f64) -> Vec<(f64, f64)> {
+        let count = (extent_deg / step_deg).round() as i64;
+        (-count..=count)
+            .map(|k| {
+                let angle_deg = k as f64 * step_deg;
+                (angle_deg, HALF_POWER_DB * (2.0 * angle_deg / width_deg).powi(2))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaussian_cuts_widths() {
+        let mut text = "# Measured cuts\nangle_deg;azimuth_gain_db;Elevation_dB\n".to_string();
+        for ((angle_deg, azimuth_db), (_, elevation_db)) in gaussian_cut(8.0, 0.5, 30.0).into_iter().zip(gaussian_cut(20.0, 0.5, 30.0)) {
+            // Gains are normalized to their maximum
+            text += &format!("{}; {}; {}\n", angle_deg, azimuth_db + 12.0, elevation_db - 3.0);
+        }
+        let cuts = PatternCuts::from_csv_str(&text).unwrap();
+        assert_eq!(cuts.azimuth.gain_db(0.0), 0.0);
+        assert_eq!(cuts.elevation.gain_db(0.0), 0.0);
+        assert!((cuts.beam_widths_rad.x.to_degrees() - 8.0).abs() < 0.01, "{:?}", cuts.beam_widths_rad);
+        assert!((cuts.beam_widths_rad.y.to_degrees() - 20.0).abs() < 0.01, "{:?}", cuts.beam_widths_rad);
+        // Held at the last sample outside of the tabulated angles
+        assert_eq!(cuts.azimuth.gain_db(PI), cuts.azimuth.samples.last().unwrap().1);
+    }
+
+    #[test]
+    fn cuts_sampled_at_different_angles() {
+        let cuts = PatternCuts::from_csv_str(
+            "elevation,angle,azimuth\n\
+            ,-20,-10\n\
+            -10,-10,\n\
+            0,0,0\n\
+            -10,10,\n\
+            ,20,-10\n"
+        ).unwrap();
+        assert_eq!(cuts.azimuth.samples.len(), 3);
+        assert_eq!(cuts.elevation.samples.len(), 3);
+        assert!((cuts.beam_widths_rad.x.to_degrees() - 2.0 * 20.0 * HALF_POWER_DB / -10.0).abs() < 1e-9);
+        assert!((cuts.beam_widths_rad.y.to_degrees() - 2.0 * 10.0 * HALF_POWER_DB / -10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_cuts() {
+        let header = "angle,azimuth,elevation\n";
+        assert_eq!(parse_error_line(PatternCuts::from_csv_str("")), 1);
+        assert_eq!(parse_error_line(PatternCuts::from_csv_str("angle,azimuth\n")), 1);
+        let cuts = |rows: &str| PatternCuts::from_csv_str(&format!("{}{}", header, rows));
+        assert_eq!(parse_error_line(cuts("-10,-10,-10\n0,0,0\n0,-10,-10\n")), 4);
+        assert_eq!(parse_error_line(cuts("-190,-10,-10\n0,0,0\n")), 2);
+        assert_eq!(parse_error_line(cuts(",-10,-10\n")), 2);
+        assert_eq!(parse_error_line(cuts("-10,x,-10\n")), 2);
+        assert_eq!(parse_error_line(cuts("-10,-10,nan\n")), 2);
+        assert_eq!(parse_error_line(cuts("-10,inf,-10\n")), 2);
+        // Cuts with a single sample, or not falling 3 dB on both sides
+        assert_eq!(parse_error_line(cuts("-10,-10,\n0,0,0\n")), 1);
+        assert_eq!(parse_error_line(cuts("-10,-10,-1\n0,0,0\n10,-10,-10\n")), 1);
+    }
+}
@@ -0,0 +1,469 @@
+use bevy::math::DVec2;
+use std::{
+    f64::consts::PI,
+    fs,
+    path::Path
+};
+
+use super::{half_power_width, parse_error, separator, PatternError};
+
+/// Angle unit of a gain grid file
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AngleUnit {
+    Degree,
+    Radian,
+}
+
+/// Gain unit of a gain grid file
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GainUnit {
+    Decibel,
+    Linear,
+}
+
+impl AngleUnit {
+    fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "deg" | "degree" | "degrees" => Some(Self::Degree),
+            "rad" | "radian" | "radians" => Some(Self::Radian),
+            _ => None,
+        }
+    }
+
+    /// Angle in radians, checked to be in [-180°, 180°], which catches most angles
+    /// given in the wrong unit
+    fn to_radians(self, angle: f64) -> Result<f64, String> {
+        let angle_rad = match self {
+            Self::Degree => angle.to_radians(),
+            Self::Radian => angle,
+        };
+        if angle_rad.is_finite() && angle_rad.abs() <= PI + 1e-9 {
+            Ok(angle_rad)
+        } else {
+            Err(format!("angle {} out of [-180°, 180°]", angle))
+        }
+    }
+}
+
+impl GainUnit {
+    fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "db" | "dbi" => Some(Self::Decibel),
+            "linear" | "lin" => Some(Self::Linear),
+            _ => None,
+        }
+    }
+
+    /// Gain in dB, linear gains having to be positive
+    fn to_db(self, gain: f64) -> Result<f64, String> {
+        match self {
+            _ if !gain.is_finite() => Err(format!("invalid gain {}", gain)),
+            Self::Decibel => Ok(gain),
+            Self::Linear if gain > 0.0 => Ok(10.0 * gain.log10()),
+            Self::Linear => Err(format!("linear gain {} is not positive", gain)),
+        }
+    }
+}
+
+/// Two-dimensional gain grid measured over azimuth and elevation angles, normalized to
+/// a 0 dB maximum
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GainGrid {
+    /// Strictly increasing azimuth angles (radians)
+    pub azimuths_rad: Vec<f64>,
+    /// Strictly increasing elevation angles (radians)
+    pub elevations_rad: Vec<f64>,
+    /// Gains (dB), one row of azimuth samples per elevation
+    pub gains_db: Vec<f64>,
+    /// Equivalent 3 dB beam widths (x: azimuth, y: elevation) through the grid peak
+    pub beam_widths_rad: DVec2,
+}
+
+impl GainGrid {
+    /// Checks the grid, then normalizes it and derives its 3 dB beam widths.
+    /// `line` is the line reported for errors on the whole grid.
+    fn finish(mut self, line: usize) -> Result<Self, PatternError> {
+        for (axis, angles) in [("azimuth", &self.azimuths_rad), ("elevation", &self.elevations_rad)] {
+            if angles.len() < 2 {
+                return Err(parse_error(line, format!("the {} axis needs at least 2 angles", axis)));
+            }
+        }
+        let max = self.gains_db.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for gain in &mut self.gains_db {
+            *gain -= max;
+        }
+
+        let columns = self.azimuths_rad.len();
+        let peak = self.gains_db.iter().position(|&gain| gain == 0.0).unwrap_or_default();
+        let (row, column) = (peak / columns, peak % columns);
+        let azimuth_cut: Vec<(f64, f64)> = self.azimuths_rad
+            .iter()
+            .zip(&self.gains_db[row * columns..(row + 1) * columns])
+            .map(|(&angle, &gain)| (angle, gain))
+            .collect();
+        let elevation_cut: Vec<(f64, f64)> = self.elevations_rad
+            .iter()
+            .enumerate()
+            .map(|(k, &angle)| (angle, self.gains_db[k * columns + column]))
+            .collect();
+        let width = |axis: &str, cut: &[(f64, f64)]| half_power_width(cut).ok_or_else(|| parse_error(
+            line,
+            format!("the gain does not fall 3 dB below its peak on both sides along the {} axis", axis)
+        ));
+        self.beam_widths_rad = DVec2::new(width("azimuth", &azimuth_cut)?, width("elevation", &elevation_cut)?);
+        Ok(self)
+    }
+
+    /// Reads a grid from a CSV file with one sample per row and a header row naming the
+    /// `azimuth`, `elevation` and `gain` columns
+    pub fn from_csv_str(s: &str) -> Result<Self, PatternError> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(k, line)| (k + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (header_line, header) = lines.next().ok_or_else(|| parse_error(1, "missing header row".to_string()))?;
+
+        // Column index and unit of the azimuth, elevation and gain columns
+        let mut azimuth = None;
+        let mut elevation = None;
+        let mut gain = None;
+        for (column, name) in header.split(separator(header)).enumerate() {
+            let name = name.trim().to_ascii_lowercase();
+            let (quantity, unit) = name.split_once('_').unwrap_or((&name, ""));
+            let unsupported = || parse_error(header_line, format!("unsupported unit in column `{}`", name));
+            match quantity {
+                "azimuth" | "elevation" => {
+                    let unit = if unit.is_empty() { AngleUnit::Degree } else {
+                        AngleUnit::parse(unit).ok_or_else(unsupported)?
+                    };
+                    let slot = if quantity == "azimuth" { &mut azimuth } else { &mut elevation };
+                    *slot = Some((column, unit));
+                },
+                "gain" => {
+                    let unit = if unit.is_empty() { GainUnit::Decibel } else {
+                        GainUnit::parse(unit).ok_or_else(unsupported)?
+                    };
+                    gain = Some((column, unit));
+                },
+                _ => {}
+            }
+        }
+        let missing: Vec<&str> = [("azimuth", azimuth.is_none()), ("elevation", elevation.is_none()), ("gain", gain.is_none())]
+            .into_iter()
+            .filter_map(|(name, missing)| missing.then_some(name))
+            .collect();
+        let (Some((azimuth_column, azimuth_unit)), Some((elevation_column, elevation_unit)), Some((gain_column, gain_unit))) =
+            (azimuth, elevation, gain) else {
+            return Err(parse_error(header_line, format!("missing column(s): {}", missing.join(", "))));
+        };
+
+        // (line, azimuth, elevation, gain) samples
+        let mut samples = Vec::new();
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(separator(line)).map(str::trim).collect();
+            let value = |column: usize, field: &str| -> Result<f64, PatternError> {
+                let text = fields.get(column).copied().unwrap_or_default();
+                text.parse().map_err(|_| parse_error(line_number, format!("invalid {} value `{}`", field, text)))
+            };
+            let invalid = |message: String| parse_error(line_number, message);
+            samples.push((
+                line_number,
+                azimuth_unit.to_radians(value(azimuth_column, "azimuth")?).map_err(invalid)?,
+                elevation_unit.to_radians(value(elevation_column, "elevation")?).map_err(invalid)?,
+                gain_unit.to_db(value(gain_column, "gain")?).map_err(invalid)?,
+            ));
+        }
+
+        let axis = |angles: Vec<f64>| {
+            let mut angles = angles;
+            angles.sort_by(f64::total_cmp);
+            angles.dedup();
+            angles
+        };
+        let azimuths_rad = axis(samples.iter().map(|sample| sample.1).collect());
+        let elevations_rad = axis(samples.iter().map(|sample| sample.2).collect());
+        let columns = azimuths_rad.len();
+        let mut gains_db = vec![None; columns * elevations_rad.len()];
+        for &(line_number, azimuth_rad, elevation_rad, gain_db) in &samples {
+            let index = |angles: &[f64], angle: f64| angles.partition_point(|&a| a < angle);
+            let cell = &mut gains_db[index(&elevations_rad, elevation_rad) * columns + index(&azimuths_rad, azimuth_rad)];
+            if cell.replace(gain_db).is_some() {
+                return Err(parse_error(line_number, "duplicate azimuth and elevation sample".to_string()));
+            }
+        }
+        let missing = gains_db.iter().filter(|gain| gain.is_none()).count();
+        if missing > 0 {
+            return Err(parse_error(header_line, format!(
+                "incomplete grid: {} of the {} x {} azimuth and elevation samples are missing",
+                missing,
+                columns,
+                elevations_rad.len()
+            )));
+        }
+        Self {
+            azimuths_rad,
+            elevations_rad,
+            gains_db: gains_db.into_iter().flatten().collect(),
+            ..Default::default()
+        }.finish(header_line)
+    }
+
+    /// Reads a grid from a text file made of a header, giving the units and the angles of
+    /// each axis, followed by the gain matrix, one row per elevation:
+    ///
+    /// ```text
+    /// angle_unit deg
+    /// gain_unit dbi
+    /// azimuth -10 -5 0 5 10
+    /// elevation -4 0 4
+    /// -9.1 -4.2 0.0 -4.1 -9.3
+    /// ...
+    /// ```
+    ///
+    /// Values are separated by whitespace, commas or semicolons. The units default to
+    /// degrees and dB, and the angles of each axis must be strictly increasing.
+    pub fn from_matrix_str(s: &str) -> Result<Self, PatternError> {
+        let mut angle_unit = AngleUnit::Degree;
+        let mut gain_unit = GainUnit::Decibel;
+        let mut azimuths = None;
+        let mut elevations = None;
+        let mut rows: Vec<(usize, Vec<f64>)> = Vec::new();
+        let mut first_line = 1;
+        for (line_number, line) in s.lines().enumerate().map(|(k, line)| (k + 1, line.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|field| !field.is_empty());
+            let keyword = fields.next().unwrap_or_default().to_ascii_lowercase();
+            let parse_values = |fields: &mut dyn Iterator<Item = &str>| -> Result<Vec<f64>, PatternError> {
+                fields
+                    .map(|text| text.parse().map_err(|_| parse_error(line_number, format!("invalid value `{}`", text))))
+                    .collect()
+            };
+            let unit = |fields: &mut dyn Iterator<Item = &str>| fields.next().unwrap_or_default().to_ascii_lowercase();
+            if !rows.is_empty() && keyword.parse::<f64>().is_err() {
+                return Err(parse_error(line_number, format!("unexpected `{}` after the gain matrix", keyword)));
+            }
+            match keyword.as_str() {
+                "angle_unit" => {
+                    let unit = unit(&mut fields);
+                    angle_unit = AngleUnit::parse(&unit)
+                        .ok_or_else(|| parse_error(line_number, format!("unsupported angle unit `{}`", unit)))?;
+                },
+                "gain_unit" => {
+                    let unit = unit(&mut fields);
+                    gain_unit = GainUnit::parse(&unit)
+                        .ok_or_else(|| parse_error(line_number, format!("unsupported gain unit `{}`", unit)))?;
+                },
+                "azimuth" => azimuths = Some((line_number, parse_values(&mut fields)?)),
+                "elevation" => elevations = Some((line_number, parse_values(&mut fields)?)),
+                _ => {
+                    if rows.is_empty() {
+                        first_line = line_number;
+                    }
+                    let mut values = vec![keyword.as_str()];
+                    values.extend(fields);
+                    rows.push((line_number, parse_values(&mut values.into_iter())?));
+                }
+            }
+        }
+
+        let mut axis = |name: &str, angles: Option<(usize, Vec<f64>)>| -> Result<Vec<f64>, PatternError> {
+            let (line, angles) = angles.ok_or_else(|| parse_error(first_line, format!("missing {} angles", name)))?;
+            let angles = angles
+                .into_iter()
+                .map(|angle| angle_unit.to_radians(angle).map_err(|message| parse_error(line, message)))
+                .collect::<Result<Vec<f64>, _>>()?;
+            if angles.windows(2).any(|pair| pair[1] <= pair[0]) {
+                return Err(parse_error(line, format!("{} angles do not strictly increase", name)));
+            }
+            first_line = first_line.max(line);
+            Ok(angles)
+        };
+        let azimuths_rad = axis("azimuth", azimuths)?;
+        let elevations_rad = axis("elevation", elevations)?;
+
+        if rows.len() != elevations_rad.len() {
+            return Err(parse_error(first_line, format!(
+                "the gain matrix has {} rows instead of one per elevation ({})",
+                rows.len(),
+                elevations_rad.len()
+            )));
+        }
+        let mut gains_db = Vec::with_capacity(azimuths_rad.len() * elevations_rad.len());
+        for (line_number, row) in rows {
+            if row.len() != azimuths_rad.len() {
+                return Err(parse_error(line_number, format!(
+                    "{} gains instead of one per azimuth ({})",
+                    row.len(),
+                    azimuths_rad.len()
+                )));
+            }
+            for gain in row {
+                gains_db.push(gain_unit.to_db(gain).map_err(|message| parse_error(line_number, message))?);
+            }
+        }
+        Self {
+            azimuths_rad,
+            elevations_rad,
+            gains_db,
+            ..Default::default()
+        }.finish(first_line)
+    }
+
+    /// Loads a grid file, CSV files (`.csv` extension) holding one sample per row and
+    /// other files a header and a gain matrix
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PatternError> {
+        let s = fs::read_to_string(&path)?;
+        if super::is_csv(path.as_ref()) {
+            Self::from_csv_str(&s)
+        } else {
+            Self::from_matrix_str(&s)
+        }
+    }
+
+    /// Gain in dB at the azimuth and elevation angles `angles_rad`, bilinearly
+    /// interpolated and held at the grid edges
+    pub fn gain_db(&self, angles_rad: DVec2) -> f64 {
+        // Lower sample index and interpolation weight along an axis
+        let locate = |angles: &[f64], angle: f64| {
+            let next = angles.partition_point(|&a| a <= angle).clamp(1, angles.len() - 1);
+            let (a0, a1) = (angles[next - 1], angles[next]);
+            (next - 1, ((angle - a0) / (a1 - a0)).clamp(0.0, 1.0))
+        };
+        if self.gains_db.is_empty() {
+            return 0.0;
+        }
+        let (i, u) = locate(&self.azimuths_rad, angles_rad.x);
+        let (j, v) = locate(&self.elevations_rad, angles_rad.y);
+        let columns = self.azimuths_rad.len();
+        let gain = |i: usize, j: usize| self.gains_db[j * columns + i];
+        (1.0 - v) * ((1.0 - u) * gain(i, j) + u * gain(i + 1, j))
+            + v * ((1.0 - u) * gain(i, j + 1) + u * gain(i + 1, j + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pattern::HALF_POWER_DB;
+
+    use super::*;
+
+    fn parse_error_line(result: Result<GainGrid, PatternError>) -> usize {
+        match result {
+            Err(PatternError::Parse { line, .. }) => line,
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    /// Gaussian beam of 3 dB widths 10° (azimuth) and 24° (elevation), sampled every 0.5°
+    /// and 1°, as (azimuth, elevation, gain) in degrees and dB
+    fn gaussian_samples() -> Vec<(f64, f64, f64)> {
+        (-30..=30)
+            .flat_map(|j| (-40..=40).map(move |i| (0.5 * i as f64, j as f64)))
+            .map(|(azimuth, elevation)| {
+                (azimuth, elevation, HALF_POWER_DB * ((azimuth / 5.0).powi(2) + (elevation / 12.0).powi(2)))
+            })
+            .collect()
+    }
+
+    fn assert_gaussian_grid(grid: &GainGrid) {
+        assert_eq!(grid.azimuths_rad.len(), 81);
+        assert_eq!(grid.elevations_rad.len(), 61);
+        assert!((grid.gain_db(DVec2::ZERO)).abs() < 1e-9);
+        assert!((grid.beam_widths_rad.x.to_degrees() - 10.0).abs() < 0.02, "{:?}", grid.beam_widths_rad);
+        assert!((grid.beam_widths_rad.y.to_degrees() - 24.0).abs() < 0.02, "{:?}", grid.beam_widths_rad);
+    }
+
+    #[test]
+    fn gaussian_grid_widths() {
+        // CSV samples in any order, in radians and linear gains scaled by the peak gain
+        let mut csv = "gain_linear;elevation_rad;azimuth_rad\n".to_string();
+        for (azimuth, elevation, gain_db) in gaussian_samples().into_iter().rev() {
+            csv += &format!("{};{};{}\n", 20.0 * 10f64.powf(0.1 * gain_db), elevation.to_radians(), azimuth.to_radians());
+        }
+        let grid = GainGrid::from_csv_str(&csv).unwrap();
+        assert_gaussian_grid(&grid);
+
+        let samples = gaussian_samples();
+        let mut matrix = "angle_unit degrees\ngain_unit dBi\n".to_string();
+        matrix += &format!("azimuth {}\n", (-40..=40).map(|i| (0.5 * i as f64).to_string()).collect::<Vec<_>>().join(" "));
+        matrix += &format!("elevation {}\n", (-30..=30).map(|j| j.to_string()).collect::<Vec<_>>().join(","));
+        for row in samples.chunks(81) {
+            matrix += &row.iter().map(|sample| (sample.2 + 18.0).to_string()).collect::<Vec<_>>().join("; ");
+            matrix += "\n";
+        }
+        let from_matrix = GainGrid::from_matrix_str(&matrix).unwrap();
+        assert_gaussian_grid(&from_matrix);
+        for (a, b) in from_matrix.gains_db.iter().zip(&grid.gains_db) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn peak_off_boresight() {
+        // 3 dB widths through the peak, at azimuth 10° and elevation -5°
+        let grid = GainGrid::from_csv_str(
+            "azimuth,elevation,gain\n\
+            0,-10,-20\n10,-10,-10\n20,-10,-20\n\
+            0,-5,-10\n10,-5,0\n20,-5,-10\n\
+            0,0,-20\n10,0,-10\n20,0,-20\n"
+        ).unwrap();
+        let expected = 2.0 * (10.0 * HALF_POWER_DB / -10.0);
+        assert!((grid.beam_widths_rad.x.to_degrees() - expected).abs() < 1e-9);
+        assert!((grid.beam_widths_rad.y.to_degrees() - expected / 2.0).abs() < 1e-9);
+        assert_eq!(grid.gain_db(DVec2::new(10f64.to_radians(), -5f64.to_radians())), 0.0);
+    }
+
+    #[test]
+    fn invalid_csv_grids() {
+        let grid = |rows: &str| GainGrid::from_csv_str(&format!("azimuth,elevation,gain\n{}", rows));
+        let complete = "-10,-10,-10\n0,-10,-5\n10,-10,-10\n-10,0,-5\n0,0,0\n10,0,-5\n-10,10,-10\n0,10,-5\n10,10,-10\n";
+        assert!(grid(complete).is_ok());
+
+        assert_eq!(parse_error_line(GainGrid::from_csv_str("")), 1);
+        assert_eq!(parse_error_line(GainGrid::from_csv_str("# comment\nazimuth,gain\n")), 2);
+        // Unit parsing
+        for header in ["azimuth_furlong,elevation,gain", "azimuth,elevation_deg,gain_watt"] {
+            assert_eq!(parse_error_line(GainGrid::from_csv_str(header)), 1, "{}", header);
+        }
+        assert!(GainGrid::from_csv_str(&format!("Azimuth_Degrees;ELEVATION_DEG;gain_dBi\n{}", complete.replace(',', ";"))).is_ok());
+        assert_eq!(parse_error_line(GainGrid::from_csv_str("azimuth_rad,elevation,gain\n4,0,0\n")), 2);
+        assert_eq!(parse_error_line(GainGrid::from_csv_str("azimuth,elevation,gain_linear\n0,0,0\n")), 2);
+        assert_eq!(parse_error_line(GainGrid::from_csv_str("azimuth,elevation,gain_linear\n0,0,-1\n")), 2);
+        // Values
+        assert_eq!(parse_error_line(grid("181,0,0\n")), 2);
+        assert_eq!(parse_error_line(grid("0,x,0\n")), 2);
+        assert_eq!(parse_error_line(grid("0,0\n")), 2);
+        assert_eq!(parse_error_line(grid("0,0,nan\n")), 2);
+        // Duplicate and missing samples, single angle axes
+        assert_eq!(parse_error_line(grid(&format!("{}0,0,-1\n", complete))), 11);
+        assert_eq!(parse_error_line(grid(&complete.replace("0,0,0\n", ""))), 1);
+        assert_eq!(parse_error_line(grid("-10,0,-5\n0,0,0\n10,0,-5\n")), 1);
+        // Gain not falling 3 dB on both sides
+        assert_eq!(parse_error_line(grid(&complete.replace("10,0,-5", "10,0,-1"))), 1);
+    }
+
+    #[test]
+    fn invalid_matrix_grids() {
+        let grid = |text: &str| GainGrid::from_matrix_str(text);
+        assert!(grid("azimuth -10 0 10\nelevation -10 0 10\n-10 -5 -10\n-5 0 -5\n-10 -5 -10\n").is_ok());
+
+        assert_eq!(parse_error_line(grid("angle_unit furlong\n")), 1);
+        assert_eq!(parse_error_line(grid("gain_unit watt\n")), 1);
+        assert_eq!(parse_error_line(grid("azimuth -10 x 10\n")), 1);
+        // Angles not strictly increasing, or out of range
+        assert_eq!(parse_error_line(grid("azimuth -10 0 0\nelevation -10 0 10\n0 0 0\n0 0 0\n0 0 0\n")), 1);
+        assert_eq!(parse_error_line(grid("azimuth -10 0 10\nelevation 10 0 -10\n0 0 0\n0 0 0\n0 0 0\n")), 2);
+        assert_eq!(parse_error_line(grid("angle_unit rad\nazimuth -4 0 4\nelevation -1 0 1\n0 0 0\n0 0 0\n0 0 0\n")), 2);
+        // Missing axes, and matrices not matching them
+        assert_eq!(parse_error_line(grid("elevation -10 0 10\n0 0 0\n")), 2);
+        assert_eq!(parse_error_line(grid("azimuth -10 0 10\nelevation -10 0 10\n-10 -5 -10\n-5 0 -5\n")), 3);
+        assert_eq!(parse_error_line(grid("azimuth -10 0 10\nelevation -10 0 10\n-10 -5 -10\n-5 0\n-10 -5 -10\n")), 4);
+        assert_eq!(parse_error_line(grid("azimuth -10 0 10\n-10 -5 -10\nelevation -10 0 10\n")), 3);
+        assert_eq!(parse_error_line(grid("gain_unit linear\nazimuth -10 0 10\nelevation 0 10\n1 1 1\n1 0 1\n")), 5);
+    }
+}
@@ -0,0 +1,411 @@
+//! Performance report of a scenario, computed without any rendering.
+//!
+//! The report gathers the quantities displayed by the application (footprints,
+//! common illuminated area, bistatic angles, resolutions, NESZ and PRF ambiguities)
+//! and can be checked against user constraints.
+
+use bevy::math::{DVec2, DVec3};
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::{
+    constants::FOOTPRINT_SEGMENTS,
+    geodesy::{GeodeticPosition, LocalFrame},
+    geometry::{linear_to_db, AmbiguityCheck, BistaticGeometry, CommonFootprint, Earth, Footprint, PlatformGeometry},
+    scenario::{PlatformScenario, Scenario},
+    state::{LinkBudgetState, SignalState}
+};
+
+/// Position in both local (World frame) and geodetic forms
+#[derive(Serialize, Clone, Debug)]
+pub struct PositionReport {
+    /// Position in World frame (East, North, Up)
+    pub local_m: DVec3,
+    pub geodetic: GeodeticPosition,
+}
+
+/// Antenna footprint extents
+#[derive(Serialize, Clone, Debug)]
+pub struct FootprintReport {
+    pub area_m2: f64,
+    /// True if the beam reaches the horizon and the footprint has been clipped
+    pub clipped: bool,
+    pub near_ground_range_m: f64,
+    pub far_ground_range_m: f64,
+    pub near_slant_range_m: f64,
+    pub far_slant_range_m: f64,
+}
+
+/// Common Tx/Rx illuminated area extents
+#[derive(Serialize, Clone, Debug)]
+pub struct CommonFootprintReport {
+    pub area_m2: f64,
+    /// Centroid and bounding box corners (East, North)
+    pub centroid_m: DVec2,
+    pub min_m: DVec2,
+    pub max_m: DVec2,
+}
+
+/// Echo timing and Doppler spread over the common area, checked against the PRF
+#[derive(Serialize, Clone, Debug)]
+pub struct AmbiguityReport {
+    pub min_range_sum_m: f64,
+    pub max_range_sum_m: f64,
+    /// Echo window start and end delays after the pulse transmission
+    pub echo_start_s: f64,
+    pub echo_end_s: f64,
+    /// Delay of the direct Tx to Rx signal
+    pub direct_delay_s: f64,
+    pub doppler_bandwidth_hz: f64,
+    pub doppler_centroid_hz: f64,
+    /// Pulses transmitted before the echo window of a pulse starts
+    pub pulses_in_flight: u64,
+    pub doppler_aliased: bool,
+    pub range_ambiguous: bool,
+    pub direct_signal_overlap: bool,
+}
+
+/// Bistatic angles and resolutions at a ground point
+#[derive(Serialize, Clone, Debug)]
+pub struct PointReport {
+    /// Ground point (East, North)
+    pub position_m: DVec2,
+    pub geodetic: GeodeticPosition,
+    pub bistatic_angle_deg: f64,
+    pub incidence_angle_deg: f64,
+    pub ground_range_resolution_m: f64,
+    pub doppler_resolution_hz: f64,
+    pub ground_doppler_resolution_m: f64,
+    pub gradients_angle_deg: f64,
+    /// Resolution cell area, `None` if range and Doppler gradients are colinear
+    pub resolution_cell_area_m2: Option<f64>,
+    /// Tx and Rx antennas gains product, `None` behind an antenna
+    pub two_way_gain_db: Option<f64>,
+    /// Noise-equivalent sigma zero, `None` if undefined or out of the antenna patterns
+    pub nesz_db: Option<f64>,
+}
+
+/// A requested constraint which is not satisfied
+#[derive(Serialize, Clone, Debug)]
+pub struct Violation {
+    pub constraint: String,
+    pub value: f64,
+    pub limit: f64,
+}
+
+/// Performance report of a scenario
+#[derive(Serialize, Clone, Debug)]
+pub struct PerformanceReport {
+    /// Distance between the Tx and Rx antenna phase centers
+    pub baseline_m: f64,
+    /// Carriers positions
+    pub tx_position: PositionReport,
+    pub rx_position: PositionReport,
+    /// Footprints, `None` when the beam does not reach the ground
+    pub tx_footprint: Option<FootprintReport>,
+    pub rx_footprint: Option<FootprintReport>,
+    /// Common illuminated area, `None` when the footprints do not overlap
+    pub common_footprint: Option<CommonFootprintReport>,
+    /// Figures at the scene center (World origin)
+    pub scene_center: PointReport,
+    /// Figures at the centroid of the common area
+    pub common_centroid: Option<PointReport>,
+    /// PRF ambiguities over the common area, `None` when the footprints do not overlap
+    pub ambiguity: Option<AmbiguityReport>,
+    pub violations: Vec<Violation>,
+}
+
+/// Performance requirements, each one being checked only when set
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Constraints {
+    pub max_ground_range_resolution_m: Option<f64>,
+    pub max_ground_doppler_resolution_m: Option<f64>,
+    pub min_bistatic_angle_deg: Option<f64>,
+    pub max_bistatic_angle_deg: Option<f64>,
+    pub min_common_area_m2: Option<f64>,
+}
+
+impl From<&Footprint> for FootprintReport {
+    fn from(footprint: &Footprint) -> Self {
+        Self {
+            area_m2: footprint.area_m2,
+            clipped: footprint.clipped,
+            near_ground_range_m: footprint.near_ground_range_m,
+            far_ground_range_m: footprint.far_ground_range_m,
+            near_slant_range_m: footprint.near_slant_range_m,
+            far_slant_range_m: footprint.far_slant_range_m,
+        }
+    }
+}
+
+impl From<&CommonFootprint> for CommonFootprintReport {
+    fn from(common: &CommonFootprint) -> Self {
+        Self {
+            area_m2: common.area_m2,
+            centroid_m: common.centroid_m,
+            min_m: common.min_m,
+            max_m: common.max_m,
+        }
+    }
+}
+
+impl From<&AmbiguityCheck> for AmbiguityReport {
+    fn from(check: &AmbiguityCheck) -> Self {
+        Self {
+            min_range_sum_m: check.min_range_sum_m,
+            max_range_sum_m: check.max_range_sum_m,
+            echo_start_s: check.echo_start_s,
+            echo_end_s: check.echo_end_s,
+            direct_delay_s: check.direct_delay_s,
+            doppler_bandwidth_hz: check.doppler_bandwidth_hz(),
+            doppler_centroid_hz: 0.5 * (check.min_doppler_hz + check.max_doppler_hz),
+            pulses_in_flight: check.pulses_in_flight,
+            doppler_aliased: check.doppler_aliased,
+            range_ambiguous: check.range_ambiguous,
+            direct_signal_overlap: check.direct_signal_overlap,
+        }
+    }
+}
+
+impl PositionReport {
+    pub fn new(position_m: DVec3, frame: &LocalFrame) -> Self {
+        Self {
+            local_m: position_m,
+            geodetic: frame.enu_to_geodetic(position_m),
+        }
+    }
+}
+
+impl PointReport {
+    pub fn new(
+        geometry: &BistaticGeometry,
+        point: DVec3,
+        signal: &SignalState,
+        budget: &LinkBudgetState,
+        frame: &LocalFrame
+    ) -> Self {
+        let angles = geometry.angles_at(point);
+        let resolution = geometry.resolution_at(point, signal);
+        Self {
+            position_m: point.truncate(),
+            geodetic: frame.enu_to_geodetic(point),
+            bistatic_angle_deg: angles.bistatic_angle_rad.to_degrees(),
+            incidence_angle_deg: angles.incidence_angle_rad.to_degrees(),
+            ground_range_resolution_m: resolution.ground_range_resolution_m,
+            doppler_resolution_hz: resolution.doppler_resolution_hz,
+            ground_doppler_resolution_m: resolution.ground_doppler_resolution_m,
+            gradients_angle_deg: resolution.gradients_angle_rad.to_degrees(),
+            resolution_cell_area_m2: resolution.ellipse.map(|ellipse| ellipse.area_m2()),
+            two_way_gain_db: Some(geometry.two_way_gain(point)).filter(|&gain| gain > 0.0).map(linear_to_db),
+            nesz_db: geometry.nesz_at(point, signal, budget).map(linear_to_db),
+        }
+    }
+}
+
+fn platform_geometry(platform: &PlatformScenario) -> PlatformGeometry {
+    PlatformGeometry::new(&platform.carrier, &platform.antenna, &platform.beam, &platform.pattern)
+}
+
+impl PerformanceReport {
+    /// Computes the report of `scenario`, with no constraint violation
+    pub fn new(scenario: &Scenario) -> Self {
+        let geometry = BistaticGeometry::new(
+            platform_geometry(&scenario.tx),
+            platform_geometry(&scenario.rx),
+            Earth::new(scenario.earth, &scenario.origin)
+        );
+        let footprint = |platform: &PlatformGeometry| platform
+            .footprint(&geometry.earth, platform.footprint_clip_range_m(&geometry.earth), FOOTPRINT_SEGMENTS);
+        let common = geometry.common_footprint(FOOTPRINT_SEGMENTS);
+        let frame = geometry.earth.frame;
+
+        Self {
+            baseline_m: geometry.baseline_m().length(),
+            tx_position: PositionReport::new(geometry.tx.carrier_position_m, &frame),
+            rx_position: PositionReport::new(geometry.rx.carrier_position_m, &frame),
+            tx_footprint: footprint(&geometry.tx).as_ref().map(FootprintReport::from),
+            rx_footprint: footprint(&geometry.rx).as_ref().map(FootprintReport::from),
+            common_footprint: common.as_ref().map(CommonFootprintReport::from),
+            scene_center: PointReport::new(&geometry, DVec3::ZERO, &scenario.signal, &scenario.link_budget, &frame),
+            common_centroid: common.as_ref().map(|common| {
+                let centroid = geometry.earth.ground_point(common.centroid_m);
+                PointReport::new(&geometry, centroid, &scenario.signal, &scenario.link_budget, &frame)
+            }),
+            ambiguity: common
+                .as_ref()
+                .map(|common| AmbiguityReport::from(&geometry.ambiguity_check(common, &scenario.signal))),
+            violations: Vec::new(),
+        }
+    }
+
+    /// Point on which the constraints are checked: the centroid of the common area,
+    /// or the scene center when the footprints do not overlap
+    pub fn reference_point(&self) -> &PointReport {
+        self.common_centroid.as_ref().unwrap_or(&self.scene_center)
+    }
+
+    /// Flat list of the report figures, `None` standing for an undefined value
+    pub fn metrics(&self) -> Vec<(String, Option<f64>)> {
+        let mut metrics = vec![("baseline_m".to_string(), Some(self.baseline_m))];
+        for (name, position) in [("tx_position", &self.tx_position), ("rx_position", &self.rx_position)] {
+            for (field, value) in [
+                ("east_m", position.local_m.x),
+                ("north_m", position.local_m.y),
+                ("up_m", position.local_m.z),
+                ("latitude_deg", position.geodetic.latitude_deg),
+                ("longitude_deg", position.geodetic.longitude_deg),
+                ("height_m", position.geodetic.height_m),
+            ] {
+                metrics.push((format!("{}.{}", name, field), Some(value)));
+            }
+        }
+        for (name, footprint) in [("tx_footprint", &self.tx_footprint), ("rx_footprint", &self.rx_footprint)] {
+            let footprint = footprint.as_ref();
+            for (field, value) in [
+                ("area_m2", footprint.map(|f| f.area_m2)),
+                ("clipped", footprint.map(|f| if f.clipped { 1.0 } else { 0.0 })),
+                ("near_ground_range_m", footprint.map(|f| f.near_ground_range_m)),
+                ("far_ground_range_m", footprint.map(|f| f.far_ground_range_m)),
+                ("near_slant_range_m", footprint.map(|f| f.near_slant_range_m)),
+                ("far_slant_range_m", footprint.map(|f| f.far_slant_range_m)),
+            ] {
+                metrics.push((format!("{}.{}", name, field), value));
+            }
+        }
+        let common = self.common_footprint.as_ref();
+        for (field, value) in [
+            ("area_m2", Some(common.map_or(0.0, |c| c.area_m2))),
+            ("centroid_east_m", common.map(|c| c.centroid_m.x)),
+            ("centroid_north_m", common.map(|c| c.centroid_m.y)),
+            ("min_east_m", common.map(|c| c.min_m.x)),
+            ("min_north_m", common.map(|c| c.min_m.y)),
+            ("max_east_m", common.map(|c| c.max_m.x)),
+            ("max_north_m", common.map(|c| c.max_m.y)),
+        ] {
+            metrics.push((format!("common_footprint.{}", field), value));
+        }
+        for (name, point) in [("scene_center", Some(&self.scene_center)), ("common_centroid", self.common_centroid.as_ref())] {
+            for (field, value) in [
+                ("latitude_deg", point.map(|p| p.geodetic.latitude_deg)),
+                ("longitude_deg", point.map(|p| p.geodetic.longitude_deg)),
+                ("bistatic_angle_deg", point.map(|p| p.bistatic_angle_deg)),
+                ("incidence_angle_deg", point.map(|p| p.incidence_angle_deg)),
+                ("ground_range_resolution_m", point.map(|p| p.ground_range_resolution_m)),
+                ("doppler_resolution_hz", point.map(|p| p.doppler_resolution_hz)),
+                ("ground_doppler_resolution_m", point.map(|p| p.ground_doppler_resolution_m)),
+                ("gradients_angle_deg", point.map(|p| p.gradients_angle_deg)),
+                ("resolution_cell_area_m2", point.and_then(|p| p.resolution_cell_area_m2)),
+                ("two_way_gain_db", point.and_then(|p| p.two_way_gain_db)),
+                ("nesz_db", point.and_then(|p| p.nesz_db)),
+            ] {
+                metrics.push((format!("{}.{}", name, field), value));
+            }
+        }
+        let ambiguity = self.ambiguity.as_ref();
+        let flag = |value: bool| if value { 1.0 } else { 0.0 };
+        for (field, value) in [
+            ("min_range_sum_m", ambiguity.map(|a| a.min_range_sum_m)),
+            ("max_range_sum_m", ambiguity.map(|a| a.max_range_sum_m)),
+            ("echo_start_s", ambiguity.map(|a| a.echo_start_s)),
+            ("echo_end_s", ambiguity.map(|a| a.echo_end_s)),
+            ("direct_delay_s", ambiguity.map(|a| a.direct_delay_s)),
+            ("doppler_bandwidth_hz", ambiguity.map(|a| a.doppler_bandwidth_hz)),
+            ("doppler_centroid_hz", ambiguity.map(|a| a.doppler_centroid_hz)),
+            ("pulses_in_flight", ambiguity.map(|a| a.pulses_in_flight as f64)),
+            ("doppler_aliased", ambiguity.map(|a| flag(a.doppler_aliased))),
+            ("range_ambiguous", ambiguity.map(|a| flag(a.range_ambiguous))),
+            ("direct_signal_overlap", ambiguity.map(|a| flag(a.direct_signal_overlap))),
+        ] {
+            metrics.push((format!("ambiguity.{}", field), value));
+        }
+        metrics
+    }
+
+    /// Report as `metric,value` CSV rows, undefined values being left empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = "metric,value\n".to_string();
+        for (name, value) in self.metrics() {
+            let _ = writeln!(csv, "{},{}", name, value.map(|v| v.to_string()).unwrap_or_default());
+        }
+        for violation in &self.violations {
+            let _ = writeln!(csv, "violation.{},{}", violation.constraint, violation.value);
+        }
+        csv
+    }
+}
+
+impl Constraints {
+    /// Constraints not satisfied by `report`, checked at its reference point
+    pub fn check(&self, report: &PerformanceReport) -> Vec<Violation> {
+        let point = report.reference_point();
+        let common_area_m2 = report.common_footprint.as_ref().map_or(0.0, |c| c.area_m2);
+        // (constraint, value, limit, true if the limit is a maximum)
+        let checks = [
+            ("max_ground_range_resolution_m", point.ground_range_resolution_m, self.max_ground_range_resolution_m, true),
+            ("max_ground_doppler_resolution_m", point.ground_doppler_resolution_m, self.max_ground_doppler_resolution_m, true),
+            ("min_bistatic_angle_deg", point.bistatic_angle_deg, self.min_bistatic_angle_deg, false),
+            ("max_bistatic_angle_deg", point.bistatic_angle_deg, self.max_bistatic_angle_deg, true),
+            ("min_common_area_m2", common_area_m2, self.min_common_area_m2, false),
+        ];
+        checks
+            .into_iter()
+            .filter_map(|(constraint, value, limit, is_max)| {
+                let limit = limit?;
+                // NaN values (undefined resolutions) never satisfy a constraint
+                let satisfied = if is_max { value <= limit } else { value >= limit };
+                (!satisfied).then(|| Violation {
+                    constraint: constraint.to_string(),
+                    value,
+                    limit,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_scenario_report() {
+        // The default Tx and Rx footprints do not overlap
+        let mut report = PerformanceReport::new(&Scenario::default());
+        assert!(report.tx_footprint.is_some() && report.rx_footprint.is_some());
+        assert!(report.common_footprint.is_none() && report.common_centroid.is_none() && report.ambiguity.is_none());
+
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "metric,value");
+        assert_eq!(lines.len(), 1 + report.metrics().len());
+        assert_eq!(lines[1], format!("baseline_m,{}", report.baseline_m));
+        // Undefined metrics are empty cells, a missing common area being null
+        assert!(lines.contains(&"common_footprint.area_m2,0"));
+        for metric in ["common_footprint.centroid_east_m", "common_centroid.nesz_db", "ambiguity.doppler_aliased"] {
+            assert!(lines.contains(&format!("{},", metric).as_str()), "{}", metric);
+        }
+
+        // Checked at the scene center
+        let constraints = Constraints {
+            max_ground_range_resolution_m: Some(report.scene_center.ground_range_resolution_m + 1.0),
+            min_bistatic_angle_deg: Some(0.0),
+            min_common_area_m2: Some(1.0),
+            ..Default::default()
+        };
+        report.violations = constraints.check(&report);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].constraint, "min_common_area_m2");
+        assert_eq!((report.violations[0].value, report.violations[0].limit), (0.0, 1.0));
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 2 + report.metrics().len());
+        assert_eq!(csv.lines().last(), Some("violation.min_common_area_m2,0"));
+
+        let constraints = Constraints {
+            max_ground_range_resolution_m: Some(0.5 * report.scene_center.ground_range_resolution_m),
+            ..Default::default()
+        };
+        let violations = constraints.check(&report);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].value, report.scene_center.ground_range_resolution_m);
+    }
+}
@@ -0,0 +1,475 @@
+//! Scenario files: the full configuration of a bistatic acquisition stored in
+//! a human-editable TOML file.
+//!
+//! Every field has a default value so that incomplete files load, and files
+//! carry a schema `version` so that older files are migrated on load.
+
+use bevy::math::{DVec2, DVec3, Vec3};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf}
+};
+
+use crate::{
+    geodesy::{GeodeticPosition, LocalFrame},
+    navigation::{Navigation, NavigationError},
+    orbit::{OrbitError, OrbitState},
+    pattern::{AntennaPattern, GridPattern, PatternError, TabulatedPattern},
+    state::{AntennaBeamState, AntennaState, CarrierState, EarthModel, LinkBudgetState, SceneOrigin, SignalState},
+    trajectory::Trajectory
+};
+
+/// Current scenario file schema version, bumped by any schema change together with a
+/// `migrate` step from the previous version.
+///
+/// Version 2 adds, all with defaults reproducing the version 1 scene:
+/// - the scene origin and Earth model
+/// - the carriers geodetic positions, orbits, trajectories and navigation logs
+/// - the antenna patterns, beam gains and cone levels
+/// - the link budget
+/// - the signal PRF, pulse duration and sampling rate
+pub const SCENARIO_VERSION: u32 = 2;
+
+/// Carrier state `time_s` after the start of the acquisition, `initial` being its state at
+/// the start: along its orbit for a spaceborne carrier, else along its navigation log if
+/// any, else along its trajectory. Out of the navigation log, the carrier is held at its
+/// first or last record. `None` for an invalid orbit or an empty navigation log.
+pub fn carrier_motion_state(
+    frame: &LocalFrame,
+    initial: &CarrierState,
+    orbit: Option<&OrbitState>,
+    navigation: Option<&Navigation>,
+    trajectory: &Trajectory,
+    time_s: f64
+) -> Option<CarrierState> {
+    match (orbit, navigation) {
+        (Some(orbit), _) => orbit.carrier_state(frame, initial, time_s).ok(),
+        (None, Some(navigation)) => navigation.carrier_state(frame, initial, time_s),
+        (None, None) => Some(trajectory.carrier_state(initial, time_s))
+    }
+}
+
+/// States of a platform (Carrier, Antenna, Antenna beam and pattern)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct PlatformScenario {
+    /// Carrier state at the start of the acquisition
+    pub carrier: CarrierState,
+    pub antenna: AntennaState,
+    pub beam: AntennaBeamState,
+    /// Antenna radiation pattern
+    pub pattern: AntennaPattern,
+    /// Carrier geodetic position, which replaces the carrier local position and height when given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geodetic: Option<GeodeticPosition>,
+    /// Orbit of a spaceborne carrier, which replaces the carrier position, velocity and attitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<OrbitState>,
+    /// Trajectory of an airborne carrier over the acquisition
+    pub trajectory: Trajectory,
+    /// Navigation log of an airborne carrier, which replaces its trajectory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navigation: Option<Navigation>,
+}
+
+/// Camera point of view
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct CameraScenario {
+    /// Point looked at in World frame
+    pub center_m: Vec3,
+    /// Distance from the camera to the center
+    pub radius_m: f32,
+    pub pitch_deg: f32,
+    pub yaw_deg: f32,
+}
+
+/// A complete bistatic acquisition scenario
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Scenario {
+    /// Schema version of the file
+    pub version: u32,
+    /// Geodetic position of the World frame origin
+    pub origin: SceneOrigin,
+    /// Ground surface model: "flat", "sphere" or "wgs84"
+    pub earth: EarthModel,
+    pub tx: PlatformScenario,
+    pub rx: PlatformScenario,
+    /// Signal transmitted by Tx
+    pub signal: SignalState,
+    /// Radar equation parameters
+    pub link_budget: LinkBudgetState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraScenario>,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    /// The file has been written by a newer version of the application
+    UnsupportedVersion(u32),
+    /// The file version is not a schema version
+    InvalidVersion(toml::Value),
+    /// A signal parameter which durations and resolutions are divided by is not positive
+    InvalidSignal(&'static str, f64),
+    Orbit(OrbitError),
+    Navigation(PathBuf, NavigationError),
+    Pattern(PathBuf, PatternError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Parse(err) => write!(f, "invalid scenario file: {}", err),
+            Self::Serialize(err) => write!(f, "can't serialize scenario: {}", err),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported scenario version {} (latest supported is {})",
+                version,
+                SCENARIO_VERSION
+            ),
+            Self::InvalidVersion(version) => write!(f, "invalid scenario version {}", version),
+            Self::InvalidSignal(name, value) => write!(f, "invalid signal {} {} (must be positive)", name, value),
+            Self::Orbit(err) => write!(f, "{}", err),
+            Self::Navigation(path, err) => write!(f, "navigation file {}: {}", path.display(), err),
+            Self::Pattern(path, err) => write!(f, "antenna pattern file {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<io::Error> for ScenarioError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ScenarioError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<OrbitError> for ScenarioError {
+    fn from(err: OrbitError) -> Self {
+        Self::Orbit(err)
+    }
+}
+
+impl From<toml::ser::Error> for ScenarioError {
+    fn from(err: toml::ser::Error) -> Self {
+        Self::Serialize(err)
+    }
+}
+
+impl PlatformScenario {
+    /// Carrier state `time_s` after the start of the acquisition
+    pub fn carrier_state_at(&self, frame: &LocalFrame, time_s: f64) -> Option<CarrierState> {
+        carrier_motion_state(
+            frame,
+            &self.carrier,
+            self.orbit.as_ref(),
+            self.navigation.as_ref(),
+            &self.trajectory,
+            time_s
+        )
+    }
+}
+
+impl Default for CameraScenario {
+    fn default() -> Self {
+        Self {
+            center_m: Vec3::ZERO,
+            radius_m: 25000.0,
+            pitch_deg: 60.0,
+            yaw_deg: 45.0,
+        }
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            version: SCENARIO_VERSION,
+            origin: SceneOrigin::default(),
+            earth: EarthModel::default(),
+            tx: PlatformScenario {
+                carrier: CarrierState {
+                    height_m: 3000.0,
+                    position_m: DVec2::new(-5000.0, 0.0),
+                    ..Default::default()
+                },
+                antenna: AntennaState {
+                    heading_deg: 45.0,
+                    elevation_deg: -60.0,
+                    ..Default::default()
+                },
+                beam: AntennaBeamState::default(),
+                pattern: AntennaPattern::default(),
+                geodetic: None,
+                orbit: None,
+                trajectory: Trajectory::default(),
+                navigation: None,
+            },
+            rx: PlatformScenario {
+                carrier: CarrierState {
+                    heading_deg: 90.0,
+                    elevation_deg: 10.0,
+                    height_m: 3000.0,
+                    velocity_m_s: DVec3::new(100.0, 0.0, 0.0), // Heading East
+                    ..Default::default()
+                },
+                antenna: AntennaState::default(),
+                beam: AntennaBeamState::default(),
+                pattern: AntennaPattern::default(),
+                geodetic: None,
+                orbit: None,
+                trajectory: Trajectory::default(),
+                navigation: None,
+            },
+            signal: SignalState::default(),
+            link_budget: LinkBudgetState::default(),
+            camera: None,
+        }
+    }
+}
+
+/// Upgrades in place a scenario `table` written with schema `version` to the current
+/// schema, one version at a time
+fn migrate(table: &mut toml::Table, version: u32) {
+    for from in version..SCENARIO_VERSION {
+        match from {
+            // Version 2 only adds fields, whose defaults reproduce the version 1 scene
+            1 => {},
+            _ => unreachable!("no migration step from scenario version {}", from),
+        }
+    }
+    table.insert("version".to_string(), toml::Value::Integer(SCENARIO_VERSION.into()));
+}
+
+impl Scenario {
+    pub fn from_toml_str(s: &str) -> Result<Self, ScenarioError> {
+        let mut table: toml::Table = s.parse()?;
+        // Files without version predate versioning and follow the first schema
+        let version = match table.get("version") {
+            None => 1,
+            Some(value) => value
+                .as_integer()
+                .and_then(|version| u32::try_from(version).ok())
+                .filter(|&version| version >= 1)
+                .ok_or_else(|| ScenarioError::InvalidVersion(value.clone()))?
+        };
+        if version > SCENARIO_VERSION {
+            return Err(ScenarioError::UnsupportedVersion(version));
+        }
+        migrate(&mut table, version);
+        let mut scenario: Self = table.try_into()?;
+        scenario.validate_signal()?;
+        scenario.resolve_geodetic_positions();
+        scenario.resolve_orbits()?;
+        Ok(scenario)
+    }
+
+    /// Checks that the signal parameters and the sampling rate, if set, are positive, the
+    /// acquisition timeline, pulse sampling, wavelength and resolutions being undefined
+    /// otherwise
+    fn validate_signal(&self) -> Result<(), ScenarioError> {
+        let signal = &self.signal;
+        for (name, value) in [
+            ("carrier_frequency_hz", signal.carrier_frequency_hz),
+            ("bandwidth_hz", signal.bandwidth_hz),
+            ("integration_time_s", signal.integration_time_s),
+            ("prf_hz", signal.prf_hz),
+            ("pulse_duration_s", signal.pulse_duration_s)
+        ]
+        .into_iter()
+        .chain(signal.sampling_rate_hz.map(|rate| ("sampling_rate_hz", rate)))
+        {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(ScenarioError::InvalidSignal(name, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the carriers geodetic positions to local positions around the scene origin
+    pub fn resolve_geodetic_positions(&mut self) {
+        let frame = self.origin.frame();
+        for platform in [&mut self.tx, &mut self.rx] {
+            if let Some(geodetic) = platform.geodetic.take() {
+                platform.carrier.set_position_enu_m(frame.geodetic_to_enu(&geodetic));
+            }
+        }
+    }
+
+    /// Places the spaceborne carriers on their orbit. Orbits are kept so that they are saved back.
+    pub fn resolve_orbits(&mut self) -> Result<(), OrbitError> {
+        let frame = self.origin.frame();
+        for platform in [&mut self.tx, &mut self.rx] {
+            if let Some(orbit) = &platform.orbit {
+                platform.carrier = orbit.carrier_state(&frame, &platform.carrier, 0.0)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, ScenarioError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Moves the airborne carriers driven by a navigation file to their state at the start
+    /// of the acquisition, once the files are loaded from `base_dir`
+    pub fn load_navigation(&mut self, base_dir: &Path) -> Result<(), ScenarioError> {
+        let frame = self.origin.frame();
+        for platform in [&mut self.tx, &mut self.rx] {
+            let Some(navigation) = &mut platform.navigation else { continue };
+            let loaded = Navigation::load(navigation.path.clone(), base_dir)
+                .map_err(|err| ScenarioError::Navigation(navigation.path.clone(), err))?;
+            navigation.track = loaded.track;
+            if platform.orbit.is_none() {
+                if let Some(carrier) = navigation.carrier_state(&frame, &platform.carrier, 0.0) {
+                    platform.carrier = carrier;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the antenna pattern files from `base_dir`, the beam widths of their antennas
+    /// being set to the widths derived from the files
+    pub fn load_patterns(&mut self, base_dir: &Path) -> Result<(), ScenarioError> {
+        for platform in [&mut self.tx, &mut self.rx] {
+            match &mut platform.pattern {
+                AntennaPattern::Tabulated(pattern) => {
+                    let loaded = TabulatedPattern::load(pattern.path.clone(), base_dir)
+                        .map_err(|err| ScenarioError::Pattern(pattern.path.clone(), err))?;
+                    pattern.cuts = loaded.cuts;
+                },
+                AntennaPattern::Grid(pattern) => {
+                    let loaded = GridPattern::load(pattern.path.clone(), base_dir)
+                        .map_err(|err| ScenarioError::Pattern(pattern.path.clone(), err))?;
+                    pattern.grid = loaded.grid;
+                },
+                _ => continue
+            }
+            if let Some(widths_rad) = platform.pattern.beam_widths_rad() {
+                platform.beam.set_beam_widths_rad(widths_rad);
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a scenario file and the navigation and antenna pattern files it refers to
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScenarioError> {
+        let mut scenario = Self::from_toml_str(&fs::read_to_string(&path)?)?;
+        let base_dir = path.as_ref().parent().unwrap_or(Path::new(""));
+        scenario.load_navigation(base_dir)?;
+        scenario.load_patterns(base_dir)?;
+        Ok(scenario)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ScenarioError> {
+        Ok(fs::write(path, self.to_toml_string()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO_V1: &str = include_str!("../tests/fixtures/scenario_v1.toml");
+
+    #[test]
+    fn version_1_file_loads() {
+        let scenario = Scenario::from_toml_str(SCENARIO_V1).unwrap();
+        assert_eq!(scenario.version, SCENARIO_VERSION);
+        assert_eq!(scenario.tx.carrier.position_m, DVec2::new(-5000.0, 0.0));
+        assert_eq!(scenario.rx.carrier.height_m, 2500.0);
+        assert_eq!(scenario.rx.antenna.elevation_deg, -45.0);
+        assert_eq!(scenario.rx.beam.azimuth_beam_width_deg, 15.0);
+        assert_eq!(scenario.signal.bandwidth_hz, 100e6);
+        assert_eq!(scenario.signal.integration_time_s, 2.0);
+        assert_eq!(scenario.camera.unwrap().radius_m, 25000.0);
+
+        // Fields added by later versions take their defaults
+        assert_eq!(scenario.origin, SceneOrigin::default());
+        assert_eq!(scenario.earth, EarthModel::Flat);
+        assert_eq!(scenario.signal.prf_hz, SignalState::default().prf_hz);
+        assert_eq!(scenario.rx.beam.gain_dbi, AntennaBeamState::default().gain_dbi);
+        assert_eq!(scenario.link_budget, LinkBudgetState::default());
+        assert_eq!(scenario.tx.pattern, AntennaPattern::default());
+        assert_eq!(scenario.tx.orbit, None);
+        assert_eq!(scenario.tx.navigation, None);
+        assert_eq!(scenario.tx.trajectory, Trajectory::default());
+    }
+
+    #[test]
+    fn migration_from_version_1() {
+        let mut table: toml::Table = SCENARIO_V1.parse().unwrap();
+        let mut expected = table.clone();
+        migrate(&mut table, 1);
+        expected.insert("version".to_string(), toml::Value::Integer(SCENARIO_VERSION.into()));
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn saved_scenario_round_trips() {
+        let scenario = Scenario::default();
+        let text = scenario.to_toml_string().unwrap();
+        assert!(text.starts_with(&format!("version = {}", SCENARIO_VERSION)));
+        assert_eq!(Scenario::from_toml_str(&text).unwrap(), scenario);
+    }
+
+    #[test]
+    fn unversioned_file_loads() {
+        let scenario = Scenario::from_toml_str("[signal]\nbandwidth_hz = 50e6\n").unwrap();
+        assert_eq!(scenario.version, SCENARIO_VERSION);
+        assert_eq!(scenario.signal.bandwidth_hz, 50e6);
+    }
+
+    #[test]
+    fn invalid_versions() {
+        assert!(matches!(
+            Scenario::from_toml_str(&format!("version = {}", SCENARIO_VERSION + 1)),
+            Err(ScenarioError::UnsupportedVersion(version)) if version == SCENARIO_VERSION + 1
+        ));
+        for text in ["version = 0", "version = -1", "version = 4294967297", "version = \"2\""] {
+            assert!(matches!(Scenario::from_toml_str(text), Err(ScenarioError::InvalidVersion(_))), "{}", text);
+        }
+    }
+
+    #[test]
+    fn invalid_signals() {
+        for (field, value) in [
+            ("integration_time_s", "0.0"),
+            ("integration_time_s", "-1.0"),
+            ("prf_hz", "0.0"),
+            ("prf_hz", "nan"),
+            ("bandwidth_hz", "-150e6"),
+            ("bandwidth_hz", "inf"),
+            ("pulse_duration_s", "0.0"),
+            ("pulse_duration_s", "-20e-6"),
+            ("carrier_frequency_hz", "0.0"),
+            ("carrier_frequency_hz", "-9.65e9"),
+            ("sampling_rate_hz", "0.0"),
+            ("sampling_rate_hz", "-180e6")
+        ] {
+            let text = format!("[signal]\n{} = {}\n", field, value);
+            assert!(
+                matches!(Scenario::from_toml_str(&text), Err(ScenarioError::InvalidSignal(name, _)) if name == field),
+                "{}",
+                text
+            );
+        }
+        let scenario = Scenario::from_toml_str("[signal]\nsampling_rate_hz = 200e6\n").unwrap();
+        assert_eq!(scenario.signal.sampling_rate_hz, Some(200e6));
+    }
+}
@@ -7,3 +7,102 @@ pub use controls::{
     PanOrbitState,
     pan_orbit_camera
 };
+
+/// Platforms states to geometry
+mod platform;
+pub use platform::{
+    EarthParam,
+    PlatformStates,
+    PlatformStatesMut,
+    StatesChanged,
+    bistatic_geometry,
+    platform_geometry
+};
+
+/// Ground point picking
+mod picking;
+pub use picking::{
+    GroundPick,
+    pick_ground_point,
+    reproject_ground_pick,
+    spawn_ground_pick_text,
+    update_ground_pick_text
+};
+
+/// Resolution cell ellipses
+mod resolution;
+pub use resolution::{
+    spawn_resolution_ellipses,
+    update_resolution_ellipses
+};
+
+/// Ground plane overlays (iso-range and iso-Doppler contours) and velocity vectors
+mod overlays;
+pub use overlays::{
+    IsoDopplerOverlay,
+    IsoRangeOverlay,
+    overlay_controls,
+    spawn_iso_doppler_overlay,
+    spawn_iso_range_overlay,
+    spawn_velocity_vectors,
+    update_iso_doppler_overlay,
+    update_iso_range_overlay,
+    update_velocity_vector
+};
+
+/// Antenna beams ground footprints
+mod footprint;
+pub use footprint::{
+    common_footprint,
+    platform_footprint,
+    spawn_footprints,
+    update_common_footprint,
+    update_footprint
+};
+
+/// Noise-equivalent sigma zero map over the ground
+mod nesz;
+pub use nesz::{
+    NeszMap,
+    colormap,
+    spawn_nesz_map,
+    update_nesz_map
+};
+
+/// PRF range and Doppler ambiguities over the common footprint
+mod ambiguity;
+pub use ambiguity::{
+    Ambiguities,
+    TimingDiagram,
+    update_ambiguities
+};
+
+/// Spaceborne carriers placed on their orbit
+mod orbit;
+pub use orbit::update_orbit_carrier;
+
+/// Acquisition timeline and carriers motion along their trajectory
+mod timeline;
+pub use timeline::{
+    Timeline,
+    acquisition_duration_s,
+    advance_timeline,
+    update_carrier_motion
+};
+
+/// Paths flown by the carriers driven by navigation logs
+mod navigation;
+pub use navigation::{
+    spawn_navigation_paths,
+    update_navigation_path
+};
+
+/// Scenario files opening and saving
+mod scenario;
+pub use scenario::{
+    InitialScenario,
+    ScenarioEvent,
+    ScenarioFile,
+    handle_scenario_events,
+    pan_orbit_state
+};
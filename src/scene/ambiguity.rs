@@ -0,0 +1,48 @@
+use bevy::ecs::{
+    prelude::{DetectChanges, DetectChangesMut, Query, With},
+    system::{Res, ResMut, Resource}
+};
+
+use crate::{
+    geometry::AmbiguityCheck,
+    scene::{bistatic_geometry, common_footprint, EarthParam, PlatformStates, StatesChanged},
+    state::{Rx, SignalState, Tx}
+};
+
+/// Timing diagram view settings
+#[derive(Resource, Clone, Copy, Default, PartialEq)]
+pub struct TimingDiagram {
+    pub visible: bool,
+}
+
+/// PRF ambiguities of the current geometry, `None` when the footprints do not overlap
+/// or the timing diagram is hidden
+#[derive(Resource, Clone, Copy, Default, PartialEq)]
+pub struct Ambiguities(pub Option<AmbiguityCheck>);
+
+/// Checks the echo window and the Doppler bandwidth against the PRF when the timing
+/// diagram is shown, a platform state, the transmitted signal or the Earth model change
+pub fn update_ambiguities(
+    diagram: Res<TimingDiagram>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_signal: Query<&SignalState, With<Tx>>,
+    query_changed: Query<(), StatesChanged>,
+    mut ambiguities: ResMut<Ambiguities>,
+) {
+    if !diagram.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    if !diagram.visible {
+        ambiguities.set_if_neq(Ambiguities(None));
+        return;
+    }
+
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    let signal = query_signal
+        .get_single()
+        .expect("Can't get `Tx` signal state");
+    let check = common_footprint(&geometry).map(|common| geometry.ambiguity_check(&common, signal));
+    ambiguities.set_if_neq(Ambiguities(check));
+}
@@ -135,12 +135,7 @@ pub fn pan_orbit_camera(
             state.yaw -= total_orbit.x;
             state.pitch -= total_orbit.y;
             // Limits pitch angles
-            if state.pitch >= PITCH_MAX_RAD {
-                state.pitch = PITCH_MAX_RAD;
-            }
-            if state.pitch <= 0.0  {
-                state.pitch = 0.0;
-            }
+            state.pitch = state.pitch.clamp(0.0, PITCH_MAX_RAD);
         }        
 
         if total_pan != Vec2::ZERO {
@@ -161,7 +156,7 @@ pub fn pan_orbit_camera(
             state.center.z = state.z_focus;
         }
 
-        if any || state.is_added() {
+        if any || state.is_changed() {
             // Camera referential is: X - right, Y - up, Z - out of screen.
             // Note: What is "seen on screen" is like if the camera was rotated then put back in its
             //       initial state, so that what is drawn on screen is moved.
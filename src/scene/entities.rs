@@ -1,7 +1,5 @@
-///
 mod axis_helper;
 pub use axis_helper::spawn_axis_helper;
 
-///
 mod world;
-pub use world::spawn_world;
\ No newline at end of file
+pub use world::{spawn_world, update_world_surface, HALF_PLANE_SIZE};
\ No newline at end of file
@@ -1,21 +1,76 @@
 use bevy::{
-    asset::Assets,
+    asset::{Assets, Handle},
     color::LinearRgba,
-    ecs::prelude::Commands,
+    ecs::{
+        component::Component,
+        prelude::{Commands, Query, With, Without}
+    },
     math::{
-        primitives::Plane3d, Vec2, Vec3
+        primitives::Plane3d, DVec2, Vec2, Vec3
     },
     pbr::StandardMaterial,
     prelude::{BuildChildren, Entity, Mesh, Meshable, PbrBundle, ResMut, Transform}
 };
 
 use crate::{
-    scene::entities::spawn_axis_helper,
-    mesh::LineList
+    geometry::Earth,
+    scene::{entities::spawn_axis_helper, EarthParam},
+    state::EarthModel,
+    mesh::{GridSurface, LineList}
 };
 
-const HALF_PLANE_SIZE: f32 = 15000.0;
+pub const HALF_PLANE_SIZE: f32 = 15000.0;
 const GRID_SIZE: f32 = 500.0;
+/// Number of cells per side of the curved Earth surface patch
+const SURFACE_PATCH_CELLS: usize = 60;
+
+/// Marker of the World ground surface entity
+#[derive(Component)]
+pub struct WorldSurfaceMarker;
+
+/// Lines drawn on the World ground, given by their ends on the World plane (East, North)
+#[derive(Component)]
+pub struct WorldLines(Vec<(Vec2, Vec2)>);
+
+impl WorldLines {
+    /// Lines lifted to the `earth` ground surface, split in segments of at most `GRID_SIZE`
+    fn on_ground(&self, earth: &Earth) -> LineList {
+        let lift = |p: Vec2| earth.ground_point(p.as_dvec2()).as_vec3();
+        let mut lines = Vec::new();
+        for &(a, b) in &self.0 {
+            let segments = (a.distance(b) / GRID_SIZE).ceil().max(1.0) as usize;
+            lines.extend((0..segments).map(|k| (
+                lift(a.lerp(b, k as f32 / segments as f32)),
+                lift(a.lerp(b, (k + 1) as f32 / segments as f32))
+            )));
+        }
+        LineList { lines }
+    }
+}
+
+/// Ground surface mesh: the flat World plane or a patch of the curved Earth surface
+fn ground_mesh(earth: &Earth) -> Mesh {
+    match earth.model {
+        EarthModel::Flat => Plane3d::new(Vec3::Z, Vec2::splat(HALF_PLANE_SIZE))
+            .mesh()
+            .subdivisions(0)
+            .into(),
+        _ => {
+            let step = 2.0 * HALF_PLANE_SIZE as f64 / SURFACE_PATCH_CELLS as f64;
+            let points = (0..=SURFACE_PATCH_CELLS)
+                .flat_map(|j| (0..=SURFACE_PATCH_CELLS).map(move |i| (i, j)))
+                .map(|(i, j)| {
+                    let position = DVec2::new(i as f64, j as f64) * step - HALF_PLANE_SIZE as f64;
+                    earth.ground_point(position).as_vec3()
+                })
+                .collect();
+            GridSurface {
+                cells: (SURFACE_PATCH_CELLS, SURFACE_PATCH_CELLS),
+                points,
+            }.into()
+        }
+    }
+}
 
 pub fn spawn_world(
     commands: &mut Commands,
@@ -26,13 +81,7 @@ pub fn spawn_world(
     // opaque plane
     let world_plane = commands.spawn(
         PbrBundle {
-            mesh: meshes.add(
-                Plane3d::new(
-                    Vec3::Z,
-                    Vec2::splat(HALF_PLANE_SIZE)
-                ).mesh()
-                .subdivisions(0)
-            ),
+            mesh: meshes.add(ground_mesh(&Earth::default())),
             material: materials.add(
                 StandardMaterial {
                     base_color: LinearRgba::rgb(0.1, 0.1, 0.1).into(),
@@ -44,20 +93,20 @@ pub fn spawn_world(
             ),
             ..Default::default()
         }
-    ).id();
+    ).insert(WorldSurfaceMarker).id();
 
     // Plane grid
     let half_num_lines = (HALF_PLANE_SIZE / GRID_SIZE).floor() as usize;
-    let mut lines = Vec::<(Vec3, Vec3)>::with_capacity(4 * half_num_lines);
+    let mut lines = Vec::<(Vec2, Vec2)>::with_capacity(4 * half_num_lines);
     // X-lines
     let mut y: f32;
     for i in 1..=half_num_lines {
         y = GRID_SIZE * i as f32;
         lines.push(
-            (Vec3::new(-HALF_PLANE_SIZE, y, 0.0), Vec3::new(HALF_PLANE_SIZE, y, 0.0))
+            (Vec2::new(-HALF_PLANE_SIZE, y), Vec2::new(HALF_PLANE_SIZE, y))
         );
         lines.push(
-            (Vec3::new(-HALF_PLANE_SIZE, -y, 0.0), Vec3::new(HALF_PLANE_SIZE, -y, 0.0))
+            (Vec2::new(-HALF_PLANE_SIZE, -y), Vec2::new(HALF_PLANE_SIZE, -y))
         );
     }
     // Y-lines
@@ -65,36 +114,36 @@ pub fn spawn_world(
     for i in 1..=half_num_lines {
         x = GRID_SIZE * i as f32;
         lines.push(
-            (Vec3::new(x, -HALF_PLANE_SIZE, 0.0), Vec3::new(x, HALF_PLANE_SIZE, 0.0))
+            (Vec2::new(x, -HALF_PLANE_SIZE), Vec2::new(x, HALF_PLANE_SIZE))
         );
         lines.push(
-            (Vec3::new(-x, -HALF_PLANE_SIZE, 0.0), Vec3::new(-x, HALF_PLANE_SIZE, 0.0))
+            (Vec2::new(-x, -HALF_PLANE_SIZE), Vec2::new(-x, HALF_PLANE_SIZE))
         );
     }
+    let grid_lines = WorldLines(lines);
 
     let world_grid = commands.spawn(
         PbrBundle {
             mesh: meshes.add(
-                LineList { lines }
+                grid_lines.on_ground(&Earth::default())
             ),
             material: materials.add(
                 StandardMaterial {
                     // base_color: LinearRgba::rgb(0.737254902, 0.737254902, 0.737254902).into(),
-                    base_color: LinearRgba::rgb(0.325490196, 0.407843137, 0.470588235).into(),
+                    base_color: LinearRgba::rgb(0.325_490_2, 0.407_843_14, 0.470_588_24).into(),
                     unlit: true,
                     ..Default::default()
             }),
             ..Default::default()
         }
-    ).id();
+    ).insert(grid_lines).id();
 
     // Center X-line
+    let center_x_lines = WorldLines(vec![(Vec2::new(-HALF_PLANE_SIZE, 0.0), Vec2::new(HALF_PLANE_SIZE, 0.0))]);
     let center_x_line = commands.spawn(
         PbrBundle {
             mesh: meshes.add(
-                LineList { 
-                    lines: vec![(Vec3::new(-HALF_PLANE_SIZE, 0.0, 0.0), Vec3::new(HALF_PLANE_SIZE, 0.0, 0.0))]
-                }
+                center_x_lines.on_ground(&Earth::default())
             ),
             material: materials.add(
                 StandardMaterial {
@@ -104,15 +153,14 @@ pub fn spawn_world(
             }),
             ..Default::default()
         }
-    ).id();
+    ).insert(center_x_lines).id();
 
     // Center Y-line
+    let center_y_lines = WorldLines(vec![(Vec2::new(0.0, -HALF_PLANE_SIZE), Vec2::new(0.0, HALF_PLANE_SIZE))]);
     let center_y_line = commands.spawn(
         PbrBundle {
             mesh: meshes.add(
-                LineList { 
-                    lines: vec![(Vec3::new(0.0, -HALF_PLANE_SIZE, 0.0), Vec3::new(0.0, HALF_PLANE_SIZE, 0.0))]
-                }
+                center_y_lines.on_ground(&Earth::default())
             ),
             material: materials.add(
                 StandardMaterial {
@@ -122,7 +170,7 @@ pub fn spawn_world(
             }),
             ..Default::default()
         }
-    ).id();
+    ).insert(center_y_lines).id();
 
     // World axis helper
     let world_axis_helper = spawn_axis_helper(
@@ -141,4 +189,22 @@ pub fn spawn_world(
             world_axis_helper
         ])
         .id()
-}
\ No newline at end of file
+}
+/// Rebuilds the World ground surface and its lines when the Earth model or the scene origin change
+pub fn update_world_surface(
+    earth: EarthParam,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_surface: Query<&mut Handle<Mesh>, With<WorldSurfaceMarker>>,
+    mut query_lines: Query<(&WorldLines, &mut Handle<Mesh>), Without<WorldSurfaceMarker>>,
+) {
+    if !earth.is_changed() {
+        return;
+    }
+    let earth = earth.earth();
+    for mut mesh in &mut query_surface {
+        *mesh = meshes.add(ground_mesh(&earth));
+    }
+    for (lines, mut mesh) in &mut query_lines {
+        *mesh = meshes.add(lines.on_ground(&earth));
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::LinearRgba,
+    ecs::{
+        component::Component,
+        prelude::{Commands, Query, With},
+        system::ResMut
+    },
+    math::Vec3,
+    pbr::{PbrBundle, StandardMaterial},
+    prelude::{AlphaMode, Mesh, Visibility},
+};
+
+use crate::{
+    constants::FOOTPRINT_SEGMENTS,
+    geometry::{BistaticGeometry, CommonFootprint, Earth, Footprint, PlatformGeometry},
+    mesh::{ConvexPolygon, LineStrip},
+    scene::{bistatic_geometry, platform_geometry, EarthParam, PlatformStates, StatesChanged},
+    state::{Rx, Tx}
+};
+
+/// Mesh and visibility of a footprint entity, updated together
+type FootprintMesh = (&'static mut Handle<Mesh>, &'static mut Visibility);
+
+/// Marker of the footprint entity, used together with `Tx` or `Rx`
+#[derive(Component)]
+pub struct FootprintMarker;
+
+#[derive(Component)]
+pub struct CommonFootprintMarker;
+
+/// Ground footprint of a platform antenna beam, as displayed in the scene
+#[inline]
+pub fn platform_footprint(geometry: &PlatformGeometry, earth: &Earth) -> Option<Footprint> {
+    geometry.footprint(earth, geometry.footprint_clip_range_m(earth), FOOTPRINT_SEGMENTS)
+}
+
+/// Ground area commonly illuminated by the Tx and Rx beams, as displayed in the scene
+pub fn common_footprint(geometry: &BistaticGeometry) -> Option<CommonFootprint> {
+    geometry.common_footprint(FOOTPRINT_SEGMENTS)
+}
+
+pub fn spawn_footprints(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut bundle = |color: LinearRgba| (
+        PbrBundle {
+            mesh: meshes.add(LineStrip { points: Vec::new() }),
+            material: materials.add(
+                StandardMaterial {
+                    base_color: color.into(),
+                    unlit: true,
+                    ..Default::default()
+                }
+            ),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        FootprintMarker
+    );
+    commands.spawn((bundle(LinearRgba::new(1.0, 1.0, 1.0, 1.0)), Tx));
+    commands.spawn((bundle(LinearRgba::new(0.6, 0.6, 0.6, 1.0)), Rx));
+
+    // Common illuminated area
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(ConvexPolygon { points: Vec::new(), normal: Vec3::Z }),
+            material: materials.add(
+                StandardMaterial {
+                    base_color: LinearRgba::new(0.0, 1.0, 0.3, 0.4).into(),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    double_sided: true,
+                    cull_mode: None,
+                    ..Default::default()
+                }
+            ),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        CommonFootprintMarker
+    ));
+}
+
+/// Redraws the `P` (`Tx` or `Rx`) antenna footprint when its states or the Earth model change
+pub fn update_footprint<P: Component>(
+    query_state: Query<PlatformStates, With<P>>,
+    query_changed: Query<(), (With<P>, StatesChanged)>,
+    earth: EarthParam,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_footprint: Query<FootprintMesh, (With<P>, With<FootprintMarker>)>,
+) {
+    if !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let Ok((mut mesh, mut visibility)) = query_footprint.get_single_mut() else { return };
+
+    let earth = earth.earth();
+    match platform_footprint(&platform_geometry(&query_state), &earth) {
+        Some(footprint) => {
+            *mesh = meshes.add(LineStrip {
+                points: footprint
+                    .polyline(&earth)
+                    .into_iter()
+                    .map(|p| p.as_vec3())
+                    .collect()
+            });
+            *visibility = Visibility::Inherited;
+        },
+        None => *visibility = Visibility::Hidden
+    }
+}
+
+/// Redraws the Tx and Rx footprints common area when a platform state or the Earth model change
+pub fn update_common_footprint(
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_changed: Query<(), StatesChanged>,
+    earth: EarthParam,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_common: Query<(&mut Handle<Mesh>, &mut Visibility), With<CommonFootprintMarker>>,
+) {
+    if !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let Ok((mut mesh, mut visibility)) = query_common.get_single_mut() else { return };
+
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    match common_footprint(&geometry) {
+        Some(common) => {
+            *mesh = meshes.add(ConvexPolygon {
+                points: common
+                    .points
+                    .iter()
+                    .map(|&p| geometry.earth.ground_point(p).as_vec3())
+                    .collect(),
+                normal: Vec3::Z
+            });
+            *visibility = Visibility::Inherited;
+        },
+        None => *visibility = Visibility::Hidden
+    }
+}
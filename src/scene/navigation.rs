@@ -0,0 +1,82 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::LinearRgba,
+    ecs::{
+        component::Component,
+        prelude::{Commands, DetectChanges, Query, Ref, With},
+        system::{Res, ResMut}
+    },
+    pbr::{PbrBundle, StandardMaterial},
+    prelude::{Mesh, Visibility},
+};
+
+use crate::{
+    mesh::LineStrip,
+    state::{CarrierNavigation, Rx, SceneOrigin, Tx}
+};
+
+/// Maximum number of points of a displayed navigation path, longer tracks being decimated
+const NAVIGATION_PATH_MAX_POINTS: usize = 10_000;
+
+/// Mesh and visibility of a flown path entity, updated together
+type NavigationPathMesh = (&'static mut Handle<Mesh>, &'static mut Visibility);
+
+/// Marker of the flown path entity, used together with `Tx` or `Rx`
+#[derive(Component)]
+pub struct NavigationPathMarker;
+
+pub fn spawn_navigation_paths(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut bundle = |color: LinearRgba| (
+        PbrBundle {
+            mesh: meshes.add(LineStrip { points: Vec::new() }),
+            material: materials.add(
+                StandardMaterial {
+                    base_color: color.into(),
+                    unlit: true,
+                    ..Default::default()
+                }
+            ),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        NavigationPathMarker
+    );
+    commands.spawn((bundle(LinearRgba::new(1.0, 0.8, 0.2, 1.0)), Tx));
+    commands.spawn((bundle(LinearRgba::new(0.2, 0.8, 1.0, 1.0)), Rx));
+}
+
+/// Redraws the path flown by the `P` (`Tx` or `Rx`) carrier when its navigation log or
+/// the scene origin change
+pub fn update_navigation_path<P: Component>(
+    origin: Res<SceneOrigin>,
+    query_navigation: Query<Ref<CarrierNavigation>, With<P>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_path: Query<NavigationPathMesh, (With<P>, With<NavigationPathMarker>)>,
+) {
+    let Ok(navigation) = query_navigation.get_single() else { return };
+    if !navigation.is_changed() && !origin.is_changed() {
+        return;
+    }
+    let Ok((mut mesh, mut visibility)) = query_path.get_single_mut() else { return };
+
+    let Some(navigation) = &navigation.0 else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let frame = origin.frame();
+    let records = &navigation.track.records;
+    let step = records.len().div_ceil(NAVIGATION_PATH_MAX_POINTS).max(1);
+    *mesh = meshes.add(LineStrip {
+        points: records
+            .iter()
+            .step_by(step)
+            .chain(records.last()) // Down to the last record
+            .map(|record| frame.geodetic_to_enu(&record.position).as_vec3())
+            .collect()
+    });
+    *visibility = Visibility::Inherited;
+}
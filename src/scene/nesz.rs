@@ -0,0 +1,190 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::LinearRgba,
+    ecs::{
+        component::Component,
+        prelude::{Commands, Query, With},
+        system::{Res, ResMut, Resource}
+    },
+    math::{DVec2, Vec3},
+    pbr::{PbrBundle, StandardMaterial},
+    prelude::{AlphaMode, DetectChanges, Image, Mesh, Transform, Visibility},
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat}
+    }
+};
+
+use crate::{
+    geometry::{linear_to_db, BistaticGeometry, Earth},
+    mesh::GridSurface,
+    scene::{bistatic_geometry, entities::HALF_PLANE_SIZE, EarthParam, PlatformStates, StatesChanged},
+    state::{LinkBudgetState, Rx, SignalState, Tx}
+};
+
+/// Number of texture pixels per side of the NESZ map
+const NESZ_MAP_PIXELS: usize = 256;
+/// Number of cells per side of the surface on which the NESZ map is drawn
+const NESZ_MAP_CELLS: usize = 60;
+/// Opacity of the NESZ map
+const NESZ_MAP_ALPHA: u8 = 200;
+
+/// Colour map stops, from low to high values (viridis)
+const COLORMAP: [[u8; 3]; 5] = [
+    [68, 1, 84],
+    [59, 82, 139],
+    [33, 145, 140],
+    [94, 201, 98],
+    [253, 231, 37],
+];
+
+/// NESZ map overlay settings
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct NeszMap {
+    pub visible: bool,
+    /// NESZ mapped to the first colour, lower values being clamped to it
+    pub min_db: f64,
+    /// NESZ mapped to the last colour, higher values being left transparent
+    pub max_db: f64,
+}
+
+impl Default for NeszMap {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            min_db: -50.0,
+            max_db: -10.0
+        }
+    }
+}
+
+/// NESZ map entity, with the handle of its texture
+#[derive(Component)]
+pub struct NeszMapTexture(Handle<Image>);
+
+/// Colour of `t` in [0, 1] (clamped), linearly interpolated between the colour map stops
+pub fn colormap(t: f64) -> [u8; 3] {
+    let x = t.clamp(0.0, 1.0) * (COLORMAP.len() - 1) as f64;
+    let k = (x.floor() as usize).min(COLORMAP.len() - 2);
+    let f = x - k as f64;
+    let (a, b) = (COLORMAP[k], COLORMAP[k + 1]);
+    [0, 1, 2].map(|c| (a[c] as f64 + f * (b[c] as f64 - a[c] as f64)).round() as u8)
+}
+
+/// Surface above the World plane on which the map is drawn, its texture covering the
+/// whole World plane
+fn nesz_map_mesh(earth: &Earth) -> Mesh {
+    let step = 2.0 * HALF_PLANE_SIZE as f64 / NESZ_MAP_CELLS as f64;
+    let points = (0..=NESZ_MAP_CELLS)
+        .flat_map(|j| (0..=NESZ_MAP_CELLS).map(move |i| (i, j)))
+        .map(|(i, j)| {
+            let position = DVec2::new(i as f64, j as f64) * step - HALF_PLANE_SIZE as f64;
+            earth.ground_point(position).as_vec3()
+        })
+        .collect();
+    GridSurface {
+        cells: (NESZ_MAP_CELLS, NESZ_MAP_CELLS),
+        points,
+    }.into()
+}
+
+/// NESZ sampled at the ground points below the texture pixel centers, rows going North
+fn nesz_map_image(
+    geometry: &BistaticGeometry,
+    signal: &SignalState,
+    budget: &LinkBudgetState,
+    map: &NeszMap
+) -> Image {
+    let step = 2.0 * HALF_PLANE_SIZE as f64 / NESZ_MAP_PIXELS as f64;
+    let mut data = Vec::with_capacity(4 * NESZ_MAP_PIXELS * NESZ_MAP_PIXELS);
+    for j in 0..NESZ_MAP_PIXELS {
+        for i in 0..NESZ_MAP_PIXELS {
+            let position = (DVec2::new(i as f64, j as f64) + 0.5) * step - HALF_PLANE_SIZE as f64;
+            let point = geometry.earth.ground_point(position);
+            let pixel = match geometry.nesz_at(point, signal, budget).map(linear_to_db) {
+                Some(nesz_db) if nesz_db <= map.max_db => {
+                    let [r, g, b] = colormap((nesz_db - map.min_db) / (map.max_db - map.min_db));
+                    [r, g, b, NESZ_MAP_ALPHA]
+                },
+                _ => [0; 4]
+            };
+            data.extend(pixel);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: NESZ_MAP_PIXELS as u32,
+            height: NESZ_MAP_PIXELS as u32,
+            depth_or_array_layers: 1
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default()
+    )
+}
+
+pub fn spawn_nesz_map(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let texture = images.add(Image::default());
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(nesz_map_mesh(&Earth::default())),
+            material: materials.add(
+                StandardMaterial {
+                    base_color: LinearRgba::WHITE.into(),
+                    base_color_texture: Some(texture.clone()),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    double_sided: true,
+                    cull_mode: None,
+                    ..Default::default()
+                }
+            ),
+            transform: Transform::from_translation( // Between the ground surface and its grid lines
+                Vec3::new(0.0, 0.0, -0.05)
+            ),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        NeszMapTexture(texture)
+    ));
+}
+
+/// Recomputes the NESZ map when its settings, a platform state, the transmitted signal,
+/// the link budget or the Earth model change
+#[allow(clippy::too_many_arguments)]
+pub fn update_nesz_map(
+    map: Res<NeszMap>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_signal: Query<(&SignalState, &LinkBudgetState), With<Tx>>,
+    query_changed: Query<(), StatesChanged>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut query_map: Query<(&NeszMapTexture, &mut Handle<Mesh>, &mut Visibility)>,
+) {
+    if !map.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let Ok((texture, mut mesh, mut visibility)) = query_map.get_single_mut() else { return };
+    if !map.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    let (signal, budget) = query_signal
+        .get_single()
+        .expect("Can't get `Tx` signal state");
+    *mesh = meshes.add(nesz_map_mesh(&geometry.earth));
+    if let Some(image) = images.get_mut(&texture.0) {
+        *image = nesz_map_image(&geometry, signal, budget, &map);
+    }
+    *visibility = Visibility::Inherited;
+}
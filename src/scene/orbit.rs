@@ -0,0 +1,24 @@
+use bevy::ecs::{
+    component::Component,
+    prelude::{DetectChanges, DetectChangesMut, Query, Ref, With},
+    system::Res
+};
+
+use crate::state::{CarrierOrbit, InitialCarrierState, SceneOrigin};
+
+/// Places the `P` (`Tx` or `Rx`) spaceborne carrier on its orbit at the orbit epoch when
+/// the orbit or the scene origin change. Invalid orbits leave the carrier in place (the
+/// error is shown by the parameters panel).
+pub fn update_orbit_carrier<P: Component>(
+    origin: Res<SceneOrigin>,
+    mut query: Query<(Ref<CarrierOrbit>, &mut InitialCarrierState), With<P>>,
+) {
+    let Ok((orbit, mut initial)) = query.get_single_mut() else { return };
+    if !orbit.is_changed() && !origin.is_changed() {
+        return;
+    }
+    let Some(orbit) = &orbit.0 else { return };
+    if let Ok(state) = orbit.carrier_state(&origin.frame(), &initial.0, 0.0) {
+        initial.set_if_neq(InitialCarrierState(state));
+    }
+}
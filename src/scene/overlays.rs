@@ -0,0 +1,306 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::LinearRgba,
+    ecs::{
+        component::Component,
+        prelude::{Changed, Commands, Or, Query, With},
+        system::{Res, ResMut, Resource}
+    },
+    math::{DVec3, Vec3},
+    input::{keyboard::KeyCode, ButtonInput},
+    pbr::{PbrBundle, StandardMaterial},
+    prelude::{DetectChanges, Mesh, Visibility},
+};
+
+use crate::{
+    geometry::{contour_levels, iso_contours, Earth, GroundGrid},
+    mesh::LineList,
+    scene::{bistatic_geometry, entities::HALF_PLANE_SIZE, EarthParam, PlatformStates},
+    state::{CarrierState, Rx, SignalState, Tx}
+};
+
+/// Number of grid cells per side used to sample fields on the World plane
+const OVERLAY_GRID_CELLS: usize = 300;
+/// Maximum number of contours drawn by an overlay
+const OVERLAY_MAX_LEVELS: usize = 500;
+/// Flight duration represented by the carriers velocity vectors
+const VELOCITY_VECTOR_DURATION_S: f64 = 20.0;
+
+/// Filter matching platforms whose carrier state or transmitted signal changed, which
+/// moves the iso-Doppler contours
+type DopplerChanged = Or<(Changed<CarrierState>, Changed<SignalState>)>;
+
+/// Iso-range (range-sum R_tx + R_rx) contours overlay settings
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct IsoRangeOverlay {
+    pub visible: bool,
+    /// Range-sum spacing between two contours
+    pub spacing_m: f64,
+}
+
+impl Default for IsoRangeOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            spacing_m: 500.0
+        }
+    }
+}
+
+/// Iso-Doppler contours overlay settings
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct IsoDopplerOverlay {
+    pub visible: bool,
+    /// Bistatic Doppler spacing between two contours
+    pub spacing_hz: f64,
+}
+
+impl Default for IsoDopplerOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            spacing_hz: 100.0
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct IsoRangeOverlayMarker;
+
+#[derive(Component)]
+pub enum IsoDopplerOverlayMarker {
+    Contours,
+    ZeroDoppler
+}
+
+/// Marker of the velocity vector entity, used together with `Tx` or `Rx`
+#[derive(Component)]
+pub struct VelocityVectorMarker;
+
+/// Samples `field` on the ground surface above the World plane grid, returning the grid,
+/// the samples and their range
+fn sample_world<F: Fn(DVec3) -> f64>(earth: &Earth, field: F) -> (GroundGrid, Vec<f64>, (f64, f64)) {
+    let grid = GroundGrid::centered(HALF_PLANE_SIZE as f64, OVERLAY_GRID_CELLS);
+    let values = grid.sample(|node| field(earth.ground_point(node.truncate())));
+    let range = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+    (grid, values, range)
+}
+
+/// Samples `field` on the ground surface and returns its contours every `spacing`
+fn contour_lines<F: Fn(DVec3) -> f64>(earth: &Earth, field: F, spacing: f64) -> LineList {
+    let (grid, values, (min, max)) = sample_world(earth, field);
+    let levels = contour_levels(min, max, spacing, OVERLAY_MAX_LEVELS);
+    contour_lines_at(earth, &grid, &values, &levels)
+}
+
+/// Contours computed on the World plane grid, lifted to the ground surface
+fn contour_lines_at(earth: &Earth, grid: &GroundGrid, values: &[f64], levels: &[f64]) -> LineList {
+    let lift = |point: DVec3| earth.ground_point(point.truncate()).as_vec3();
+    LineList {
+        lines: iso_contours(grid, values, levels)
+            .into_iter()
+            .map(|(a, b)| (lift(a), lift(b)))
+            .collect()
+    }
+}
+
+pub fn spawn_iso_range_overlay(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(LineList { lines: Vec::new() }),
+            material: materials.add(
+                StandardMaterial {
+                    base_color: LinearRgba::new(0.0, 0.8, 1.0, 1.0).into(),
+                    unlit: true,
+                    ..Default::default()
+                }
+            ),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        IsoRangeOverlayMarker
+    ));
+}
+
+pub fn spawn_iso_doppler_overlay(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (marker, color) in [
+        (IsoDopplerOverlayMarker::Contours, LinearRgba::new(1.0, 0.5, 0.0, 1.0)),
+        (IsoDopplerOverlayMarker::ZeroDoppler, LinearRgba::new(1.0, 0.0, 1.0, 1.0))
+    ] {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(LineList { lines: Vec::new() }),
+                material: materials.add(
+                    StandardMaterial {
+                        base_color: color.into(),
+                        unlit: true,
+                        ..Default::default()
+                    }
+                ),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            marker
+        ));
+    }
+}
+
+pub fn spawn_velocity_vectors(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(
+        StandardMaterial {
+            base_color: LinearRgba::new(1.0, 1.0, 1.0, 1.0).into(),
+            unlit: true,
+            ..Default::default()
+        }
+    );
+    let mut bundle = || (
+        PbrBundle {
+            mesh: meshes.add(LineList { lines: Vec::new() }),
+            material: material.clone(),
+            ..Default::default()
+        },
+        VelocityVectorMarker
+    );
+    commands.spawn((bundle(), Tx));
+    commands.spawn((bundle(), Rx));
+}
+
+/// Keyboard controls:
+/// - `R` toggles the iso-range overlay, `[` and `]` halve and double its spacing,
+/// - `D` toggles the iso-Doppler overlay, `-` and `=` halve and double its spacing.
+pub fn overlay_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut iso_range: ResMut<IsoRangeOverlay>,
+    mut iso_doppler: ResMut<IsoDopplerOverlay>,
+) {
+    if keys.just_pressed(KeyCode::KeyR) {
+        iso_range.visible = !iso_range.visible;
+    }
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        iso_range.spacing_m *= 0.5;
+    }
+    if keys.just_pressed(KeyCode::BracketRight) {
+        iso_range.spacing_m *= 2.0;
+    }
+    if keys.just_pressed(KeyCode::KeyD) {
+        iso_doppler.visible = !iso_doppler.visible;
+    }
+    if keys.just_pressed(KeyCode::Minus) {
+        iso_doppler.spacing_hz *= 0.5;
+    }
+    if keys.just_pressed(KeyCode::Equal) {
+        iso_doppler.spacing_hz *= 2.0;
+    }
+}
+
+/// Redraws the iso-range contours when the overlay settings, a carrier state or the Earth model change
+pub fn update_iso_range_overlay(
+    overlay: Res<IsoRangeOverlay>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_changed: Query<(), Changed<CarrierState>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_overlay: Query<(&mut Handle<Mesh>, &mut Visibility), With<IsoRangeOverlayMarker>>,
+) {
+    if !overlay.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let Ok((mut mesh, mut visibility)) = query_overlay.get_single_mut() else { return };
+    if !overlay.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    *mesh = meshes.add(
+        contour_lines(&geometry.earth, |point| geometry.range_sum_m(point), overlay.spacing_m)
+    );
+    *visibility = Visibility::Inherited;
+}
+
+/// Redraws the iso-Doppler contours and the zero-Doppler line when the overlay
+/// settings, a carrier state, the transmitted signal or the Earth model change
+#[allow(clippy::too_many_arguments)]
+pub fn update_iso_doppler_overlay(
+    overlay: Res<IsoDopplerOverlay>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_signal: Query<&SignalState, With<Tx>>,
+    query_changed: Query<(), DopplerChanged>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_overlay: Query<(&IsoDopplerOverlayMarker, &mut Handle<Mesh>, &mut Visibility)>,
+) {
+    if !overlay.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    if !overlay.visible {
+        for (_, _, mut visibility) in &mut query_overlay {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    let wavelength_m = query_signal
+        .get_single()
+        .expect("Can't get `Tx` signal state")
+        .wavelength_m();
+    let (grid, values, (min, max)) = sample_world(&geometry.earth, |point| geometry.doppler_hz(point, wavelength_m));
+
+    for (marker, mut mesh, mut visibility) in &mut query_overlay {
+        let levels = match marker {
+            IsoDopplerOverlayMarker::Contours => contour_levels(min, max, overlay.spacing_hz, OVERLAY_MAX_LEVELS)
+                .into_iter()
+                .filter(|&level| level != 0.0)
+                .collect(),
+            IsoDopplerOverlayMarker::ZeroDoppler => vec![0.0]
+        };
+        *mesh = meshes.add(contour_lines_at(&geometry.earth, &grid, &values, &levels));
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Redraws the `P` (`Tx` or `Rx`) carrier velocity vector when its state changes
+pub fn update_velocity_vector<P: Component>(
+    query_state: Query<PlatformStates, (With<P>, Changed<CarrierState>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_vector: Query<&mut Handle<Mesh>, (With<P>, With<VelocityVectorMarker>)>,
+) {
+    let Ok((carrier, ..)) = query_state.get_single() else { return };
+    let Ok(mut mesh) = query_vector.get_single_mut() else { return };
+
+    let origin = carrier.position_enu_m();
+    let tip = origin + carrier.velocity_m_s * VELOCITY_VECTOR_DURATION_S;
+    let lines = match carrier.velocity_m_s.try_normalize() {
+        Some(direction) => {
+            // Arrow head drawn along the horizontal perpendicular to the velocity
+            let side = direction.cross(DVec3::Z).try_normalize().unwrap_or(DVec3::X);
+            let head = 0.1 * (tip - origin).length();
+            [
+                (origin, tip),
+                (tip, tip - head * (direction + 0.5 * side)),
+                (tip, tip - head * (direction - 0.5 * side))
+            ]
+            .map(|(a, b)| (a.as_vec3(), b.as_vec3()))
+            .to_vec()
+        },
+        None => Vec::<(Vec3, Vec3)>::new()
+    };
+    *mesh = meshes.add(LineList { lines });
+}
@@ -0,0 +1,257 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        prelude::{Commands, Query, With},
+        system::{Local, Res, ResMut, Resource}
+    },
+    input::{mouse::MouseButton, ButtonInput},
+    math::{DVec3, Vec2},
+    prelude::{Camera, DetectChanges, GlobalTransform, default},
+    text::{Text, TextStyle},
+    ui::{node_bundles::TextBundle, PositionType, Style, Val},
+    window::{PrimaryWindow, Window}
+};
+
+use crate::{
+    geodesy::{GeodeticPosition, LocalFrame},
+    geometry::{linear_to_db, BistaticGeometry, BistaticResolution, CommonFootprint, Earth, Footprint, PlatformGeometry},
+    scene::{
+        bistatic_geometry, common_footprint, platform_footprint,
+        EarthParam, PlatformStates, PanOrbitState, StatesChanged
+    },
+    state::{LinkBudgetState, Rx, SignalState, Tx}
+};
+
+/// Maximum cursor displacement (in pixels) between press and release for a click
+const CLICK_MAX_DISTANCE: f32 = 3.0;
+
+/// The last ground point picked with the mouse
+#[derive(Resource, Default)]
+pub struct GroundPick {
+    pub point_m: Option<DVec3>,
+}
+
+#[derive(Component)]
+pub struct GroundPickTextMarker;
+
+/// Picks the ground point under the cursor on a left click (press and release
+/// without moving, so that it does not interfere with camera orbiting).
+pub fn pick_ground_point(
+    mbi: Res<ButtonInput<MouseButton>>,
+    mut press_position: Local<Option<Vec2>>,
+    mut ground_pick: ResMut<GroundPick>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PanOrbitState>>,
+    earth: EarthParam,
+) {
+    let Ok(window) = q_window.get_single() else { return };
+    let Some(cursor) = window.cursor_position() else { return };
+
+    if mbi.just_pressed(MouseButton::Left) {
+        *press_position = Some(cursor);
+    }
+    if !mbi.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(press) = press_position.take() else { return };
+    if press.distance(cursor) > CLICK_MAX_DISTANCE {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = q_camera.get_single() else { return };
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else { return };
+
+    if let Some(point) = earth.earth().intersect(ray.origin.as_dvec3(), ray.direction.as_dvec3()) {
+        ground_pick.point_m = Some(point);
+    }
+}
+
+/// Moves the picked ground point onto the ground surface when the Earth model or the
+/// scene origin change, keeping its World plane position (East, North)
+pub fn reproject_ground_pick(earth: EarthParam, mut ground_pick: ResMut<GroundPick>) {
+    if !earth.is_changed() {
+        return;
+    }
+    let Some(point) = ground_pick.point_m else { return };
+    let reprojected = earth.earth().ground_point(point.truncate());
+    if reprojected != point {
+        ground_pick.point_m = Some(reprojected);
+    }
+}
+
+pub fn spawn_ground_pick_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "Click on the ground to compute bistatic angles",
+            TextStyle {
+                font_size: 16.0,
+                ..default()
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        GroundPickTextMarker
+    ));
+}
+
+fn format_geodetic(position: &GeodeticPosition) -> String {
+    format!(
+        "lat {:.6}°, lon {:.6}°, h {:.1} m",
+        position.latitude_deg,
+        position.longitude_deg,
+        position.height_m
+    )
+}
+
+fn format_carrier(name: &str, geometry: &PlatformGeometry, frame: &LocalFrame) -> Vec<String> {
+    let position = geometry.carrier_position_m;
+    vec![
+        format!(
+            "{} carrier (E, N, U): ({:.1}, {:.1}, {:.1}) m",
+            name, position.x, position.y, position.z
+        ),
+        format!("  {}", format_geodetic(&frame.enu_to_geodetic(position))),
+    ]
+}
+
+fn format_resolution(resolution: &BistaticResolution) -> Vec<String> {
+    let cell = match resolution.ellipse {
+        Some(ellipse) => format!(
+            "{:.2} x {:.2} m (area {:.2} m²)",
+            2.0 * ellipse.semi_major_axis_m,
+            2.0 * ellipse.semi_minor_axis_m,
+            ellipse.area_m2()
+        ),
+        None => "undefined (colinear gradients)".to_string()
+    };
+    vec![
+        format!("  Ground range resolution: {:.2} m", resolution.ground_range_resolution_m),
+        format!(
+            "  Doppler resolution: {:.3} Hz ({:.2} m on ground)",
+            resolution.doppler_resolution_hz,
+            resolution.ground_doppler_resolution_m
+        ),
+        format!(
+            "  Range/Doppler gradients angle: {:.2}°",
+            resolution.gradients_angle_rad.to_degrees()
+        ),
+        format!("  Resolution cell: {}", cell),
+    ]
+}
+
+fn format_radiometry(geometry: &BistaticGeometry, point: DVec3, signal: &SignalState, budget: &LinkBudgetState) -> Vec<String> {
+    let gain = geometry.two_way_gain(point);
+    vec![
+        if gain > 0.0 {
+            format!("  Two-way antenna gain: {:.1} dB", linear_to_db(gain))
+        } else {
+            "  Two-way antenna gain: none (behind an antenna)".to_string()
+        },
+        match geometry.nesz_at(point, signal, budget) {
+            Some(nesz) => format!("  NESZ: {:.1} dB", linear_to_db(nesz)),
+            None => "  NESZ: undefined".to_string()
+        },
+    ]
+}
+
+fn format_footprint(name: &str, footprint: Option<&Footprint>) -> Vec<String> {
+    match footprint {
+        Some(footprint) => vec![
+            format!(
+                "{} footprint: {:.3} km²{}",
+                name,
+                footprint.area_m2 * 1e-6,
+                if footprint.clipped { " (clipped, beam above horizon)" } else { "" }
+            ),
+            format!(
+                "  Near/far ground range: {:.1} / {:.1} m",
+                footprint.near_ground_range_m,
+                footprint.far_ground_range_m
+            ),
+            format!(
+                "  Near/far slant range: {:.1} / {:.1} m",
+                footprint.near_slant_range_m,
+                footprint.far_slant_range_m
+            ),
+        ],
+        None => vec![format!("{} footprint: beam does not reach the ground", name)]
+    }
+}
+
+fn format_common_footprint(common: Option<&CommonFootprint>, earth: &Earth) -> Vec<String> {
+    match common {
+        Some(common) => vec![
+            format!("Common Tx/Rx illuminated area: {:.3} km²", common.area_m2 * 1e-6),
+            format!(
+                "  Centroid (E, N): ({:.1}, {:.1}) m",
+                common.centroid_m.x,
+                common.centroid_m.y
+            ),
+            format!("    {}", format_geodetic(&earth.frame.enu_to_geodetic(earth.ground_point(common.centroid_m)))),
+            format!(
+                "  Extents (E, N): [{:.1}, {:.1}] x [{:.1}, {:.1}] m",
+                common.min_m.x, common.max_m.x,
+                common.min_m.y, common.max_m.y
+            ),
+        ],
+        None => vec!["No common Tx/Rx illuminated area (footprints do not overlap)".to_string()]
+    }
+}
+
+/// Displays the antenna footprints and their common area, the resolutions at the scene center and the bistatic angles and
+/// resolutions at the picked ground point
+pub fn update_ground_pick_text(
+    ground_pick: Res<GroundPick>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_signal: Query<(&SignalState, &LinkBudgetState), With<Tx>>,
+    query_changed: Query<(), StatesChanged>,
+    mut q_text: Query<&mut Text, With<GroundPickTextMarker>>,
+) {
+    if !ground_pick.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let Ok(mut text) = q_text.get_single_mut() else { return };
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    let (signal, budget) = query_signal
+        .get_single()
+        .expect("Can't get `Tx` signal state");
+
+    let frame = geometry.earth.frame;
+
+    let mut lines = format_carrier("Tx", &geometry.tx, &frame);
+    lines.extend(format_carrier("Rx", &geometry.rx, &frame));
+    lines.extend(format_footprint("Tx", platform_footprint(&geometry.tx, &geometry.earth).as_ref()));
+    lines.extend(format_footprint("Rx", platform_footprint(&geometry.rx, &geometry.earth).as_ref()));
+    lines.extend(format_common_footprint(common_footprint(&geometry).as_ref(), &geometry.earth));
+    lines.push("Scene center:".to_string());
+    lines.extend(format_resolution(&geometry.resolution_at(DVec3::ZERO, signal)));
+    lines.extend(format_radiometry(&geometry, DVec3::ZERO, signal, budget));
+    match ground_pick.point_m {
+        Some(point) => {
+            let angles = geometry.angles_at(point);
+            lines.push(format!("Point (E, N): ({:.1}, {:.1}) m", point.x, point.y));
+            lines.push(format!("  {}", format_geodetic(&frame.enu_to_geodetic(point))));
+            lines.push(format!(
+                "  Bistatic angle: {:.2}°",
+                angles.bistatic_angle_rad.to_degrees()
+            ));
+            lines.push(format!(
+                "  Bisector (E, N, U): ({:.3}, {:.3}, {:.3})",
+                angles.bisector.x, angles.bisector.y, angles.bisector.z
+            ));
+            lines.push(format!(
+                "  Bistatic incidence angle: {:.2}°",
+                angles.incidence_angle_rad.to_degrees()
+            ));
+            lines.extend(format_resolution(&geometry.resolution_at(point, signal)));
+            lines.extend(format_radiometry(&geometry, point, signal, budget));
+        },
+        None => lines.push("Click on the ground to compute bistatic angles".to_string())
+    }
+    text.sections[0].value = lines.join("\n");
+}
@@ -0,0 +1,100 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        prelude::{Changed, DetectChanges, Or, Query, With},
+        system::{Res, SystemParam}
+    }
+};
+
+use crate::{
+    geometry::{BistaticGeometry, Earth, PlatformGeometry},
+    state::{
+        AntennaBeamState,
+        AntennaPatternState,
+        AntennaState,
+        CarrierNavigation,
+        CarrierOrbit,
+        CarrierState,
+        CarrierTrajectory,
+        EarthModel,
+        InitialCarrierState,
+        LinkBudgetState,
+        Rx,
+        SceneOrigin,
+        SignalState,
+        Tx
+    }
+};
+
+/// States held by a platform (`Tx` or `Rx`) Carrier entity
+pub type PlatformStates = (
+    &'static CarrierState,
+    &'static AntennaState,
+    &'static AntennaBeamState,
+    &'static AntennaPatternState
+);
+
+/// Mutable states held by a platform Carrier entity, as edited by the user (the Carrier
+/// state being the one at the start of the acquisition)
+pub type PlatformStatesMut = (
+    &'static mut InitialCarrierState,
+    &'static mut AntennaState,
+    &'static mut AntennaBeamState,
+    &'static mut AntennaPatternState,
+    &'static mut CarrierOrbit,
+    &'static mut CarrierTrajectory,
+    &'static mut CarrierNavigation
+);
+
+/// Filter matching platforms whose states changed since the last run of a system
+pub type StatesChanged = Or<(
+    Changed<CarrierState>,
+    Changed<AntennaState>,
+    Changed<AntennaBeamState>,
+    Changed<AntennaPatternState>,
+    Changed<SignalState>,
+    Changed<LinkBudgetState>
+)>;
+
+/// Earth model and scene origin, from which the ground surface is built
+#[derive(SystemParam)]
+pub struct EarthParam<'w> {
+    model: Res<'w, EarthModel>,
+    origin: Res<'w, SceneOrigin>,
+}
+
+impl EarthParam<'_> {
+    #[inline]
+    pub fn earth(&self) -> Earth {
+        Earth::new(*self.model, &self.origin)
+    }
+
+    /// True if the ground surface changed since the last run of the system
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.model.is_changed() || self.origin.is_changed()
+    }
+}
+
+/// Computes the geometry of the `P` (`Tx` or `Rx`) platform from its states
+pub fn platform_geometry<P: Component>(
+    query: &Query<PlatformStates, With<P>>
+) -> PlatformGeometry {
+    let (carrier, antenna, beam, pattern) = query
+        .get_single()
+        .expect("Can't get platform states");
+    PlatformGeometry::new(carrier, antenna, beam, &pattern.0)
+}
+
+/// Computes the bistatic geometry from both platforms states over the `earth` ground surface
+pub fn bistatic_geometry(
+    query_tx: &Query<PlatformStates, With<Tx>>,
+    query_rx: &Query<PlatformStates, With<Rx>>,
+    earth: Earth
+) -> BistaticGeometry {
+    BistaticGeometry::new(
+        platform_geometry(query_tx),
+        platform_geometry(query_rx),
+        earth
+    )
+}
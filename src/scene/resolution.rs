@@ -0,0 +1,94 @@
+use bevy::{
+    asset::{Assets, Handle},
+    color::LinearRgba,
+    ecs::{
+        component::Component,
+        prelude::{Commands, Query, With},
+        system::{Res, ResMut}
+    },
+    math::DVec3,
+    pbr::{PbrBundle, StandardMaterial},
+    prelude::{DetectChanges, Mesh, Visibility},
+};
+
+use crate::{
+    mesh::LineStrip,
+    scene::{bistatic_geometry, EarthParam, GroundPick, PlatformStates, StatesChanged},
+    state::{Rx, SignalState, Tx}
+};
+
+const ELLIPSE_SEGMENTS: usize = 72;
+
+/// Ground point on which a resolution ellipse is drawn
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionEllipseMarker {
+    SceneCenter,
+    GroundPick
+}
+
+pub fn spawn_resolution_ellipses(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(
+        StandardMaterial {
+            base_color: LinearRgba::new(1.0, 1.0, 0.0, 1.0).into(),
+            unlit: true,
+            ..Default::default()
+        }
+    );
+    for marker in [ResolutionEllipseMarker::SceneCenter, ResolutionEllipseMarker::GroundPick] {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(LineStrip { points: Vec::new() }),
+                material: material.clone(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            marker
+        ));
+    }
+}
+
+/// Redraws the resolution cell ellipses at the scene center and at the picked ground point
+#[allow(clippy::too_many_arguments)]
+pub fn update_resolution_ellipses(
+    ground_pick: Res<GroundPick>,
+    earth: EarthParam,
+    query_tx: Query<PlatformStates, With<Tx>>,
+    query_rx: Query<PlatformStates, With<Rx>>,
+    query_signal: Query<&SignalState, With<Tx>>,
+    query_changed: Query<(), StatesChanged>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query_ellipse: Query<(&ResolutionEllipseMarker, &mut Handle<Mesh>, &mut Visibility)>,
+) {
+    if !ground_pick.is_changed() && !earth.is_changed() && query_changed.is_empty() {
+        return;
+    }
+    let geometry = bistatic_geometry(&query_tx, &query_rx, earth.earth());
+    let signal = query_signal
+        .get_single()
+        .expect("Can't get `Tx` signal state");
+
+    for (marker, mut mesh, mut visibility) in &mut query_ellipse {
+        let point = match marker {
+            ResolutionEllipseMarker::SceneCenter => Some(DVec3::ZERO),
+            ResolutionEllipseMarker::GroundPick => ground_pick.point_m
+        };
+        let ellipse = point.and_then(|point| geometry.resolution_at(point, signal).ellipse);
+        match ellipse {
+            Some(ellipse) => {
+                *mesh = meshes.add(LineStrip {
+                    points: ellipse
+                        .points(ELLIPSE_SEGMENTS)
+                        .into_iter()
+                        .map(|p| p.as_vec3())
+                        .collect()
+                });
+                *visibility = Visibility::Inherited;
+            },
+            None => *visibility = Visibility::Hidden
+        }
+    }
+}
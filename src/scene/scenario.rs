@@ -0,0 +1,144 @@
+use bevy::ecs::{
+    event::{Event, EventReader},
+    prelude::{DetectChangesMut, Query, With, Without},
+    query::QueryFilter,
+    system::{ResMut, Resource}
+};
+use std::path::PathBuf;
+
+use crate::{
+    scenario::{CameraScenario, PlatformScenario, Scenario},
+    scene::{PanOrbitState, PlatformStatesMut, Timeline},
+    state::{AntennaPatternState, CarrierNavigation, CarrierOrbit, CarrierTrajectory, EarthModel, InitialCarrierState, LinkBudgetState, Rx, SceneOrigin, SignalState, Tx}
+};
+
+/// Scenario used to build the scene at startup
+#[derive(Resource)]
+pub struct InitialScenario(pub Scenario);
+
+/// Requests to open or save a scenario file
+#[derive(Event)]
+pub enum ScenarioEvent {
+    Open(PathBuf),
+    Save(PathBuf),
+}
+
+/// Current scenario file path and status of the last open/save operation
+#[derive(Resource, Default)]
+pub struct ScenarioFile {
+    pub path: String,
+    pub status: String,
+}
+
+/// Camera point of view of the pan-orbit controller state
+pub fn camera_scenario(state: &PanOrbitState) -> CameraScenario {
+    CameraScenario {
+        center_m: state.center,
+        radius_m: state.radius,
+        pitch_deg: state.pitch.to_degrees(),
+        yaw_deg: state.yaw.to_degrees(),
+    }
+}
+
+/// Pan-orbit controller state looking from the `camera` point of view
+pub fn pan_orbit_state(camera: &CameraScenario) -> PanOrbitState {
+    PanOrbitState {
+        center: camera.center_m,
+        z_focus: camera.center_m.z,
+        radius: camera.radius_m,
+        pitch: camera.pitch_deg.to_radians(),
+        yaw: camera.yaw_deg.to_radians(),
+    }
+}
+
+fn apply_platform<F: QueryFilter>(
+    platform: &PlatformScenario,
+    query: &mut Query<PlatformStatesMut, F>
+) {
+    if let Ok((mut carrier, mut antenna, mut beam, mut pattern, mut orbit, mut trajectory, mut navigation)) = query.get_single_mut() {
+        carrier.set_if_neq(InitialCarrierState(platform.carrier));
+        antenna.set_if_neq(platform.antenna);
+        beam.set_if_neq(platform.beam);
+        pattern.set_if_neq(AntennaPatternState(platform.pattern.clone()));
+        orbit.set_if_neq(CarrierOrbit(platform.orbit.clone()));
+        trajectory.set_if_neq(CarrierTrajectory(platform.trajectory.clone()));
+        navigation.set_if_neq(CarrierNavigation(platform.navigation.clone()));
+    }
+}
+
+fn read_platform<F: QueryFilter>(
+    query: &Query<PlatformStatesMut, F>
+) -> PlatformScenario {
+    let (carrier, antenna, beam, pattern, orbit, trajectory, navigation) = query
+        .get_single()
+        .expect("Can't get platform states");
+    PlatformScenario {
+        carrier: carrier.0,
+        antenna: *antenna,
+        beam: *beam,
+        pattern: pattern.0.clone(),
+        geodetic: None,
+        orbit: orbit.0.clone(),
+        trajectory: trajectory.0.clone(),
+        navigation: navigation.0.clone(),
+    }
+}
+
+/// Opens and saves scenario files, applying opened scenarios to the scene
+#[allow(clippy::too_many_arguments)]
+pub fn handle_scenario_events(
+    mut events: EventReader<ScenarioEvent>,
+    mut file: ResMut<ScenarioFile>,
+    mut origin: ResMut<SceneOrigin>,
+    mut earth: ResMut<EarthModel>,
+    mut query_tx: Query<PlatformStatesMut, (With<Tx>, Without<Rx>)>,
+    mut query_rx: Query<PlatformStatesMut, (With<Rx>, Without<Tx>)>,
+    mut query_signal: Query<(&mut SignalState, &mut LinkBudgetState), With<Tx>>,
+    mut query_camera: Query<&mut PanOrbitState>,
+    mut timeline: ResMut<Timeline>,
+) {
+    for event in events.read() {
+        match event {
+            ScenarioEvent::Open(path) => match Scenario::load(path) {
+                Ok(scenario) => {
+                    origin.set_if_neq(scenario.origin);
+                    earth.set_if_neq(scenario.earth);
+                    apply_platform(&scenario.tx, &mut query_tx);
+                    apply_platform(&scenario.rx, &mut query_rx);
+                    if let Ok((mut signal, mut budget)) = query_signal.get_single_mut() {
+                        signal.set_if_neq(scenario.signal);
+                        budget.set_if_neq(scenario.link_budget);
+                    }
+                    if let (Some(camera), Ok(mut state)) = (scenario.camera, query_camera.get_single_mut()) {
+                        *state = pan_orbit_state(&camera);
+                    }
+                    timeline.set_if_neq(Timeline::default()); // Back to the start of the acquisition
+                    file.status = format!("Opened {}", path.display());
+                },
+                Err(err) => file.status = format!("Can't open {}: {}", path.display(), err)
+            },
+            ScenarioEvent::Save(path) => {
+                let scenario = Scenario {
+                    origin: *origin,
+                    earth: *earth,
+                    tx: read_platform(&query_tx),
+                    rx: read_platform(&query_rx),
+                    signal: query_signal
+                        .get_single()
+                        .map(|(signal, _)| *signal)
+                        .unwrap_or_default(),
+                    link_budget: query_signal
+                        .get_single()
+                        .map(|(_, budget)| *budget)
+                        .unwrap_or_default(),
+                    camera: query_camera.get_single().ok().map(camera_scenario),
+                    ..Default::default()
+                };
+                file.status = match scenario.save(path) {
+                    Ok(()) => format!("Saved {}", path.display()),
+                    Err(err) => format!("Can't save {}: {}", path.display(), err)
+                };
+            }
+        }
+    }
+}
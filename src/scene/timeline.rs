@@ -0,0 +1,102 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        prelude::{DetectChanges, DetectChangesMut, Query, Ref, With},
+        system::{Res, ResMut, Resource}
+    },
+    time::Time
+};
+
+use crate::{
+    scenario::carrier_motion_state,
+    state::{
+        CarrierNavigation,
+        CarrierOrbit,
+        CarrierState,
+        CarrierTrajectory,
+        InitialCarrierState,
+        SceneOrigin,
+        SignalState,
+        Tx
+    }
+};
+
+/// Motion settings of a carrier and its state moved along them
+type CarrierMotion = (
+    Ref<'static, InitialCarrierState>,
+    Ref<'static, CarrierOrbit>,
+    Ref<'static, CarrierNavigation>,
+    Ref<'static, CarrierTrajectory>,
+    &'static mut CarrierState
+);
+
+/// Acquisition timeline: the time at which the carriers are displayed, within the
+/// synthetic aperture integration time
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Timeline {
+    /// Time elapsed since the start of the acquisition
+    pub time_s: f64,
+    pub playing: bool,
+    /// Playback speed (acquisition seconds per second)
+    pub rate: f64,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            time_s: 0.0,
+            playing: false,
+            rate: 1.0,
+        }
+    }
+}
+
+/// Acquisition duration: the integration time of the signal transmitted by Tx
+pub fn acquisition_duration_s(query_signal: &Query<&SignalState, With<Tx>>) -> f64 {
+    query_signal
+        .get_single()
+        .map_or(SignalState::default().integration_time_s, |signal| signal.integration_time_s)
+}
+
+/// Advances the timeline while playing, looping over the acquisition
+pub fn advance_timeline(
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    query_signal: Query<&SignalState, With<Tx>>,
+) {
+    if !timeline.playing {
+        return;
+    }
+    let duration_s = acquisition_duration_s(&query_signal);
+    timeline.time_s = (timeline.time_s + timeline.rate * time.delta_seconds_f64()).rem_euclid(duration_s);
+}
+
+/// Moves the `P` (`Tx` or `Rx`) carrier to its state at the timeline time: along its orbit
+/// for a spaceborne carrier, else along its navigation log if any, else along its trajectory
+pub fn update_carrier_motion<P: Component>(
+    timeline: Res<Timeline>,
+    origin: Res<SceneOrigin>,
+    mut query: Query<CarrierMotion, With<P>>,
+) {
+    let Ok((initial, orbit, navigation, trajectory, mut carrier)) = query.get_single_mut() else { return };
+    let motion_changed = initial.is_changed()
+        || orbit.is_changed()
+        || navigation.is_changed()
+        || trajectory.is_changed();
+    if !motion_changed && !timeline.is_changed() && !origin.is_changed() {
+        return;
+    }
+    let state = carrier_motion_state(
+        &origin.frame(),
+        &initial.0,
+        orbit.0.as_ref(),
+        navigation.0.as_ref(),
+        &trajectory.0,
+        timeline.time_s
+    );
+    // Invalid orbits and empty navigation logs leave the carrier in place, times out of
+    // the log hold it at the first or last record
+    if let Some(state) = state {
+        carrier.set_if_neq(state);
+    }
+}
@@ -0,0 +1,51 @@
+use bsarconf::{
+    scenario::Scenario,
+    simulation::{simulate, PointTarget, RawData}
+};
+
+use crate::{cli::SimulateArgs, compute::EXIT_ERROR};
+
+/// Headless simulation mode: loads the scenario and the targets, writes the simulated raw
+/// data and its sidecar, and returns the process exit status
+pub fn run(args: &SimulateArgs) -> i32 {
+    let scenario = match Scenario::load(&args.scenario) {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            eprintln!("Can't load scenario {}: {}", args.scenario.display(), err);
+            return EXIT_ERROR;
+        }
+    };
+    let mut targets = args.targets.clone();
+    for path in &args.target_files {
+        match PointTarget::load(path) {
+            Ok(loaded) => targets.extend(loaded),
+            Err(err) => {
+                eprintln!("Can't load targets {}: {}", path.display(), err);
+                return EXIT_ERROR;
+            }
+        }
+    }
+
+    let sampling_rate_hz = args
+        .sampling_rate_hz
+        .unwrap_or(scenario.signal.rx_sampling_rate_hz());
+    let raw = match simulate(&scenario, &targets, sampling_rate_hz) {
+        Ok(raw) => raw,
+        Err(err) => {
+            eprintln!("Can't simulate raw data: {}", err);
+            return EXIT_ERROR;
+        }
+    };
+    if let Err(err) = raw.save(&args.output) {
+        eprintln!("Can't write raw data {}: {}", args.output.display(), err);
+        return EXIT_ERROR;
+    }
+    println!(
+        "{} pulses of {} samples written to {} (sidecar {})",
+        raw.header.pulses,
+        raw.header.samples_per_pulse,
+        args.output.display(),
+        RawData::sidecar_path(&args.output).display()
+    );
+    0
+}
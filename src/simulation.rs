@@ -0,0 +1,318 @@
+//! Point-target raw echo simulation: the complex baseband data that Rx would record
+//! for a list of point targets on the ground, illuminated by the chirp pulses of Tx
+//! along the carriers motion over the acquisition.
+//!
+//! Echoes follow the stop-and-hop approximation (carriers are still during a pulse
+//! and its echo), are weighted by the Tx and Rx antenna patterns and by the bistatic
+//! radar equation, and are demodulated by the carrier frequency. Neither the thermal
+//! noise nor the direct Tx to Rx signal is simulated.
+//!
+//! Point targets are read from CSV files with a header row naming the columns, in any
+//! order, separated by commas or semicolons. Lines starting with `#` are comments.
+//! The columns are (with accepted aliases):
+//! - `east` (`east_m`, `x`), `north` (`north_m`, `y`): ground position in meters, in
+//!   World frame
+//! - `rcs` (`rcs_m2`): radar cross-section in square meters, optional (1 m² if absent)
+
+use bevy::math::{DVec2, DVec3};
+use num_complex::{Complex32, Complex64};
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::{PI, TAU},
+    fmt,
+    fs,
+    io,
+    path::Path
+};
+
+use crate::{
+    constants::SPEED_OF_LIGHT_M_S,
+    geometry::{BistaticGeometry, Earth, PlatformGeometry},
+    scenario::Scenario
+};
+
+mod raw;
+pub use raw::{RawData, RawDataHeader, RAW_DATA_FORMAT};
+pub(crate) use raw::{companion_path, read_samples, write_samples, SAMPLE_BYTES};
+
+/// Largest number of simulated samples (2 GiB of raw data)
+const MAX_SAMPLES: usize = 1 << 28;
+
+/// Column names accepted for the east, north and radar cross-section fields
+const COLUMNS: [&[&str]; 3] = [
+    &["east", "east_m", "x"],
+    &["north", "north_m", "y"],
+    &["rcs", "rcs_m2"],
+];
+
+/// A point target on the ground
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PointTarget {
+    /// Ground position (East, North) in World frame
+    pub position_m: DVec2,
+    /// Radar cross-section
+    pub rcs_m2: f64,
+}
+
+#[derive(Debug)]
+pub enum SimulationError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// Invalid target file content at a 1-based line number
+    Parse { line: usize, message: String },
+    /// Inconsistent signal, sampling or raw data parameters
+    Settings(String),
+    /// A carrier state is undefined (invalid orbit or time out of a navigation log)
+    Motion { time_s: f64 },
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Json(err) => write!(f, "invalid JSON: {}", err),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Self::Settings(message) => write!(f, "{}", message),
+            Self::Motion { time_s } => write!(f, "no carrier state at {} s after the acquisition start", time_s),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+impl From<io::Error> for SimulationError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SimulationError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl PointTarget {
+    pub fn new(position_m: DVec2) -> Self {
+        Self { position_m, rcs_m2: 1.0 }
+    }
+
+    /// Reads point targets from a CSV file content
+    pub fn from_csv_str(s: &str) -> Result<Vec<Self>, SimulationError> {
+        let error = |line: usize, message: String| SimulationError::Parse { line, message };
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(k, line)| (k + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let separator = |line: &str| if line.contains(';') { ';' } else { ',' };
+
+        let (header_line, header) = lines.next().ok_or_else(|| error(1, "missing header row".to_string()))?;
+        let names: Vec<String> = header
+            .split(separator(header))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let [east_column, north_column, rcs_column] = COLUMNS
+            .map(|aliases| names.iter().position(|name| aliases.contains(&name.as_str())));
+        let (Some(east_column), Some(north_column)) = (east_column, north_column) else {
+            return Err(error(header_line, "missing east or north column".to_string()));
+        };
+
+        let mut targets = Vec::new();
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(separator(line)).map(str::trim).collect();
+            let value = |column: usize, field: &str| -> Result<Option<f64>, SimulationError> {
+                match fields.get(column).copied() {
+                    None | Some("") => Ok(None),
+                    Some(text) => text
+                        .parse()
+                        .map(Some)
+                        .map_err(|_| error(line_number, format!("invalid {} value `{}`", field, text))),
+                }
+            };
+            let required = |column: usize, field: &str| value(column, field)?
+                .ok_or_else(|| error(line_number, format!("missing {} value", field)));
+            let mut target = Self::new(DVec2::new(required(east_column, "east")?, required(north_column, "north")?));
+            if let Some(rcs_m2) = rcs_column.map(|column| value(column, "rcs")).transpose()?.flatten() {
+                if rcs_m2 < 0.0 {
+                    return Err(error(line_number, format!("negative radar cross-section {}", rcs_m2)));
+                }
+                target.rcs_m2 = rcs_m2;
+            }
+            targets.push(target);
+        }
+        Ok(targets)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Self>, SimulationError> {
+        Self::from_csv_str(&fs::read_to_string(path)?)
+    }
+}
+
+/// Echo of a target for a pulse
+#[derive(Clone, Copy, Debug)]
+struct Echo {
+    /// Tx to target to Rx propagation delay
+    delay_s: f64,
+    /// Received amplitude (square root of the received peak power)
+    amplitude: f64,
+}
+
+/// Simulates the raw data recorded by Rx for `targets` over the acquisition of
+/// `scenario`, sampled at `sampling_rate_hz`. Pulses are transmitted at the PRF from
+/// the start of the acquisition for its integration time, and every pulse is recorded
+/// over the same window, spanning the echoes of all the targets.
+pub fn simulate(scenario: &Scenario, targets: &[PointTarget], sampling_rate_hz: f64) -> Result<RawData, SimulationError> {
+    let signal = &scenario.signal;
+    if targets.is_empty() {
+        return Err(SimulationError::Settings("no point target to simulate".to_string()));
+    }
+    if !(signal.prf_hz > 0.0 && signal.pulse_duration_s > 0.0 && sampling_rate_hz > 0.0) {
+        return Err(SimulationError::Settings(
+            "the PRF, pulse duration and sampling rate must be positive".to_string()
+        ));
+    }
+    let frame = scenario.origin.frame();
+    let earth = Earth::new(scenario.earth, &scenario.origin);
+    let pulses = (signal.integration_time_s * signal.prf_hz).round().max(1.0) as usize;
+    let pulse_times_s: Vec<f64> = (0..pulses).map(|n| n as f64 / signal.prf_hz).collect();
+    let points_m: Vec<DVec3> = targets.iter().map(|target| earth.ground_point(target.position_m)).collect();
+
+    let mut geometry = BistaticGeometry::new(
+        PlatformGeometry::new(&scenario.tx.carrier, &scenario.tx.antenna, &scenario.tx.beam, &scenario.tx.pattern),
+        PlatformGeometry::new(&scenario.rx.carrier, &scenario.rx.antenna, &scenario.rx.beam, &scenario.rx.pattern),
+        earth
+    );
+    let mut tx_positions_m = Vec::with_capacity(pulses);
+    let mut rx_positions_m = Vec::with_capacity(pulses);
+    let mut echoes = Vec::with_capacity(pulses * targets.len());
+    for &time_s in &pulse_times_s {
+        for (platform, platform_scenario) in [(&mut geometry.tx, &scenario.tx), (&mut geometry.rx, &scenario.rx)] {
+            let carrier = platform_scenario
+                .carrier_state_at(&frame, time_s)
+                .ok_or(SimulationError::Motion { time_s })?;
+            platform.set_carrier(&carrier, &platform_scenario.antenna);
+        }
+        tx_positions_m.push(geometry.tx.antenna_position_m);
+        rx_positions_m.push(geometry.rx.antenna_position_m);
+        for (target, &point_m) in targets.iter().zip(&points_m) {
+            echoes.push(Echo {
+                delay_s: (point_m.distance(geometry.tx.antenna_position_m)
+                    + point_m.distance(geometry.rx.antenna_position_m)) / SPEED_OF_LIGHT_M_S,
+                amplitude: geometry
+                    .echo_power_w(point_m, target.rcs_m2, signal, &scenario.link_budget)
+                    .sqrt(),
+            });
+        }
+    }
+
+    // Recording window, starting on a sample
+    let (min_delay_s, max_delay_s) = echoes
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), echo| (min.min(echo.delay_s), max.max(echo.delay_s)));
+    let window_start_s = (min_delay_s * sampling_rate_hz).floor() / sampling_rate_hz;
+    let samples_per_pulse = ((max_delay_s + signal.pulse_duration_s - window_start_s) * sampling_rate_hz).ceil() as usize + 1;
+    if pulses.saturating_mul(samples_per_pulse) > MAX_SAMPLES {
+        return Err(SimulationError::Settings(format!(
+            "{} pulses of {} samples exceed the {} samples limit",
+            pulses,
+            samples_per_pulse,
+            MAX_SAMPLES
+        )));
+    }
+
+    let header = RawDataHeader {
+        format: RAW_DATA_FORMAT.to_string(),
+        data_file: Default::default(),
+        pulses,
+        samples_per_pulse,
+        sampling_rate_hz,
+        prf_hz: signal.prf_hz,
+        carrier_frequency_hz: signal.carrier_frequency_hz,
+        bandwidth_hz: signal.bandwidth_hz,
+        pulse_duration_s: signal.pulse_duration_s,
+        chirp_rate_hz_s: signal.chirp_rate_hz_s(),
+        window_start_s,
+        origin: scenario.origin,
+        earth: scenario.earth,
+        pulse_times_s,
+        tx_positions_m,
+        rx_positions_m,
+        targets: targets.to_vec(),
+    };
+    let mut samples = vec![Complex32::ZERO; pulses * samples_per_pulse];
+    for (pulse, pulse_echoes) in samples.chunks_mut(samples_per_pulse).zip(echoes.chunks(targets.len())) {
+        for echo in pulse_echoes.iter().filter(|echo| echo.amplitude > 0.0) {
+            // Samples within the echo, whose carrier phase is the one of the delay
+            let first = ((echo.delay_s - window_start_s) * sampling_rate_hz).ceil() as usize;
+            let end = ((echo.delay_s + signal.pulse_duration_s - window_start_s) * sampling_rate_hz).ceil() as usize;
+            let carrier_phase = (-TAU * signal.carrier_frequency_hz * echo.delay_s).rem_euclid(TAU);
+            for (i, sample) in pulse.iter_mut().enumerate().take(end.min(samples_per_pulse)).skip(first) {
+                let u = header.sample_delay_s(i) - echo.delay_s - 0.5 * signal.pulse_duration_s;
+                let value = Complex64::from_polar(echo.amplitude, PI * header.chirp_rate_hz_s * u * u + carrier_phase);
+                *sample += Complex32::new(value.re as f32, value.im as f32);
+            }
+        }
+    }
+    Ok(RawData { header, samples })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{env, path::PathBuf};
+
+    use crate::focusing::{backproject, FocusingGrid};
+
+    use super::*;
+
+    /// Default scenario over a short acquisition, with a target at the ground point of the
+    /// Rx boresight, on which the Tx antenna is pointed
+    pub(crate) fn short_acquisition() -> (Scenario, PointTarget) {
+        let mut scenario = Scenario::default();
+        scenario.signal.bandwidth_hz = 50e6;
+        scenario.signal.integration_time_s = 0.2;
+        scenario.signal.prf_hz = 500.0;
+        scenario.signal.pulse_duration_s = 2e-6;
+        let earth = Earth::new(scenario.earth, &scenario.origin);
+        let rx = PlatformGeometry::new(&scenario.rx.carrier, &scenario.rx.antenna, &scenario.rx.beam, &scenario.rx.pattern);
+        let point_m = earth.intersect(rx.antenna_position_m, rx.boresight()).unwrap();
+
+        // The Tx carrier is level and heading North
+        let direction = point_m - scenario.tx.carrier.position_enu_m();
+        scenario.tx.antenna.heading_deg = direction.x.atan2(direction.y).to_degrees();
+        scenario.tx.antenna.elevation_deg = direction.z.atan2(direction.truncate().length()).to_degrees();
+        (scenario, PointTarget { position_m: point_m.truncate(), rcs_m2: 1.0 })
+    }
+
+    #[test]
+    fn focused_target_lies_within_a_pixel() {
+        let (scenario, target) = short_acquisition();
+        let raw = simulate(&scenario, &[target], scenario.signal.rx_sampling_rate_hz()).unwrap();
+        assert_eq!(raw.header.pulses, 100);
+
+        // Target between grid points
+        let spacing_m = 0.25 * scenario.signal.range_sum_resolution_m();
+        let center_m = target.position_m + DVec2::new(0.4, -0.3) * spacing_m;
+        let grid = FocusingGrid::centered(center_m, DVec2::splat(16.0 * spacing_m), spacing_m);
+        let image = backproject(&raw, &grid, 2).unwrap();
+        let (i, j) = image.peak();
+        let offset_m = (grid.position_m(i, j) - target.position_m).abs();
+        assert!(offset_m.max_element() <= spacing_m, "peak {:?} m from the target", offset_m);
+    }
+
+    #[test]
+    fn sidecar_does_not_overwrite_data() {
+        let (scenario, target) = short_acquisition();
+        let raw = simulate(&scenario, &[target], scenario.signal.rx_sampling_rate_hz()).unwrap();
+        let path = env::temp_dir().join(format!("bsarconf-simulate-{}.json", std::process::id()));
+        let sidecar = RawData::sidecar_path(&path);
+        assert_eq!(sidecar, PathBuf::from(format!("{}.json", path.display())));
+
+        raw.save(&path).unwrap();
+        let loaded = RawData::load(&sidecar).unwrap();
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&sidecar).unwrap();
+        assert_eq!(loaded.header.data_file, PathBuf::from(path.file_name().unwrap()));
+        assert_eq!(loaded.samples, raw.samples);
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::math::DVec3;
+use num_complex::Complex32;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf}
+};
+
+use crate::state::{EarthModel, SceneOrigin};
+
+use super::{PointTarget, SimulationError};
+
+/// Raw data sample format: complex samples made of two little-endian f32 (I then Q),
+/// stored pulse after pulse
+pub const RAW_DATA_FORMAT: &str = "cf32le";
+/// Size in bytes of a raw data sample
+pub(crate) const SAMPLE_BYTES: usize = 8;
+
+/// Raw data description, stored as a JSON sidecar of the binary data file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RawDataHeader {
+    /// Sample format, `RAW_DATA_FORMAT`
+    pub format: String,
+    /// Binary data file, relative to the sidecar directory
+    pub data_file: PathBuf,
+    pub pulses: usize,
+    pub samples_per_pulse: usize,
+    /// Rx sampling rate (complex samples per second)
+    pub sampling_rate_hz: f64,
+    pub prf_hz: f64,
+    /// Frequency by which the echoes have been demodulated
+    pub carrier_frequency_hz: f64,
+    /// Chirp bandwidth and duration
+    pub bandwidth_hz: f64,
+    pub pulse_duration_s: f64,
+    /// Chirp frequency rate, positive for an up-chirp centered on the pulse middle
+    pub chirp_rate_hz_s: f64,
+    /// Delay of the first sample of each pulse after the pulse transmission
+    pub window_start_s: f64,
+    /// World frame origin and ground surface model of the simulated scenario
+    pub origin: SceneOrigin,
+    pub earth: EarthModel,
+    /// Transmission time of each pulse after the start of the acquisition
+    pub pulse_times_s: Vec<f64>,
+    /// Tx and Rx antenna phase center positions at each pulse, in World frame
+    pub tx_positions_m: Vec<DVec3>,
+    pub rx_positions_m: Vec<DVec3>,
+    /// Simulated point targets
+    pub targets: Vec<PointTarget>,
+}
+
+/// Complex baseband raw data: `pulses` rows of `samples_per_pulse` samples
+#[derive(Clone, Debug)]
+pub struct RawData {
+    pub header: RawDataHeader,
+    pub samples: Vec<Complex32>,
+}
+
+/// File written next to the data file `path`, named after its full file name followed by
+/// `.extension`, so that it can't overwrite the data file whatever its extension
+pub(crate) fn companion_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Writes `samples` to the file `path` in the `RAW_DATA_FORMAT` format
+pub(crate) fn write_samples(path: &Path, samples: &[Complex32]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for sample in samples {
+        writer.write_all(&sample.re.to_le_bytes())?;
+        writer.write_all(&sample.im.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Reads the samples of the file `path`, in the `RAW_DATA_FORMAT` format. Trailing bytes
+/// of an incomplete sample are ignored: callers check the file size.
+pub(crate) fn read_samples(path: &Path) -> io::Result<Vec<Complex32>> {
+    let bytes = fs::read(path)?;
+    let float = |bytes: &[u8]| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    Ok(bytes
+        .chunks_exact(SAMPLE_BYTES)
+        .map(|sample| Complex32::new(float(&sample[..4]), float(&sample[4..])))
+        .collect())
+}
+
+impl RawDataHeader {
+    /// Delay after the pulse transmission of the sample `index` of a pulse
+    #[inline]
+    pub fn sample_delay_s(&self, index: usize) -> f64 {
+        self.window_start_s + index as f64 / self.sampling_rate_hz
+    }
+
+    /// Checks the consistency of the sample format and of the per-pulse arrays
+    fn validate(&self) -> Result<(), SimulationError> {
+        if self.format != RAW_DATA_FORMAT {
+            return Err(SimulationError::Settings(format!("unsupported raw data format `{}`", self.format)));
+        }
+        let per_pulse = [self.pulse_times_s.len(), self.tx_positions_m.len(), self.rx_positions_m.len()];
+        if per_pulse.iter().any(|&len| len != self.pulses) {
+            return Err(SimulationError::Settings(format!(
+                "pulse times and positions are not given for each of the {} pulses",
+                self.pulses
+            )));
+        }
+        if !(self.sampling_rate_hz > 0.0 && self.prf_hz > 0.0) {
+            return Err(SimulationError::Settings("the sampling rate and PRF must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl RawData {
+    /// Samples of the pulse `index`
+    #[inline]
+    pub fn pulse(&self, index: usize) -> &[Complex32] {
+        let n = self.header.samples_per_pulse;
+        &self.samples[index * n..(index + 1) * n]
+    }
+
+    /// Sidecar file of the data file `path`: its full file name followed by `.json`
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        companion_path(path, "json")
+    }
+
+    /// Writes the samples to the data file `path` and the header to its JSON sidecar
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SimulationError> {
+        let path = path.as_ref();
+        write_samples(path, &self.samples)?;
+
+        let header = RawDataHeader {
+            data_file: path.file_name().map(PathBuf::from).unwrap_or_default(),
+            ..self.header.clone()
+        };
+        fs::write(Self::sidecar_path(path), serde_json::to_string_pretty(&header)?)?;
+        Ok(())
+    }
+
+    /// Reads raw data from its JSON sidecar `path` and the data file it refers to
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SimulationError> {
+        let path = path.as_ref();
+        let header: RawDataHeader = serde_json::from_str(&fs::read_to_string(path)?)?;
+        header.validate()?;
+        let data_path = path.parent().unwrap_or(Path::new("")).join(&header.data_file);
+        let expected = header.pulses * header.samples_per_pulse;
+        if fs::metadata(&data_path)?.len() != (expected * SAMPLE_BYTES) as u64 {
+            return Err(SimulationError::Settings(format!(
+                "the data file does not hold {} pulses of {} samples",
+                header.pulses,
+                header.samples_per_pulse
+            )));
+        }
+        let samples = read_samples(&data_path)?;
+        Ok(Self { header, samples })
+    }
+}
@@ -0,0 +1,300 @@
+use bevy::{
+    ecs::{component::Component, system::Resource},
+    math::{DQuat, DVec2, DVec3, EulerRot}
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{DEFAULT_OVERSAMPLING, ENU_TO_NED_DROT, SPEED_OF_LIGHT_M_S},
+    geodesy::{GeodeticPosition, LocalFrame},
+    navigation::Navigation,
+    orbit::OrbitState,
+    pattern::AntennaPattern,
+    trajectory::Trajectory
+};
+
+// Markers of the Transmitter and Receiver platforms entities
+#[derive(Component)]
+pub struct Tx;
+
+#[derive(Component)]
+pub struct Rx;
+
+// The internal state of the Carrier
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct CarrierState {
+    /// Carrier orientation in World frame (NED referential)
+    pub heading_deg: f64,
+    pub elevation_deg: f64,
+    pub bank_deg: f64,
+    /// Carrier height above the World plane
+    pub height_m: f64,
+    /// Carrier horizontal position in World frame (East, North)
+    pub position_m: DVec2,
+    /// Carrier velocity in World frame (East, North, Up)
+    pub velocity_m_s: DVec3,
+    /// Carrier to Antenna phase center lever arms (in NED Carier frame)
+    pub lever_arms_m: DVec3,
+}
+
+// The Carrier state at the start of the acquisition, from which the CarrierState at the
+// timeline time is computed along the Carrier trajectory or orbit
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct InitialCarrierState(pub CarrierState);
+
+// The trajectory of an airborne Carrier over the acquisition
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct CarrierTrajectory(pub Trajectory);
+
+// The navigation log driving an airborne Carrier instead of its trajectory (None if not measured)
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct CarrierNavigation(pub Option<Navigation>);
+
+// The orbit of a spaceborne Carrier, which then drives the CarrierState (None for an airborne Carrier)
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct CarrierOrbit(pub Option<OrbitState>);
+
+// The internal state of the Antenna
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct AntennaState {
+    /// Antenna orientation relative to Carrier
+    pub heading_deg: f64,
+    pub elevation_deg: f64,
+    pub bank_deg: f64,
+}
+
+// The internal state of the Antenna
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct AntennaBeamState {
+    /// Antenna 3d beam widths
+    pub elevation_beam_width_deg: f64,
+    pub azimuth_beam_width_deg: f64,
+    /// Antenna peak (boresight) gain
+    pub gain_dbi: f64,
+    /// Gain relative to the peak gain of the contour drawn as the antenna cone and
+    /// bounding the footprint
+    pub cone_level_db: f64,
+}
+
+// The radiation pattern of the Antenna
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct AntennaPatternState(pub AntennaPattern);
+
+// The waveform transmitted by Tx (linear chirp pulses) and its sampling by Rx
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct SignalState {
+    pub carrier_frequency_hz: f64,
+    /// Chirp bandwidth
+    pub bandwidth_hz: f64,
+    /// Synthetic aperture integration time
+    pub integration_time_s: f64,
+    /// Pulse repetition frequency
+    pub prf_hz: f64,
+    pub pulse_duration_s: f64,
+    /// Rx complex sampling rate, `None` for `DEFAULT_OVERSAMPLING` times the bandwidth
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling_rate_hz: Option<f64>,
+}
+
+// The radar equation parameters other than the signal and the antenna gains
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct LinkBudgetState {
+    /// Transmitter peak power
+    pub transmit_power_w: f64,
+    /// System losses (transmission, propagation and processing)
+    pub losses_db: f64,
+    /// Receiver noise figure
+    pub noise_figure_db: f64,
+}
+
+// The geodetic position of the World frame origin (ENU referential tangent to the WGS84 ellipsoid)
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct SceneOrigin(pub GeodeticPosition);
+
+// The shape of the ground on which beams are intersected
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EarthModel {
+    /// Plane tangent to the ellipsoid at the scene origin (World plane Z = 0)
+    #[default]
+    Flat,
+    /// Sphere of mean Earth radius, tangent to the World plane at the origin
+    Sphere,
+    /// WGS84 ellipsoid raised to the height of the scene origin
+    Wgs84,
+}
+
+impl SceneOrigin {
+    #[inline]
+    pub fn frame(&self) -> LocalFrame {
+        LocalFrame::new(self.0)
+    }
+}
+
+impl CarrierState {
+    /// Carrier position in World frame (East, North, Up)
+    #[inline]
+    pub fn position_enu_m(&self) -> DVec3 {
+        self.position_m.extend(self.height_m)
+    }
+
+    /// Moves the carrier to the World frame position `enu_m`
+    #[inline]
+    pub fn set_position_enu_m(&mut self, enu_m: DVec3) {
+        self.position_m = enu_m.truncate();
+        self.height_m = enu_m.z;
+    }
+
+    /// Orients the carrier from its rotation from World axes to Carrier NED axes
+    /// (the inverse of the rotation built by `PlatformGeometry::new`)
+    pub fn set_rotation(&mut self, rotation: DQuat) {
+        let (heading, elevation, bank) = (ENU_TO_NED_DROT.inverse() * rotation).to_euler(EulerRot::ZYX);
+        self.heading_deg = heading.to_degrees();
+        self.elevation_deg = elevation.to_degrees();
+        self.bank_deg = bank.to_degrees();
+    }
+}
+
+impl AntennaBeamState {
+    /// Sets the 3 dB beam widths (x: azimuth, y: elevation)
+    pub fn set_beam_widths_rad(&mut self, widths_rad: DVec2) {
+        self.azimuth_beam_width_deg = widths_rad.x.to_degrees();
+        self.elevation_beam_width_deg = widths_rad.y.to_degrees();
+    }
+}
+
+impl SignalState {
+    #[inline]
+    pub fn wavelength_m(&self) -> f64 {
+        SPEED_OF_LIGHT_M_S / self.carrier_frequency_hz
+    }
+
+    /// Fraction of the time during which Tx transmits
+    #[inline]
+    pub fn duty_cycle(&self) -> f64 {
+        self.pulse_duration_s * self.prf_hz
+    }
+
+    /// Chirp frequency rate
+    #[inline]
+    pub fn chirp_rate_hz_s(&self) -> f64 {
+        self.bandwidth_hz / self.pulse_duration_s
+    }
+
+    /// Rx complex sampling rate, set or derived from the bandwidth
+    #[inline]
+    pub fn rx_sampling_rate_hz(&self) -> f64 {
+        self.sampling_rate_hz.unwrap_or(DEFAULT_OVERSAMPLING * self.bandwidth_hz)
+    }
+
+    /// Resolution of the range sum R_tx + R_rx: c / B
+    #[inline]
+    pub fn range_sum_resolution_m(&self) -> f64 {
+        SPEED_OF_LIGHT_M_S / self.bandwidth_hz
+    }
+
+    /// Slant range resolution of the equivalent monostatic radar: c / 2B
+    #[inline]
+    pub fn range_resolution_m(&self) -> f64 {
+        0.5 * self.range_sum_resolution_m()
+    }
+
+    /// Range sum R_tx + R_rx travelled during a pulse repetition interval: c / PRF
+    #[inline]
+    pub fn unambiguous_range_sum_m(&self) -> f64 {
+        SPEED_OF_LIGHT_M_S / self.prf_hz
+    }
+
+    /// Unambiguous slant range of the equivalent monostatic radar: c / 2·PRF
+    #[inline]
+    pub fn unambiguous_range_m(&self) -> f64 {
+        0.5 * self.unambiguous_range_sum_m()
+    }
+}
+
+impl Default for CarrierState {
+    fn default() -> Self {
+        Self {
+            heading_deg: 0.0,
+            elevation_deg: 0.0,
+            bank_deg: 0.0,
+            height_m: 300.0,
+            position_m: DVec2::ZERO,
+            velocity_m_s: DVec3::new(0.0, 100.0, 0.0), // Heading North
+            lever_arms_m: DVec3::ZERO,
+        }
+    }
+}
+
+impl Default for AntennaState {
+    fn default() -> Self {
+        Self {
+            heading_deg: 90.0,
+            elevation_deg: -60.0,
+            bank_deg: 0.0,
+        }
+    }
+}
+
+impl Default for AntennaBeamState {
+    fn default() -> Self {
+        Self {
+            elevation_beam_width_deg: 18.0,
+            azimuth_beam_width_deg: 22.0,
+            gain_dbi: 18.0,
+            cone_level_db: -3.0
+        }
+    }
+}
+
+impl Default for SignalState {
+    fn default() -> Self {
+        Self {
+            carrier_frequency_hz: 9.65e9, // X-band
+            bandwidth_hz: 150e6,
+            integration_time_s: 1.0,
+            prf_hz: 1500.0,
+            pulse_duration_s: 20e-6,
+            sampling_rate_hz: None
+        }
+    }
+}
+
+impl Default for LinkBudgetState {
+    fn default() -> Self {
+        Self {
+            transmit_power_w: 1000.0,
+            losses_db: 3.0,
+            noise_figure_db: 3.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_signal_figures() {
+        // 20 µs pulses of 150 MHz at 1500 Hz
+        let mut signal = SignalState::default();
+        assert!((signal.duty_cycle() - 0.03).abs() < 1e-15);
+        assert!((signal.chirp_rate_hz_s() - 7.5e12).abs() < 1e-3);
+        assert_eq!(signal.rx_sampling_rate_hz(), 1.2 * 150e6);
+        assert!((signal.range_sum_resolution_m() - 1.998_616).abs() < 1e-6);
+        assert!((signal.range_resolution_m() - 0.999_308).abs() < 1e-6);
+        assert!((signal.unambiguous_range_sum_m() - 199_861.638_7).abs() < 1e-4);
+        assert!((signal.unambiguous_range_m() - 99_930.819_3).abs() < 1e-4);
+        assert!((signal.wavelength_m() - 0.031_066_576).abs() < 1e-9);
+
+        signal.sampling_rate_hz = Some(200e6);
+        assert_eq!(signal.rx_sampling_rate_hz(), 200e6);
+    }
+}
@@ -0,0 +1,198 @@
+//! Carrier trajectories over the acquisition: the carrier state at a time after
+//! the start of the acquisition, from its state at the start.
+
+use bevy::math::{DVec2, DVec3};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::STANDARD_GRAVITY_M_S2, state::CarrierState};
+
+/// Motion of an airborne carrier from its initial state
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trajectory {
+    /// Straight line at the carrier velocity, the attitude being kept
+    #[default]
+    Line,
+    /// Circle at constant height and speed around `center_m` (East, North), through the
+    /// initial carrier position. The carrier follows the circle tangent and banks as in
+    /// a coordinated turn.
+    Loiter {
+        center_m: DVec2,
+        speed_m_s: f64,
+        clockwise: bool,
+    },
+    /// Straight segments from the initial carrier position through the `points_m` waypoints
+    /// (East, North, Up), flown at constant speed, wings level. The last segment is
+    /// extended beyond the last waypoint.
+    Waypoints {
+        points_m: Vec<DVec3>,
+        speed_m_s: f64,
+    },
+}
+
+/// Carrier `state` moving at `velocity_m_s` from `position_m`, its nose along the velocity
+fn along_velocity(state: &CarrierState, position_m: DVec3, velocity_m_s: DVec3, bank_deg: f64) -> CarrierState {
+    let mut state = CarrierState {
+        heading_deg: velocity_m_s.x.atan2(velocity_m_s.y).to_degrees(), // From North towards East
+        elevation_deg: velocity_m_s.z.atan2(velocity_m_s.truncate().length()).to_degrees(),
+        bank_deg,
+        velocity_m_s,
+        ..*state
+    };
+    state.set_position_enu_m(position_m);
+    state
+}
+
+impl Trajectory {
+    /// Carrier state `time_s` after the start of the acquisition, the carrier being in the
+    /// `initial` state at the start
+    pub fn carrier_state(&self, initial: &CarrierState, time_s: f64) -> CarrierState {
+        match self {
+            Self::Line => {
+                let mut state = *initial;
+                state.set_position_enu_m(initial.position_enu_m() + time_s * initial.velocity_m_s);
+                state
+            },
+            Self::Loiter { center_m, speed_m_s, clockwise } => {
+                let offset = initial.position_m - *center_m;
+                let radius = offset.length();
+                if radius == 0.0 {
+                    return *initial;
+                }
+                let sign = if *clockwise { -1.0 } else { 1.0 };
+                let angle = offset.to_angle() + sign * speed_m_s * time_s / radius;
+                let radial = DVec2::from_angle(angle);
+                let velocity = sign * speed_m_s * radial.perp();
+                // Right turns (clockwise seen from above) bank right wing down
+                let bank = -sign * (speed_m_s * speed_m_s / (STANDARD_GRAVITY_M_S2 * radius)).atan();
+                along_velocity(
+                    initial,
+                    (*center_m + radius * radial).extend(initial.height_m),
+                    velocity.extend(0.0),
+                    bank.to_degrees()
+                )
+            },
+            Self::Waypoints { points_m, speed_m_s } => {
+                let mut start = initial.position_enu_m();
+                let mut distance = speed_m_s * time_s;
+                let mut direction = None;
+                for &end in points_m {
+                    let Some(segment) = (end - start).try_normalize() else { continue };
+                    direction = Some(segment);
+                    let length = start.distance(end);
+                    if distance <= length {
+                        break;
+                    }
+                    distance -= length;
+                    start = end;
+                }
+                match direction {
+                    Some(direction) => along_velocity(
+                        initial,
+                        start + distance * direction,
+                        *speed_m_s * direction,
+                        0.0
+                    ),
+                    None => *initial // No waypoint away from the initial position
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn initial() -> CarrierState {
+        CarrierState {
+            heading_deg: 10.0,
+            elevation_deg: 2.0,
+            bank_deg: 1.0,
+            height_m: 1000.0,
+            position_m: DVec2::new(1000.0, 0.0),
+            velocity_m_s: DVec3::new(50.0, 100.0, 5.0),
+            lever_arms_m: DVec3::new(1.0, 0.0, 0.5),
+        }
+    }
+
+    #[test]
+    fn straight_line() {
+        let initial = initial();
+        assert_eq!(Trajectory::Line.carrier_state(&initial, 0.0), initial);
+        let state = Trajectory::Line.carrier_state(&initial, 10.0);
+        assert!(state.position_enu_m().abs_diff_eq(DVec3::new(1500.0, 1000.0, 1050.0), 1e-9));
+        assert_eq!((state.heading_deg, state.elevation_deg, state.bank_deg), (10.0, 2.0, 1.0));
+    }
+
+    #[test]
+    fn loiter_quarter_turns() {
+        // Turns of radius 1 km at 100 m/s around the origin, from the East of the circle
+        let initial = initial();
+        let (radius_m, speed_m_s) = (1000.0, 100.0);
+        let quarter_turn_s = FRAC_PI_2 * radius_m / speed_m_s;
+        let bank_deg = (speed_m_s * speed_m_s / (STANDARD_GRAVITY_M_S2 * radius_m)).atan().to_degrees();
+
+        let left = Trajectory::Loiter { center_m: DVec2::ZERO, speed_m_s, clockwise: false };
+        let start = left.carrier_state(&initial, 0.0);
+        assert!(start.position_enu_m().abs_diff_eq(initial.position_enu_m(), 1e-9));
+        assert!(start.velocity_m_s.abs_diff_eq(DVec3::new(0.0, speed_m_s, 0.0), 1e-9));
+        let state = left.carrier_state(&initial, quarter_turn_s);
+        assert!(state.position_enu_m().abs_diff_eq(DVec3::new(0.0, radius_m, 1000.0), 1e-9));
+        assert!(state.velocity_m_s.abs_diff_eq(DVec3::new(-speed_m_s, 0.0, 0.0), 1e-9));
+        assert!((state.heading_deg + 90.0).abs() < 1e-9 && state.elevation_deg == 0.0);
+        // Left turn: left wing down
+        assert!((state.bank_deg + bank_deg).abs() < 1e-9, "{} deg", state.bank_deg);
+        assert_eq!(state.lever_arms_m, initial.lever_arms_m);
+
+        let right = Trajectory::Loiter { center_m: DVec2::ZERO, speed_m_s, clockwise: true };
+        let state = right.carrier_state(&initial, quarter_turn_s);
+        assert!(state.position_enu_m().abs_diff_eq(DVec3::new(0.0, -radius_m, 1000.0), 1e-9));
+        assert!(state.velocity_m_s.abs_diff_eq(DVec3::new(-speed_m_s, 0.0, 0.0), 1e-9));
+        assert!((state.bank_deg - bank_deg).abs() < 1e-9, "{} deg", state.bank_deg);
+
+        // Degenerate circle through its center
+        let centered = Trajectory::Loiter { center_m: initial.position_m, speed_m_s, clockwise: false };
+        assert_eq!(centered.carrier_state(&initial, 10.0), initial);
+    }
+
+    #[test]
+    fn waypoint_segments() {
+        // East for 1 km, then North and climbing for 2 km (and 100 m), at 100 m/s
+        let initial = initial();
+        let speed_m_s = 100.0;
+        let points_m = vec![DVec3::new(2000.0, 0.0, 1000.0), DVec3::new(2000.0, 2000.0, 1000.0 + 2000.0 * 0.05)];
+        let second_m = points_m[1] - points_m[0];
+        let trajectory = Trajectory::Waypoints { points_m: points_m.clone(), speed_m_s };
+
+        let state = trajectory.carrier_state(&initial, 5.0);
+        assert!(state.position_enu_m().abs_diff_eq(DVec3::new(1500.0, 0.0, 1000.0), 1e-9));
+        assert!(state.velocity_m_s.abs_diff_eq(DVec3::new(speed_m_s, 0.0, 0.0), 1e-9));
+        assert!((state.heading_deg - 90.0).abs() < 1e-9 && state.bank_deg == 0.0);
+
+        // At the first waypoint, still on the first segment
+        let state = trajectory.carrier_state(&initial, 10.0);
+        assert!(state.position_enu_m().abs_diff_eq(points_m[0], 1e-9));
+        assert!((state.heading_deg - 90.0).abs() < 1e-9);
+
+        let state = trajectory.carrier_state(&initial, 15.0);
+        assert!(state.position_enu_m().abs_diff_eq(points_m[0] + 500.0 * second_m.normalize(), 1e-9));
+        assert!(state.velocity_m_s.abs_diff_eq(speed_m_s * second_m.normalize(), 1e-9));
+        assert!(state.heading_deg.abs() < 1e-9);
+        assert!((state.elevation_deg - 0.05f64.atan().to_degrees()).abs() < 1e-9);
+
+        // Extended 1 km beyond the last waypoint
+        let state = trajectory.carrier_state(&initial, 10.0 + (second_m.length() + 1000.0) / speed_m_s);
+        assert!(state.position_enu_m().abs_diff_eq(points_m[1] + 1000.0 * second_m.normalize(), 1e-9));
+        assert!(state.velocity_m_s.abs_diff_eq(speed_m_s * second_m.normalize(), 1e-9));
+
+        // Waypoints at the initial position are skipped
+        let trajectory = Trajectory::Waypoints { points_m: vec![initial.position_enu_m(), points_m[0]], speed_m_s };
+        let state = trajectory.carrier_state(&initial, 5.0);
+        assert!(state.position_enu_m().abs_diff_eq(DVec3::new(1500.0, 0.0, 1000.0), 1e-9));
+        let trajectory = Trajectory::Waypoints { points_m: vec![initial.position_enu_m()], speed_m_s };
+        assert_eq!(trajectory.carrier_state(&initial, 5.0), initial);
+    }
+}
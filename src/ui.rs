@@ -0,0 +1,23 @@
+/// Top menu bar
+mod menu;
+pub use menu::menu_bar;
+
+/// Acquisition timeline bottom bar
+mod timeline;
+pub use timeline::timeline_panel;
+
+/// Colour maps legends
+mod legend;
+pub use legend::nesz_legend;
+
+/// PRF timing diagram and ambiguity flags
+mod timing;
+pub use timing::timing_diagram;
+
+/// Parameters side panel
+mod panel;
+pub use panel::{
+    keyboard_over_ui,
+    parameters_panel,
+    pointer_over_ui
+};
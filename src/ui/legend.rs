@@ -0,0 +1,50 @@
+use bevy::ecs::system::Res;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::scene::{colormap, NeszMap};
+
+/// Size of the colour bar of the legends
+const COLOR_BAR_SIZE: egui::Vec2 = egui::vec2(240.0, 16.0);
+/// Number of colour steps drawn in a colour bar
+const COLOR_BAR_STEPS: usize = 64;
+
+/// Horizontal colour bar of the colour map, from its low (left) to high (right) values
+fn color_bar(ui: &mut egui::Ui) {
+    let (rect, _) = ui.allocate_exact_size(COLOR_BAR_SIZE, egui::Sense::hover());
+    let step = rect.width() / COLOR_BAR_STEPS as f32;
+    for k in 0..COLOR_BAR_STEPS {
+        let [r, g, b] = colormap((k as f64 + 0.5) / COLOR_BAR_STEPS as f64);
+        let left = rect.left() + k as f32 * step;
+        ui.painter().rect_filled(
+            egui::Rect::from_min_max(egui::pos2(left, rect.top()), egui::pos2(left + step, rect.bottom())),
+            0.0,
+            egui::Color32::from_rgb(r, g, b)
+        );
+    }
+}
+
+/// Legend of the NESZ map colours, shown at the bottom left of the scene with the map
+pub fn nesz_legend(
+    mut contexts: EguiContexts,
+    nesz_map: Res<NeszMap>,
+) {
+    if !nesz_map.visible {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    egui::Window::new("NESZ")
+        .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -10.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            color_bar(ui);
+            ui.horizontal(|ui| {
+                ui.set_width(COLOR_BAR_SIZE.x);
+                ui.label(format!("{:.1} dB", nesz_map.min_db));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(format!("{:.1} dB", nesz_map.max_db));
+                });
+            });
+            ui.small("Transparent above the upper bound");
+        });
+}
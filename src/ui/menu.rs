@@ -0,0 +1,36 @@
+use bevy::ecs::{event::EventWriter, system::ResMut};
+use bevy_egui::{egui, EguiContexts};
+use std::path::PathBuf;
+
+use crate::scene::{ScenarioEvent, ScenarioFile};
+
+/// Top menu bar with the File menu (scenario path, Open and Save)
+pub fn menu_bar(
+    mut contexts: EguiContexts,
+    mut file: ResMut<ScenarioFile>,
+    mut events: EventWriter<ScenarioEvent>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button("File", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Scenario");
+                    ui.text_edit_singleline(&mut file.path);
+                });
+                let path = PathBuf::from(file.path.trim());
+                let has_path = !file.path.trim().is_empty();
+                if ui.add_enabled(has_path, egui::Button::new("Open")).clicked() {
+                    events.send(ScenarioEvent::Open(path.clone()));
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_path, egui::Button::new("Save")).clicked() {
+                    events.send(ScenarioEvent::Save(path));
+                    ui.close_menu();
+                }
+            });
+            ui.separator();
+            ui.label(&file.status);
+        });
+    });
+}
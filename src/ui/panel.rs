@@ -0,0 +1,597 @@
+use bevy::{
+    ecs::{
+        prelude::{DetectChangesMut, Query, With, Without},
+        query::QueryFilter,
+        system::ResMut
+    },
+    math::{DVec2, DVec3},
+    window::PrimaryWindow
+};
+use bevy_egui::{egui, EguiContext, EguiContexts};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    geodesy::{GeodeticPosition, LocalFrame},
+    navigation::Navigation,
+    orbit::{AttitudeMode, Epoch, KeplerianElements, OrbitElements, OrbitState},
+    pattern::AntennaPattern,
+    scene::{IsoDopplerOverlay, IsoRangeOverlay, NeszMap, PlatformStatesMut, TimingDiagram},
+    state::{
+        AntennaBeamState,
+        AntennaPatternState,
+        AntennaState,
+        CarrierNavigation,
+        CarrierOrbit,
+        CarrierState,
+        CarrierTrajectory,
+        EarthModel,
+        InitialCarrierState,
+        LinkBudgetState,
+        Rx,
+        SceneOrigin,
+        SignalState,
+        Tx
+    },
+    trajectory::Trajectory
+};
+
+const PANEL_WIDTH: f32 = 320.0;
+
+/// Run condition: true when the mouse is over an egui area or an egui widget is being dragged,
+/// in which case the mouse should not drive the 3D scene
+pub fn pointer_over_ui(query: Query<&EguiContext, With<PrimaryWindow>>) -> bool {
+    query
+        .get_single()
+        .is_ok_and(|ctx| ctx.get().is_pointer_over_area() || ctx.get().wants_pointer_input())
+}
+
+/// Run condition: true when an egui widget has the keyboard focus
+pub fn keyboard_over_ui(query: Query<&EguiContext, With<PrimaryWindow>>) -> bool {
+    query
+        .get_single()
+        .is_ok_and(|ctx| ctx.get().wants_keyboard_input())
+}
+
+/// Adds a labelled widget on a new row of a grid
+fn row(ui: &mut egui::Ui, label: &str, widget: impl egui::Widget) -> egui::Response {
+    ui.label(label);
+    let response = ui.add(widget);
+    ui.end_row();
+    response
+}
+
+fn angle_slider(value: &mut f64, max_deg: f64) -> egui::Slider<'_> {
+    egui::Slider::new(value, -max_deg..=max_deg).suffix("°")
+}
+
+fn angle_drag(value: &mut f64) -> egui::DragValue<'_> {
+    egui::DragValue::new(value).speed(0.01).max_decimals(4).suffix("°")
+}
+
+/// Edits `value` displayed in `scale` units (e.g. 1e9 for GHz)
+fn scaled_drag(ui: &mut egui::Ui, label: &str, value: &mut f64, scale: f64, suffix: &str) {
+    let mut scaled = *value / scale;
+    if row(ui, label, egui::DragValue::new(&mut scaled).speed(0.1).suffix(suffix)).changed() {
+        *value = scaled * scale;
+    }
+}
+
+fn dvec2_drag(ui: &mut egui::Ui, label: &str, value: &mut DVec2, suffix: &str) {
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut value.x).speed(10.0).suffix(suffix));
+        ui.add(egui::DragValue::new(&mut value.y).speed(10.0).suffix(suffix));
+    });
+    ui.end_row();
+}
+
+fn dvec3_drag(ui: &mut egui::Ui, label: &str, value: &mut DVec3, speed: f64, suffix: &str) {
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut value.x).speed(speed).suffix(suffix));
+        ui.add(egui::DragValue::new(&mut value.y).speed(speed).suffix(suffix));
+        ui.add(egui::DragValue::new(&mut value.z).speed(speed).suffix(suffix));
+    });
+    ui.end_row();
+}
+
+/// Edits a geodetic position, returning true if it has been modified
+fn geodetic_rows(ui: &mut egui::Ui, position: &mut GeodeticPosition) -> bool {
+    let mut changed = row(ui, "Latitude", egui::DragValue::new(&mut position.latitude_deg)
+        .speed(1e-4).range(-90.0..=90.0).max_decimals(7).suffix("°")).changed();
+    changed |= row(ui, "Longitude", egui::DragValue::new(&mut position.longitude_deg)
+        .speed(1e-4).range(-180.0..=180.0).max_decimals(7).suffix("°")).changed();
+    changed |= row(ui, "Ellipsoidal height", egui::DragValue::new(&mut position.height_m)
+        .speed(10.0).suffix(" m")).changed();
+    changed
+}
+
+fn carrier_grid(ui: &mut egui::Ui, id: &str, frame: &LocalFrame, carrier: &mut CarrierState) {
+    egui::Grid::new((id, "carrier")).num_columns(2).show(ui, |ui| {
+        row(ui, "Heading", angle_slider(&mut carrier.heading_deg, 180.0));
+        row(ui, "Elevation", angle_slider(&mut carrier.elevation_deg, 90.0));
+        row(ui, "Bank", angle_slider(&mut carrier.bank_deg, 180.0));
+        row(ui, "Height", egui::DragValue::new(&mut carrier.height_m).speed(10.0).range(0.0..=1e6).suffix(" m"));
+        dvec2_drag(ui, "Position (E, N)", &mut carrier.position_m, " m");
+        // The geodetic position is converted back only when edited, to avoid round-trip drifts
+        let mut geodetic = frame.enu_to_geodetic(carrier.position_enu_m());
+        if geodetic_rows(ui, &mut geodetic) {
+            carrier.set_position_enu_m(frame.geodetic_to_enu(&geodetic));
+        }
+        dvec3_drag(ui, "Velocity (E, N, U)", &mut carrier.velocity_m_s, 1.0, " m/s");
+        dvec3_drag(ui, "Lever arms (N, E, D)", &mut carrier.lever_arms_m, 0.01, " m");
+    });
+}
+
+/// Edits an epoch as ISO 8601 text. The text being typed is kept in the egui memory
+/// until it loses the focus, and the epoch is updated whenever the text is valid.
+fn epoch_row(ui: &mut egui::Ui, label: &str, id: egui::Id, epoch: &mut Epoch) {
+    ui.label(label);
+    let mut text = ui
+        .data_mut(|data| data.get_temp::<String>(id))
+        .unwrap_or_else(|| epoch.to_string());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).id(id));
+    if response.changed() {
+        if let Ok(parsed) = Epoch::parse(&text) {
+            *epoch = parsed;
+        }
+    }
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(id, text));
+    } else {
+        ui.data_mut(|data| data.remove::<String>(id));
+    }
+    ui.end_row();
+}
+
+fn keplerian_rows(ui: &mut egui::Ui, id: &str, elements: &mut KeplerianElements) {
+    scaled_drag(ui, "Semi-major axis", &mut elements.semi_major_axis_m, 1e3, " km");
+    row(ui, "Eccentricity", egui::DragValue::new(&mut elements.eccentricity).speed(1e-4).range(0.0..=0.99).max_decimals(7));
+    row(ui, "Inclination", angle_drag(&mut elements.inclination_deg).range(0.0..=180.0));
+    row(ui, "RAAN", angle_drag(&mut elements.raan_deg));
+    row(ui, "Argument of perigee", angle_drag(&mut elements.argument_of_perigee_deg));
+    row(ui, "Mean anomaly", angle_drag(&mut elements.mean_anomaly_deg));
+    epoch_row(ui, "Elements epoch", egui::Id::new((id, "elements_epoch")), &mut elements.epoch);
+}
+
+/// Orbit of a spaceborne carrier (`None` for an airborne carrier)
+fn orbit_grid(ui: &mut egui::Ui, id: &str, orbit: &mut Option<OrbitState>) {
+    egui::Grid::new((id, "orbit")).num_columns(2).show(ui, |ui| {
+        ui.label("Platform");
+        let mut spaceborne = orbit.is_some();
+        egui::ComboBox::from_id_source((id, "platform"))
+            .selected_text(if spaceborne { "Spaceborne" } else { "Airborne" })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut spaceborne, false, "Airborne");
+                ui.selectable_value(&mut spaceborne, true, "Spaceborne");
+            });
+        ui.end_row();
+        if spaceborne != orbit.is_some() {
+            *orbit = spaceborne.then(OrbitState::default);
+        }
+        let Some(orbit) = orbit else { return };
+
+        ui.label("Elements");
+        let mut tle = matches!(orbit.elements, OrbitElements::Tle { .. });
+        egui::ComboBox::from_id_source((id, "elements"))
+            .selected_text(if tle { "TLE" } else { "Keplerian" })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut tle, false, "Keplerian");
+                ui.selectable_value(&mut tle, true, "TLE");
+            });
+        ui.end_row();
+        if tle != matches!(orbit.elements, OrbitElements::Tle { .. }) {
+            orbit.elements = match tle {
+                true => OrbitElements::Tle { line1: String::new(), line2: String::new() },
+                // A valid TLE is converted to its mean elements
+                false => OrbitElements::Keplerian(orbit.elements.keplerian().unwrap_or_default()),
+            };
+        }
+        match &mut orbit.elements {
+            OrbitElements::Keplerian(elements) => keplerian_rows(ui, id, elements),
+            OrbitElements::Tle { line1, line2 } => {
+                for (label, line) in [("Line 1", line1), ("Line 2", line2)] {
+                    row(ui, label, egui::TextEdit::singleline(line).code_editor().desired_width(f32::INFINITY));
+                }
+            }
+        }
+        epoch_row(ui, "Epoch", egui::Id::new((id, "epoch")), &mut orbit.epoch);
+
+        ui.label("Attitude");
+        let steering = AttitudeMode::ZeroDopplerSteering;
+        let fixed_yaw = AttitudeMode::FixedYaw { yaw_deg: 0.0 };
+        egui::ComboBox::from_id_source((id, "attitude"))
+            .selected_text(match orbit.attitude {
+                AttitudeMode::ZeroDopplerSteering => "Zero-Doppler steering",
+                AttitudeMode::FixedYaw { .. } => "Fixed yaw",
+            })
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(orbit.attitude == steering, "Zero-Doppler steering").clicked() {
+                    orbit.attitude = steering;
+                }
+                if ui.selectable_label(matches!(orbit.attitude, AttitudeMode::FixedYaw { .. }), "Fixed yaw").clicked()
+                    && orbit.attitude == steering
+                {
+                    orbit.attitude = fixed_yaw;
+                }
+            });
+        ui.end_row();
+        if let AttitudeMode::FixedYaw { yaw_deg } = &mut orbit.attitude {
+            row(ui, "Yaw", angle_slider(yaw_deg, 180.0));
+        }
+    });
+    if let Some(Err(err)) = orbit.as_ref().map(OrbitState::orbital_state) {
+        ui.colored_label(egui::Color32::RED, err.to_string());
+    }
+}
+
+/// Trajectory of an airborne carrier from its initial state `carrier`
+fn trajectory_grid(ui: &mut egui::Ui, id: &str, carrier: &CarrierState, trajectory: &mut Trajectory) {
+    egui::Grid::new((id, "trajectory")).num_columns(2).show(ui, |ui| {
+        ui.label("Trajectory");
+        let speed_m_s = carrier.velocity_m_s.length();
+        let mut selected = trajectory.clone();
+        egui::ComboBox::from_id_source((id, "trajectory"))
+            .selected_text(match trajectory {
+                Trajectory::Line => "Line",
+                Trajectory::Loiter { .. } => "Loiter",
+                Trajectory::Waypoints { .. } => "Waypoints",
+            })
+            .show_ui(ui, |ui| {
+                // A new trajectory keeps the carrier speed
+                let choices = [
+                    ("Line", Trajectory::Line),
+                    ("Loiter", Trajectory::Loiter { center_m: carrier.position_m + DVec2::X * 5000.0, speed_m_s, clockwise: true }),
+                    ("Waypoints", Trajectory::Waypoints { points_m: vec![carrier.position_enu_m() + DVec3::Y * 10_000.0], speed_m_s }),
+                ];
+                for (label, choice) in choices {
+                    let current = std::mem::discriminant(trajectory) == std::mem::discriminant(&choice);
+                    if ui.selectable_label(current, label).clicked() && !current {
+                        selected = choice;
+                    }
+                }
+            });
+        ui.end_row();
+        *trajectory = selected;
+
+        match trajectory {
+            Trajectory::Line => {},
+            Trajectory::Loiter { center_m, speed_m_s, clockwise } => {
+                dvec2_drag(ui, "Center (E, N)", center_m, " m");
+                row(ui, "Speed", egui::DragValue::new(speed_m_s).speed(1.0).range(0.0..=1e4).suffix(" m/s"));
+                ui.label("");
+                ui.checkbox(clockwise, "Clockwise");
+                ui.end_row();
+            },
+            Trajectory::Waypoints { points_m, speed_m_s } => {
+                row(ui, "Speed", egui::DragValue::new(speed_m_s).speed(1.0).range(0.0..=1e4).suffix(" m/s"));
+                let mut removed = None;
+                for (k, point) in points_m.iter_mut().enumerate() {
+                    dvec3_drag(ui, &format!("Waypoint {} (E, N, U)", k + 1), point, 10.0, " m");
+                    ui.label("");
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(k);
+                    }
+                    ui.end_row();
+                }
+                if let Some(k) = removed {
+                    points_m.remove(k);
+                }
+                ui.label("");
+                if ui.button("Add waypoint").clicked() {
+                    // Continues the last leg by the same length
+                    let last = points_m.last().copied().unwrap_or(carrier.position_enu_m());
+                    let before = points_m.iter().rev().nth(1).copied().unwrap_or(carrier.position_enu_m());
+                    let leg = if last == before { DVec3::Y * 10_000.0 } else { last - before };
+                    points_m.push(last + leg);
+                }
+                ui.end_row();
+            }
+        }
+    });
+}
+
+/// Navigation log driving an airborne carrier, imported from a CSV file
+fn navigation_grid(ui: &mut egui::Ui, id: &str, navigation: &mut Option<Navigation>) {
+    let path_id = egui::Id::new((id, "navigation_path"));
+    let status_id = egui::Id::new((id, "navigation_status"));
+    let mut remove = false;
+    egui::Grid::new((id, "navigation")).num_columns(2).show(ui, |ui| {
+        ui.label("Navigation file");
+        match navigation {
+            Some(loaded) => {
+                ui.horizontal(|ui| {
+                    ui.label(loaded.path.display().to_string());
+                    remove = ui.small_button("Remove").clicked();
+                });
+                ui.end_row();
+                if let Some((first, last)) = loaded.track.time_span_s() {
+                    ui.label("Records");
+                    ui.label(format!("{} over {:.1} s", loaded.track.records.len(), last - first));
+                    ui.end_row();
+                    let mut start_time_s = loaded.start_time_s();
+                    let start = egui::DragValue::new(&mut start_time_s).speed(0.1).range(first..=last).suffix(" s");
+                    if row(ui, "Acquisition start", start).changed() {
+                        loaded.start_time_s = Some(start_time_s);
+                    }
+                }
+            },
+            None => {
+                let mut path = ui.data_mut(|data| data.get_temp::<String>(path_id)).unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut path);
+                    if ui.add_enabled(!path.trim().is_empty(), egui::Button::new("Import")).clicked() {
+                        let path = PathBuf::from(path.trim());
+                        // Stored absolute, so that scenario files can be saved anywhere
+                        let path = std::path::absolute(&path).unwrap_or(path);
+                        match Navigation::load(path, Path::new("")) {
+                            Ok(loaded) => *navigation = Some(loaded),
+                            Err(err) => ui.data_mut(|data| data.insert_temp(status_id, err.to_string()))
+                        }
+                    }
+                });
+                ui.data_mut(|data| data.insert_temp(path_id, path));
+                ui.end_row();
+            }
+        }
+    });
+    if remove {
+        *navigation = None;
+    }
+    match navigation {
+        Some(_) => ui.data_mut(|data| { data.remove::<String>(status_id); }),
+        None => if let Some(status) = ui.data_mut(|data| data.get_temp::<String>(status_id)) {
+            ui.colored_label(egui::Color32::RED, status);
+        }
+    }
+}
+
+/// Antenna pattern model selection, tabulated cuts and gain grids being imported from a file
+fn pattern_rows(ui: &mut egui::Ui, id: &str, pattern: &mut AntennaPattern) {
+    let path_id = egui::Id::new((id, "pattern_path"));
+    let status_id = egui::Id::new((id, "pattern_status"));
+    ui.label("Pattern");
+    egui::ComboBox::from_id_source((id, "pattern_model"))
+        .selected_text(pattern.name())
+        .show_ui(ui, |ui| {
+            for model in [AntennaPattern::Sinc2, AntennaPattern::Gaussian, AntennaPattern::Cosine] {
+                let name = model.name();
+                ui.selectable_value(pattern, model, name);
+            }
+        });
+    ui.end_row();
+    // The path field stays visible to replace a loaded file, starting from its path
+    ui.label("Pattern file");
+    let mut path = ui
+        .data_mut(|data| data.get_temp::<String>(path_id))
+        .or_else(|| pattern.path().map(|path| path.display().to_string()))
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut path);
+        if ui.add_enabled(!path.trim().is_empty(), egui::Button::new("Import")).clicked() {
+            let path = PathBuf::from(path.trim());
+            // Stored absolute, so that scenario files can be saved anywhere
+            let path = std::path::absolute(&path).unwrap_or(path);
+            match AntennaPattern::import(path, Path::new("")) {
+                Ok(loaded) => {
+                    *pattern = loaded;
+                    ui.data_mut(|data| { data.remove::<String>(status_id); });
+                },
+                Err(err) => ui.data_mut(|data| data.insert_temp(status_id, err.to_string()))
+            }
+        }
+    });
+    ui.data_mut(|data| data.insert_temp(path_id, path));
+    ui.end_row();
+    if let Some(path) = pattern.path() {
+        ui.label("");
+        ui.label(format!("Loaded {}", path.display()));
+        ui.end_row();
+    }
+    if let Some(status) = ui.data_mut(|data| data.get_temp::<String>(status_id)) {
+        ui.label("");
+        ui.colored_label(egui::Color32::RED, status);
+        ui.end_row();
+    }
+}
+
+fn antenna_grid(
+    ui: &mut egui::Ui,
+    id: &str,
+    antenna: &mut AntennaState,
+    beam: &mut AntennaBeamState,
+    pattern: &mut AntennaPattern
+) {
+    egui::Grid::new((id, "antenna")).num_columns(2).show(ui, |ui| {
+        row(ui, "Heading", angle_slider(&mut antenna.heading_deg, 180.0));
+        row(ui, "Elevation", angle_slider(&mut antenna.elevation_deg, 90.0));
+        row(ui, "Bank", angle_slider(&mut antenna.bank_deg, 180.0));
+        pattern_rows(ui, id, pattern);
+        // The beam widths of a pattern file are derived from it
+        let file_widths_rad = pattern.beam_widths_rad();
+        if let Some(widths_rad) = file_widths_rad {
+            beam.set_beam_widths_rad(widths_rad);
+        }
+        let analytic = file_widths_rad.is_none();
+        ui.label("Azimuth beam width");
+        ui.add_enabled(analytic, egui::Slider::new(&mut beam.azimuth_beam_width_deg, 0.1..=170.0).suffix("°"));
+        ui.end_row();
+        ui.label("Elevation beam width");
+        ui.add_enabled(analytic, egui::Slider::new(&mut beam.elevation_beam_width_deg, 0.1..=170.0).suffix("°"));
+        ui.end_row();
+        row(ui, "Gain", egui::DragValue::new(&mut beam.gain_dbi).speed(0.1).range(-20.0..=70.0).suffix(" dBi"));
+        row(ui, "Cone level", egui::Slider::new(&mut beam.cone_level_db, -30.0..=-0.1).suffix(" dB"));
+    });
+}
+
+fn signal_grid(ui: &mut egui::Ui, signal: &mut SignalState) {
+    egui::Grid::new("signal").num_columns(2).show(ui, |ui| {
+        scaled_drag(ui, "Carrier frequency", &mut signal.carrier_frequency_hz, 1e9, " GHz");
+        scaled_drag(ui, "Bandwidth", &mut signal.bandwidth_hz, 1e6, " MHz");
+        row(ui, "Integration time", egui::DragValue::new(&mut signal.integration_time_s).speed(0.01).range(1e-3..=1e3).suffix(" s"));
+        row(ui, "PRF", egui::DragValue::new(&mut signal.prf_hz).speed(10.0).range(1.0..=1e6).suffix(" Hz"));
+        scaled_drag(ui, "Pulse duration", &mut signal.pulse_duration_s, 1e-6, " µs");
+        ui.label("Rx sampling rate");
+        ui.horizontal(|ui| {
+            let mut derived = signal.sampling_rate_hz.is_none();
+            if ui.checkbox(&mut derived, "Auto").changed() {
+                signal.sampling_rate_hz = (!derived).then(|| signal.rx_sampling_rate_hz());
+            }
+            let mut rate_mhz = signal.rx_sampling_rate_hz() / 1e6;
+            if ui.add_enabled(!derived, egui::DragValue::new(&mut rate_mhz).speed(0.1).range(0.1..=1e5).suffix(" MHz")).changed() {
+                signal.sampling_rate_hz = Some(rate_mhz * 1e6);
+            }
+        });
+        ui.end_row();
+        if signal.rx_sampling_rate_hz() < signal.bandwidth_hz {
+            ui.label("");
+            ui.colored_label(egui::Color32::RED, "Sampling rate below the bandwidth");
+            ui.end_row();
+        }
+        for (label, value) in [
+            ("Wavelength", format!("{:.2} cm", 100.0 * signal.wavelength_m())),
+            ("Range resolution", format!("{:.2} m (range sum {:.2} m)", signal.range_resolution_m(), signal.range_sum_resolution_m())),
+            ("Unambiguous range", format!("{:.1} km (range sum {:.1} km)", 1e-3 * signal.unambiguous_range_m(), 1e-3 * signal.unambiguous_range_sum_m())),
+            ("Duty cycle", format!("{:.2} %", 100.0 * signal.duty_cycle())),
+        ] {
+            ui.label(label);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
+}
+
+fn link_budget_grid(ui: &mut egui::Ui, budget: &mut LinkBudgetState) {
+    egui::Grid::new("link_budget").num_columns(2).show(ui, |ui| {
+        row(ui, "Peak power", egui::DragValue::new(&mut budget.transmit_power_w).speed(1.0).range(1e-3..=1e7).suffix(" W"));
+        row(ui, "Losses", egui::DragValue::new(&mut budget.losses_db).speed(0.1).range(0.0..=100.0).suffix(" dB"));
+        row(ui, "Noise figure", egui::DragValue::new(&mut budget.noise_figure_db).speed(0.1).range(0.0..=50.0).suffix(" dB"));
+    });
+}
+
+/// Platform (Carrier, Antenna and Beam) collapsing section.
+/// States are edited on copies and written back only when modified, so that change
+/// detection is not triggered every frame.
+fn platform_section<F: QueryFilter>(
+    ui: &mut egui::Ui,
+    name: &str,
+    frame: &LocalFrame,
+    query: &mut Query<PlatformStatesMut, F>,
+) {
+    let Ok((mut carrier, mut antenna, mut beam, mut pattern, mut orbit, mut trajectory, mut navigation)) = query.get_single_mut() else { return };
+    let (mut edited_carrier, mut edited_antenna, mut edited_beam) = (carrier.0, *antenna, *beam);
+    let mut edited_pattern = pattern.0.clone();
+    let (mut edited_orbit, mut edited_trajectory) = (orbit.0.clone(), trajectory.0.clone());
+    let mut edited_navigation = navigation.0.clone();
+    egui::CollapsingHeader::new(name).default_open(true).show(ui, |ui| {
+        ui.strong("Carrier");
+        orbit_grid(ui, name, &mut edited_orbit);
+        if edited_orbit.is_none() {
+            navigation_grid(ui, name, &mut edited_navigation);
+        }
+        // The position, velocity and attitude of a spaceborne carrier follow its orbit, and
+        // those of a carrier with a navigation log follow the log
+        ui.add_enabled_ui(edited_orbit.is_none() && edited_navigation.is_none(), |ui| {
+            ui.label("At the start of the acquisition:");
+            carrier_grid(ui, name, frame, &mut edited_carrier);
+            trajectory_grid(ui, name, &edited_carrier, &mut edited_trajectory);
+        });
+        ui.strong("Antenna");
+        antenna_grid(ui, name, &mut edited_antenna, &mut edited_beam, &mut edited_pattern);
+    });
+    carrier.set_if_neq(InitialCarrierState(edited_carrier));
+    antenna.set_if_neq(edited_antenna);
+    beam.set_if_neq(edited_beam);
+    pattern.set_if_neq(AntennaPatternState(edited_pattern));
+    orbit.set_if_neq(CarrierOrbit(edited_orbit));
+    trajectory.set_if_neq(CarrierTrajectory(edited_trajectory));
+    navigation.set_if_neq(CarrierNavigation(edited_navigation));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn parameters_panel(
+    mut contexts: EguiContexts,
+    mut query_tx: Query<PlatformStatesMut, (With<Tx>, Without<Rx>)>,
+    mut query_rx: Query<PlatformStatesMut, (With<Rx>, Without<Tx>)>,
+    mut query_signal: Query<(&mut SignalState, &mut LinkBudgetState), With<Tx>>,
+    mut iso_range: ResMut<IsoRangeOverlay>,
+    mut iso_doppler: ResMut<IsoDopplerOverlay>,
+    mut nesz_map: ResMut<NeszMap>,
+    mut timing_diagram: ResMut<TimingDiagram>,
+    mut origin: ResMut<SceneOrigin>,
+    mut earth: ResMut<EarthModel>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    egui::SidePanel::right("parameters")
+        .default_width(PANEL_WIDTH)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut edited_origin = *origin;
+                let mut edited_earth = *earth;
+                egui::CollapsingHeader::new("Earth").default_open(false).show(ui, |ui| {
+                    egui::Grid::new("earth").num_columns(2).show(ui, |ui| {
+                        ui.label("Model");
+                        egui::ComboBox::from_id_source("earth_model")
+                            .selected_text(format!("{:?}", edited_earth))
+                            .show_ui(ui, |ui| {
+                                for model in [EarthModel::Flat, EarthModel::Sphere, EarthModel::Wgs84] {
+                                    ui.selectable_value(&mut edited_earth, model, format!("{:?}", model));
+                                }
+                            });
+                        ui.end_row();
+                        ui.strong("Scene origin");
+                        ui.end_row();
+                        geodetic_rows(ui, &mut edited_origin.0);
+                    });
+                });
+                origin.set_if_neq(edited_origin);
+                earth.set_if_neq(edited_earth);
+
+                let frame = origin.frame();
+                platform_section(ui, "Transmitter", &frame, &mut query_tx);
+                platform_section(ui, "Receiver", &frame, &mut query_rx);
+
+                if let Ok((mut signal, mut budget)) = query_signal.get_single_mut() {
+                    let (mut edited_signal, mut edited_budget) = (*signal, *budget);
+                    egui::CollapsingHeader::new("Waveform (Tx)").default_open(true).show(ui, |ui| {
+                        signal_grid(ui, &mut edited_signal);
+                    });
+                    egui::CollapsingHeader::new("Link budget").default_open(false).show(ui, |ui| {
+                        link_budget_grid(ui, &mut edited_budget);
+                    });
+                    signal.set_if_neq(edited_signal);
+                    budget.set_if_neq(edited_budget);
+                }
+
+                egui::CollapsingHeader::new("Overlays").default_open(true).show(ui, |ui| {
+                    let mut edited_iso_range = *iso_range;
+                    let mut edited_iso_doppler = *iso_doppler;
+                    let mut edited_nesz_map = *nesz_map;
+                    let mut edited_timing_diagram = *timing_diagram;
+                    egui::Grid::new("overlays").num_columns(2).show(ui, |ui| {
+                        ui.checkbox(&mut edited_iso_range.visible, "Iso-range");
+                        ui.add(egui::DragValue::new(&mut edited_iso_range.spacing_m).speed(10.0).range(1.0..=1e5).suffix(" m"));
+                        ui.end_row();
+                        ui.checkbox(&mut edited_iso_doppler.visible, "Iso-Doppler");
+                        ui.add(egui::DragValue::new(&mut edited_iso_doppler.spacing_hz).speed(1.0).range(0.1..=1e5).suffix(" Hz"));
+                        ui.end_row();
+                        ui.checkbox(&mut edited_nesz_map.visible, "NESZ map");
+                        ui.horizontal(|ui| {
+                            let max_db = edited_nesz_map.max_db;
+                            ui.add(egui::DragValue::new(&mut edited_nesz_map.min_db).speed(0.5).range(-100.0..=max_db - 1.0).suffix(" dB"));
+                            ui.label("to");
+                            let min_db = edited_nesz_map.min_db;
+                            ui.add(egui::DragValue::new(&mut edited_nesz_map.max_db).speed(0.5).range(min_db + 1.0..=100.0).suffix(" dB"));
+                        });
+                        ui.end_row();
+                        ui.checkbox(&mut edited_timing_diagram.visible, "Timing diagram");
+                        ui.end_row();
+                    });
+                    iso_range.set_if_neq(edited_iso_range);
+                    iso_doppler.set_if_neq(edited_iso_doppler);
+                    nesz_map.set_if_neq(edited_nesz_map);
+                    timing_diagram.set_if_neq(edited_timing_diagram);
+                });
+            });
+        });
+}
@@ -0,0 +1,37 @@
+use bevy::ecs::{
+    prelude::{DetectChangesMut, Query, With},
+    system::ResMut
+};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    scene::{acquisition_duration_s, Timeline},
+    state::{SignalState, Tx}
+};
+
+/// Bottom timeline bar: play/pause, time scrubber over the acquisition and playback speed
+pub fn timeline_panel(
+    mut contexts: EguiContexts,
+    mut timeline: ResMut<Timeline>,
+    query_signal: Query<&SignalState, With<Tx>>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    let duration_s = acquisition_duration_s(&query_signal);
+    let mut edited = *timeline;
+    egui::TopBottomPanel::bottom("timeline").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            if ui.button(if edited.playing { "⏸" } else { "▶" }).clicked() {
+                edited.playing = !edited.playing;
+            }
+            if ui.button("⏮").clicked() {
+                edited.time_s = 0.0;
+            }
+            ui.label("Time");
+            ui.spacing_mut().slider_width = (ui.available_width() - 200.0).max(100.0);
+            ui.add(egui::Slider::new(&mut edited.time_s, 0.0..=duration_s).suffix(" s"));
+            ui.label("Speed");
+            ui.add(egui::DragValue::new(&mut edited.rate).speed(0.01).range(0.01..=1e3).suffix("×"));
+        });
+    });
+    timeline.set_if_neq(edited);
+}
@@ -0,0 +1,124 @@
+use bevy::ecs::system::Res;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    geometry::AmbiguityCheck,
+    scene::{Ambiguities, TimingDiagram}
+};
+
+/// Pulse repetition intervals shown by the diagram
+const DIAGRAM_PRIS: usize = 3;
+/// Width of the diagram time axis and height of its rows
+const DIAGRAM_WIDTH: f32 = 420.0;
+const ROW_HEIGHT: f32 = 18.0;
+/// Width of the row labels column
+const LABEL_WIDTH: f32 = 90.0;
+/// Largest number of echo windows drawn per row (echoes of earlier pulses)
+const MAX_ECHOES: u64 = 1000;
+
+const TX_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 160, 255);
+const DIRECT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 60);
+const ECHO_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 200, 120);
+const AMBIGUOUS_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 60, 60);
+
+/// Draws a row of intervals (`start`, `end`) in seconds, repeated every PRI over the
+/// diagram span
+fn timing_row(ui: &mut egui::Ui, label: &str, check: &AmbiguityCheck, (start_s, end_s): (f64, f64), color: egui::Color32) {
+    ui.horizontal(|ui| {
+        ui.add_sized([LABEL_WIDTH, ROW_HEIGHT], egui::Label::new(label));
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(DIAGRAM_WIDTH, ROW_HEIGHT), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let span_s = DIAGRAM_PRIS as f64 * check.pri_s;
+        let x = |time_s: f64| rect.left() + (time_s / span_s).clamp(0.0, 1.0) as f32 * rect.width();
+        // Repetitions of earlier pulses reaching the diagram span
+        let first = -((end_s / check.pri_s).ceil() as i64).min(MAX_ECHOES as i64);
+        for k in first..=DIAGRAM_PRIS as i64 {
+            let offset_s = k as f64 * check.pri_s;
+            if offset_s + end_s < 0.0 || offset_s + start_s > span_s {
+                continue;
+            }
+            let (left, right) = (x(offset_s + start_s), x(offset_s + end_s));
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(left, rect.top() + 2.0), egui::pos2(right.max(left + 1.0), rect.bottom() - 2.0)),
+                0.0,
+                color
+            );
+        }
+        for k in 1..DIAGRAM_PRIS {
+            let left = rect.left() + k as f32 / DIAGRAM_PRIS as f32 * rect.width();
+            painter.vline(left, rect.y_range(), ui.visuals().widgets.noninteractive.bg_stroke);
+        }
+    });
+}
+
+/// Status line of an ambiguity flag
+fn flag_label(ui: &mut egui::Ui, ambiguous: bool, text: String) {
+    if ambiguous {
+        ui.colored_label(AMBIGUOUS_COLOR, format!("⚠ {}", text));
+    } else {
+        ui.label(format!("✔ {}", text));
+    }
+}
+
+/// Timing diagram of the Tx pulses, of the direct signal and of the echo window received
+/// by Rx over a few pulse repetition intervals, with the PRF ambiguity flags
+pub fn timing_diagram(
+    mut contexts: EguiContexts,
+    diagram: Res<TimingDiagram>,
+    ambiguities: Res<Ambiguities>,
+) {
+    if !diagram.visible {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    egui::Window::new("Timing diagram")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .collapsible(true)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let Some(check) = &ambiguities.0 else {
+                ui.label("No common Tx/Rx illuminated area");
+                return;
+            };
+            let echo_color = if check.range_ambiguous || check.direct_signal_overlap { AMBIGUOUS_COLOR } else { ECHO_COLOR };
+            timing_row(ui, "Tx pulses", check, (0.0, check.pulse_duration_s), TX_COLOR);
+            timing_row(ui, "Direct signal", check, (check.direct_delay_s, check.direct_delay_s + check.pulse_duration_s), DIRECT_COLOR);
+            timing_row(ui, "Echo window", check, (check.echo_start_s, check.echo_end_s), echo_color);
+            ui.small(format!("{} pulse repetition intervals of {:.1} µs", DIAGRAM_PRIS, 1e6 * check.pri_s));
+            ui.separator();
+
+            ui.label(format!(
+                "Echo window {:.1} to {:.1} µs ({:.1} µs), {} pulses in flight",
+                1e6 * check.echo_start_s,
+                1e6 * check.echo_end_s,
+                1e6 * check.echo_window_s(),
+                check.pulses_in_flight
+            ));
+            ui.label(format!(
+                "Range sum {:.2} to {:.2} km, direct signal at {:.1} µs",
+                1e-3 * check.min_range_sum_m,
+                1e-3 * check.max_range_sum_m,
+                1e6 * check.direct_delay_s
+            ));
+            ui.label(format!(
+                "Doppler {:.1} to {:.1} Hz ({:.1} Hz)",
+                check.min_doppler_hz,
+                check.max_doppler_hz,
+                check.doppler_bandwidth_hz()
+            ));
+            flag_label(ui, check.doppler_aliased, format!(
+                "Doppler bandwidth {} the PRF ({:.1} Hz)",
+                if check.doppler_aliased { "exceeds" } else { "within" },
+                1.0 / check.pri_s
+            ));
+            flag_label(ui, check.range_ambiguous, format!(
+                "Echo window {} the pulse repetition interval",
+                if check.range_ambiguous { "exceeds" } else { "within" }
+            ));
+            flag_label(ui, check.direct_signal_overlap, format!(
+                "Direct signal {} the echo window",
+                if check.direct_signal_overlap { "overlaps" } else { "clear of" }
+            ));
+        });
+}